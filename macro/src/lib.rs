@@ -3,15 +3,16 @@ mod dialect;
 mod operation;
 mod parse;
 mod pass;
+mod pass_definition;
 mod r#type;
 mod utility;
 
 use dialect::DialectInput;
-use parse::{DialectOperationSet, IdentifierList, PassSet};
+use parse::{DialectOperationSet, IdentifierList, PassAttributes, PassSet, TableGenPassSet};
 use proc_macro::TokenStream;
 use quote::quote;
 use std::error::Error;
-use syn::parse_macro_input;
+use syn::{parse_macro_input, ItemStruct};
 
 /// Generates a dialect module from a TableGen file.
 ///
@@ -90,6 +91,64 @@ pub fn passes(stream: TokenStream) -> TokenStream {
     }))
 }
 
+/// Generates pass functions and option types from TableGen files.
+///
+/// The macro generates `create_*` and `register_*` functions for passes
+/// defined in the given files, and option types for passes with options.
+/// Names of passes are derived from the names of their definitions without
+/// given prefixes and suffixes.
+///
+/// The generated items refer to `crate::pass`, so the macro is only for
+/// internal use in pass modules of Melior.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// melior_macro::passes_from_tablegen! {
+///     prefix: "Conversion",
+///     files: ["mlir/Conversion/Passes.td"],
+///     strip_prefixes: ["Convert"],
+///     strip_suffixes: ["ConversionPass", "Pass"],
+/// }
+/// ```
+#[proc_macro]
+pub fn passes_from_tablegen(stream: TokenStream) -> TokenStream {
+    let set = parse_macro_input!(stream as TableGenPassSet);
+
+    convert_result(pass::generate_from_table_gen(&set))
+}
+
+/// Defines a pass written in Rust on a struct implementing `RunExternalPass`.
+///
+/// The macro implements `PassDefinition` and `Default` for the struct. Fields
+/// with `#[option(...)]` attributes are set from textual pass options and
+/// fields of `PassStatistic` with `#[statistic(...)]` attributes are created
+/// with names of the fields. Statistics are attached to passes created from
+/// the struct, so pass managers collect them automatically.
+///
+/// The struct can have lifetime parameters but not type or const ones as its
+/// type ID is shared by all instances.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// #[melior::pass(name = "MyPass", argument = "my-pass", anchor = "func.func")]
+/// #[derive(Clone)]
+/// struct MyPass {
+///     #[option(argument = "max-depth", description = "A maximum depth.", default = 4)]
+///     max_depth: u32,
+///     #[statistic(name = "num-rewrites", description = "Number of rewrites.")]
+///     rewrites: PassStatistic,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn pass(attributes: TokenStream, item: TokenStream) -> TokenStream {
+    let attributes = parse_macro_input!(attributes as PassAttributes);
+    let item = parse_macro_input!(item as ItemStruct);
+
+    convert_result(pass_definition::generate(&attributes, item))
+}
+
 fn convert_result(result: Result<TokenStream, Box<dyn Error>>) -> TokenStream {
     result.unwrap_or_else(|error| {
         let message = error.to_string();
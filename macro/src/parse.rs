@@ -1,7 +1,11 @@
 mod dialect_operation_set;
 mod identifier_list;
+mod pass_attributes;
 mod pass_set;
+mod table_gen_pass_set;
 
 pub use dialect_operation_set::DialectOperationSet;
 pub use identifier_list::IdentifierList;
+pub use pass_attributes::PassAttributes;
 pub use pass_set::PassSet;
+pub use table_gen_pass_set::TableGenPassSet;
@@ -0,0 +1,86 @@
+use proc_macro2::Ident;
+use quote::format_ident;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    LitStr, Result, Token,
+};
+
+pub struct PassAttributes {
+    name: String,
+    argument: String,
+    description: String,
+    anchor: String,
+    dependent_dialects: Vec<Ident>,
+}
+
+impl PassAttributes {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn argument(&self) -> &str {
+        &self.argument
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn anchor(&self) -> &str {
+        &self.anchor
+    }
+
+    pub fn dependent_dialects(&self) -> &[Ident] {
+        &self.dependent_dialects
+    }
+}
+
+impl Parse for PassAttributes {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut name = None;
+        let mut argument = None;
+        let mut description = String::new();
+        let mut anchor = String::new();
+        let mut dependent_dialects = vec![];
+
+        while !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+
+            if ident == format_ident!("name") {
+                name = Some(input.parse::<LitStr>()?.value());
+            } else if ident == format_ident!("argument") {
+                argument = Some(input.parse::<LitStr>()?.value());
+            } else if ident == format_ident!("description") {
+                description = input.parse::<LitStr>()?.value();
+            } else if ident == format_ident!("anchor") {
+                anchor = input.parse::<LitStr>()?.value();
+            } else if ident == format_ident!("dependent_dialects") {
+                let content;
+                bracketed!(content in input);
+                dependent_dialects = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("invalid attribute {ident}"),
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self {
+            name: name.ok_or_else(|| input.error("pass name required"))?,
+            argument: argument.ok_or_else(|| input.error("pass argument required"))?,
+            description,
+            anchor,
+            dependent_dialects,
+        })
+    }
+}
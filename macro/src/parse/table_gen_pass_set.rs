@@ -0,0 +1,166 @@
+use proc_macro2::Ident;
+use quote::format_ident;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    LitStr, Result, Token,
+};
+
+pub struct TableGenPassSet {
+    prefix: String,
+    files: Vec<String>,
+    include_directories: Vec<String>,
+    strip_prefixes: Vec<String>,
+    strip_suffixes: Vec<String>,
+}
+
+impl TableGenPassSet {
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    pub fn include_directories(&self) -> &[String] {
+        &self.include_directories
+    }
+
+    pub fn extract_pass_name(&self, mut name: &str) -> String {
+        for prefix in &self.strip_prefixes {
+            name = name.strip_prefix(prefix.as_str()).unwrap_or(name);
+        }
+
+        for suffix in &self.strip_suffixes {
+            name = name.strip_suffix(suffix.as_str()).unwrap_or(name);
+        }
+
+        name.into()
+    }
+}
+
+impl Parse for TableGenPassSet {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut prefix = None;
+        let mut files = vec![];
+        let mut include_directories = vec![];
+        let mut strip_prefixes = vec![];
+        let mut strip_suffixes = vec![];
+
+        while !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+            input.parse::<Token![:]>()?;
+
+            if ident == format_ident!("prefix") {
+                prefix = Some(input.parse::<LitStr>()?.value());
+            } else if ident == format_ident!("files") {
+                files = parse_strings(input)?;
+            } else if ident == format_ident!("include_directories") {
+                include_directories = parse_strings(input)?;
+            } else if ident == format_ident!("strip_prefixes") {
+                strip_prefixes = parse_strings(input)?;
+            } else if ident == format_ident!("strip_suffixes") {
+                strip_suffixes = parse_strings(input)?;
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("invalid field {ident}"),
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self {
+            prefix: prefix.ok_or_else(|| input.error("pass prefix required"))?,
+            files,
+            include_directories,
+            strip_prefixes,
+            strip_suffixes,
+        })
+    }
+}
+
+fn parse_strings(input: ParseStream) -> Result<Vec<String>> {
+    let content;
+    bracketed!(content in input);
+
+    Ok(Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+        .into_iter()
+        .map(|literal| literal.value())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pass_set() {
+        let set = syn::parse_str::<TableGenPassSet>(
+            r#"
+            prefix: "Conversion",
+            files: ["mlir/Conversion/Passes.td"],
+            include_directories: ["foo", "bar"],
+            strip_prefixes: ["Convert"],
+            strip_suffixes: ["ConversionPass", "Pass"],
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(set.prefix(), "Conversion");
+        assert_eq!(set.files(), ["mlir/Conversion/Passes.td"]);
+        assert_eq!(set.include_directories(), ["foo", "bar"]);
+    }
+
+    #[test]
+    fn parse_pass_set_with_prefix_only() {
+        let set = syn::parse_str::<TableGenPassSet>(r#"prefix: "Transforms""#).unwrap();
+
+        assert_eq!(set.prefix(), "Transforms");
+        assert!(set.files().is_empty());
+        assert!(set.include_directories().is_empty());
+    }
+
+    #[test]
+    fn extract_pass_name() {
+        let set = syn::parse_str::<TableGenPassSet>(
+            r#"
+            prefix: "Conversion",
+            strip_prefixes: ["Convert"],
+            strip_suffixes: ["ConversionPass", "Pass"],
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(set.extract_pass_name("ConvertFuncToLLVMPass"), "FuncToLLVM");
+        assert_eq!(
+            set.extract_pass_name("ConvertArithToLLVMConversionPass"),
+            "ArithToLLVM"
+        );
+        assert_eq!(
+            set.extract_pass_name("SCFToControlFlow"),
+            "SCFToControlFlow"
+        );
+    }
+
+    #[test]
+    fn fail_to_parse_pass_set_without_prefix() {
+        assert!(syn::parse_str::<TableGenPassSet>(r#"files: ["foo.td"]"#).is_err());
+    }
+
+    #[test]
+    fn fail_to_parse_invalid_field() {
+        assert_eq!(
+            syn::parse_str::<TableGenPassSet>(r#"prefix: "Foo", bar: "baz""#)
+                .err()
+                .unwrap()
+                .to_string(),
+            "invalid field bar"
+        );
+    }
+}
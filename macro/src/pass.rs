@@ -1,10 +1,13 @@
+use crate::parse::TableGenPassSet;
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span};
-use quote::quote;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
 use std::error::Error;
+use tblgen::{record::Record, TableGenParser};
 
 const CREATE_FUNCTION_PREFIX: &str = "mlirCreate";
+const LLVM_INCLUDE_DIRECTORY: &str = env!("LLVM_INCLUDE_DIRECTORY");
 
 pub fn generate(
     names: &[Ident],
@@ -17,30 +20,188 @@ pub fn generate(
         let foreign_name = foreign_name.strip_prefix(CREATE_FUNCTION_PREFIX).unwrap();
         let pass_name = extract_pass_name(foreign_name);
 
-        let function_name = create_function_name("create", &pass_name, name.span());
-        let document = format!(" Creates a `{pass_name}` pass.");
+        stream.extend(TokenStream::from(generate_functions(
+            foreign_name,
+            &pass_name,
+            None,
+            name.span(),
+        )));
+    }
+
+    Ok(stream)
+}
+
+pub fn generate_from_table_gen(set: &TableGenPassSet) -> Result<TokenStream, Box<dyn Error>> {
+    let mut parser = TableGenParser::new().add_include_directory(LLVM_INCLUDE_DIRECTORY);
+
+    for directory in set.include_directories() {
+        parser = parser.add_include_directory(directory);
+    }
+
+    let keeper = parser
+        .add_source(&set.files().iter().fold(String::new(), |source, path| {
+            source + "include \"" + path + "\"\n"
+        }))?
+        .parse()?;
+
+    let mut stream = TokenStream2::new();
+
+    for definition in keeper.all_derived_definitions("PassBase") {
+        let foreign_name = format!("{}{}", set.prefix(), definition.name()?);
+        let pass_name = set.extract_pass_name(definition.name()?);
+        let summary = definition.str_value("summary")?;
+
+        stream.extend(generate_functions(
+            &foreign_name,
+            &pass_name,
+            (!summary.is_empty()).then_some(summary),
+            Span::call_site(),
+        ));
+        stream.extend(generate_options(definition, &pass_name)?);
+    }
+
+    Ok(stream.into())
+}
+
+fn generate_functions(
+    foreign_name: &str,
+    pass_name: &str,
+    summary: Option<&str>,
+    span: Span,
+) -> TokenStream2 {
+    let summary = summary
+        .map(|summary| format!("\n\n {}", summary.trim()))
+        .unwrap_or_default();
+
+    // A missing C symbol results in a compile error at the macro call site.
+    let foreign_function_name =
+        Ident::new(&format!("{CREATE_FUNCTION_PREFIX}{foreign_name}"), span);
+    let function_name = create_function_name("create", pass_name, span);
+    let document = format!(" Creates a `{pass_name}` pass.{summary}");
+
+    let mut stream = quote! {
+        #[doc = #document]
+        pub fn #function_name() -> crate::pass::Pass {
+            unsafe { crate::pass::Pass::__private_from_raw_fn(mlir_sys::#foreign_function_name) }
+        }
+    };
+
+    let foreign_function_name = Ident::new(&format!("mlirRegister{foreign_name}"), span);
+    let function_name = create_function_name("register", pass_name, span);
+    let document = format!(" Registers a `{pass_name}` pass.{summary}");
+
+    stream.extend(quote! {
+        #[doc = #document]
+        pub fn #function_name() {
+            unsafe { mlir_sys::#foreign_function_name() }
+        }
+    });
 
-        stream.extend(TokenStream::from(quote! {
-            #[doc = #document]
-            pub fn #function_name() -> crate::pass::Pass {
-                unsafe { crate::pass::Pass::__private_from_raw_fn(mlir_sys::#name) }
+    stream
+}
+
+fn generate_options(definition: Record, pass_name: &str) -> Result<TokenStream2, Box<dyn Error>> {
+    let options = definition.list_value("options")?;
+
+    if options.is_empty() {
+        return Ok(quote!());
+    }
+
+    let name = format_ident!("{}Options", pass_name.to_case(Case::Pascal));
+    let argument = definition.str_value("argument")?;
+    let document = format!(" Options of a `{pass_name}` pass.");
+    let mut fields = vec![];
+    let mut types = vec![];
+    let mut keys = vec![];
+    let mut documents = vec![];
+
+    for option in options.iter() {
+        let option = Record::try_from(option)?;
+        let key = option.str_value("argument")?;
+        let r#type = generate_option_type(option.str_value("type")?);
+        let description = option.str_value("description")?.trim();
+
+        fields.push(sanitize_identifier(&key.to_case(Case::Snake)));
+        types.push(if option.subclass_of("ListOption") {
+            quote!(Vec<#r#type>)
+        } else {
+            r#type
+        });
+        keys.push(key);
+        documents.push(match option.str_value("defaultValue") {
+            Ok(default) if !default.is_empty() => {
+                format!(" {description}\n\n Defaults to `{default}`.")
             }
-        }));
+            _ => format!(" {description}"),
+        });
+    }
 
-        let foreign_function_name =
-            Ident::new(&("mlirRegister".to_owned() + foreign_name), name.span());
-        let function_name = create_function_name("register", &pass_name, name.span());
-        let document = format!(" Registers a `{pass_name}` pass.");
+    Ok(quote! {
+        #[doc = #document]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct #name {
+            #(#fields: Option<#types>,)*
+        }
 
-        stream.extend(TokenStream::from(quote! {
-            #[doc = #document]
-            pub fn #function_name() {
-                unsafe { mlir_sys::#foreign_function_name() }
+        impl #name {
+            /// Creates options with default values.
+            pub fn new() -> Self {
+                Self::default()
             }
-        }));
+
+            #(
+                #[doc = #documents]
+                pub fn #fields(mut self, value: #types) -> Self {
+                    self.#fields = Some(value);
+                    self
+                }
+            )*
+        }
+
+        impl crate::pass::PassOptions for #name {
+            const ARGUMENT: &'static str = #argument;
+        }
+
+        impl std::fmt::Display for #name {
+            #[allow(unused_assignments)]
+            fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                let mut first = true;
+
+                #(
+                    if let Some(value) = &self.#fields {
+                        if !first {
+                            write!(formatter, " ")?;
+                        }
+
+                        first = false;
+                        write!(formatter, "{}=", #keys)?;
+                        crate::pass::PassOptionValue::format(value, formatter)?;
+                    }
+                )*
+
+                Ok(())
+            }
+        }
+    })
+}
+
+fn generate_option_type(r#type: &str) -> TokenStream2 {
+    match r#type.trim_start_matches("::") {
+        "bool" => quote!(bool),
+        "int" | "int32_t" => quote!(i32),
+        "unsigned" | "uint32_t" => quote!(u32),
+        "int64_t" => quote!(i64),
+        "uint64_t" => quote!(u64),
+        "size_t" => quote!(usize),
+        "float" | "double" => quote!(f64),
+        "mlir::GreedySimplifyRegionLevel" => quote!(crate::pass::RegionSimplificationLevel),
+        // Other types, such as enumerations, are passed in their textual forms.
+        _ => quote!(String),
     }
+}
 
-    Ok(stream)
+fn sanitize_identifier(name: &str) -> Ident {
+    syn::parse_str::<Ident>(name).unwrap_or_else(|_| format_ident!("r#{}", name))
 }
 
 fn create_function_name(prefix: &str, pass_name: &str, span: Span) -> Ident {
@@ -0,0 +1,223 @@
+use crate::parse::PassAttributes;
+use convert_case::{Case, Casing};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::error::Error;
+use syn::{
+    meta::ParseNestedMeta, parse_quote, Attribute, Expr, ExprLit, Fields, GenericParam, ItemStruct,
+    Lifetime, LifetimeParam, Lit, LitStr, Meta,
+};
+
+const OPTION_ATTRIBUTE: &str = "option";
+const STATISTIC_ATTRIBUTE: &str = "statistic";
+
+pub fn generate(
+    attributes: &PassAttributes,
+    mut item: ItemStruct,
+) -> Result<TokenStream, Box<dyn Error>> {
+    // A type ID is a static variable shared by all instances of generic types.
+    if let Some(parameter) = item
+        .generics
+        .params
+        .iter()
+        .find(|parameter| !matches!(parameter, GenericParam::Lifetime(_)))
+    {
+        return Err(syn::Error::new_spanned(
+            parameter,
+            "pass cannot have type or const generic parameters",
+        )
+        .into());
+    }
+
+    let Fields::Named(fields) = &mut item.fields else {
+        return Err(
+            syn::Error::new_spanned(&item, "pass must be a struct with named fields").into(),
+        );
+    };
+
+    let argument = attributes.argument();
+    let mut initializers = vec![];
+    let mut option_arms = vec![];
+    let mut option_formats = vec![];
+    let mut statistics = vec![];
+
+    for field in &mut fields.named {
+        let name = field.ident.clone().expect("named field");
+        let r#type = &field.ty;
+
+        if let Some(attribute) = take_attribute(&mut field.attrs, OPTION_ATTRIBUTE) {
+            let mut key = name.to_string().to_case(Case::Kebab);
+            let mut default = None::<Expr>;
+
+            parse_nested_meta(&attribute, |meta| {
+                if meta.path.is_ident("argument") {
+                    key = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("default") {
+                    default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("description") {
+                    let description = meta.value()?.parse::<LitStr>()?;
+                    field.attrs.push(parse_quote!(#[doc = #description]));
+                } else {
+                    return Err(meta.error("invalid option attribute"));
+                }
+
+                Ok(())
+            })?;
+
+            initializers.push(match default {
+                Some(
+                    default @ Expr::Lit(ExprLit {
+                        lit: Lit::Str(_), ..
+                    }),
+                ) => quote!(#name: ::core::convert::Into::into(#default)),
+                Some(default) => quote!(#name: #default),
+                None => quote!(#name: ::core::default::Default::default()),
+            });
+            option_arms.push(quote! {
+                #key => {
+                    self.#name = <#r#type as ::melior::pass::PassOptionValue>::parse(value)
+                        .ok_or_else(|| {
+                            ::melior::Error::ParsePassOption(::std::format!(
+                                "invalid value `{value}` of option `{key}`"
+                            ))
+                        })?;
+
+                    ::core::result::Result::Ok(())
+                }
+            });
+
+            let prefix = format!("{}{key}=", if option_formats.is_empty() { "" } else { " " });
+
+            option_formats.push(quote! {
+                formatter.write_str(#prefix)?;
+                <#r#type as ::melior::pass::PassOptionValue>::format(&self.#name, formatter)?;
+            });
+        } else if let Some(attribute) = take_attribute(&mut field.attrs, STATISTIC_ATTRIBUTE) {
+            let mut statistic = name.to_string();
+            let mut description = String::new();
+
+            parse_nested_meta(&attribute, |meta| {
+                if meta.path.is_ident("name") {
+                    statistic = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("description") {
+                    description = meta.value()?.parse::<LitStr>()?.value();
+                } else {
+                    return Err(meta.error("invalid statistic attribute"));
+                }
+
+                Ok(())
+            })?;
+
+            initializers.push(quote! {
+                #name: ::melior::pass::PassStatistic::new(#argument, #statistic, #description)
+            });
+            statistics.push(quote!(::core::clone::Clone::clone(&self.#name)));
+        } else {
+            initializers.push(quote!(#name: ::core::default::Default::default()));
+        }
+    }
+
+    let ident = &item.ident;
+    let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+
+    // A pass shares a context lifetime with a struct if it has any lifetimes.
+    let mut definition_generics = item.generics.clone();
+    let lifetime = if let Some(lifetime) = item.generics.lifetimes().next() {
+        lifetime.lifetime.clone()
+    } else {
+        let lifetime = Lifetime::new("'c", Span::call_site());
+
+        definition_generics.params.insert(
+            0,
+            GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+        );
+
+        lifetime
+    };
+    let (definition_impl_generics, _, _) = definition_generics.split_for_impl();
+
+    let name = attributes.name();
+    let description = attributes.description();
+    let anchor = attributes.anchor();
+    let dialects = attributes.dependent_dialects();
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics ::core::default::Default for #ident #type_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#initializers,)*
+                }
+            }
+        }
+
+        impl #definition_impl_generics ::melior::pass::PassDefinition<#lifetime>
+            for #ident #type_generics #where_clause
+        {
+            const NAME: &'static str = #name;
+            const ARGUMENT: &'static str = #argument;
+            const DESCRIPTION: &'static str = #description;
+            const ANCHOR: &'static str = #anchor;
+
+            fn type_id() -> ::melior::ir::r#type::TypeId<'static> {
+                #[repr(align(8))]
+                struct PassId;
+
+                static PASS_ID: PassId = PassId;
+
+                ::melior::ir::r#type::TypeId::create(&PASS_ID)
+            }
+
+            fn dependent_dialects() -> ::std::vec::Vec<::melior::dialect::DialectHandle> {
+                ::std::vec![#(::melior::dialect::DialectHandle::#dialects()),*]
+            }
+
+            #[allow(unused_variables)]
+            fn set_option(&mut self, key: &str, value: &str) -> ::core::result::Result<(), ::melior::Error> {
+                match key {
+                    #(#option_arms)*
+                    _ => ::core::result::Result::Err(::melior::Error::ParsePassOption(
+                        ::std::format!("unknown option `{key}` of pass `{}`", #argument),
+                    )),
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn format_options(
+                &self,
+                formatter: &mut ::core::fmt::Formatter,
+            ) -> ::core::fmt::Result {
+                #(#option_formats)*
+
+                ::core::result::Result::Ok(())
+            }
+
+            fn statistics(&self) -> ::std::vec::Vec<::melior::pass::PassStatistic> {
+                ::std::vec![#(#statistics),*]
+            }
+        }
+    }
+    .into())
+}
+
+fn parse_nested_meta(
+    attribute: &Attribute,
+    logic: impl FnMut(ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    // Attributes without arguments, such as `#[statistic]`, are allowed.
+    if matches!(attribute.meta, Meta::Path(_)) {
+        Ok(())
+    } else {
+        attribute.parse_nested_meta(logic)
+    }
+}
+
+fn take_attribute(attributes: &mut Vec<Attribute>, name: &str) -> Option<Attribute> {
+    let index = attributes
+        .iter()
+        .position(|attribute| attribute.path().is_ident(name))?;
+
+    Some(attributes.remove(index))
+}
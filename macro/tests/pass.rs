@@ -0,0 +1,130 @@
+mod utility;
+
+use melior::{
+    ir::{operation::OperationLike, Module, OperationRef},
+    pass::{ExternalPass, PassDefinition, PassManager, PassStatistic, RunExternalPass},
+    ContextRef,
+};
+use pretty_assertions::assert_eq;
+use std::marker::PhantomData;
+use utility::*;
+
+#[melior::pass(
+    name = "CountPass",
+    argument = "macro-count",
+    description = "Counts functions.",
+    anchor = "func.func",
+    dependent_dialects = [func],
+)]
+#[derive(Clone, Debug)]
+struct CountPass {
+    #[option(description = "An increment.", default = 1)]
+    increment: u64,
+    #[option(argument = "label", default = "foo")]
+    name: String,
+    #[statistic(name = "num-functions", description = "Number of functions.")]
+    functions: PassStatistic,
+    runs: u64,
+}
+
+impl<'c> RunExternalPass<'c> for CountPass {
+    fn initialize(&mut self, _context: ContextRef<'c>) {}
+
+    fn run(&mut self, operation: OperationRef<'c, '_>, _pass: ExternalPass<'_>) {
+        assert_eq!(operation.name().as_string_ref().as_str(), Ok("func.func"));
+
+        self.runs += 1;
+        self.functions.add(self.increment);
+    }
+}
+
+#[melior::pass(name = "ContextPass", argument = "macro-context")]
+#[derive(Clone, Debug)]
+struct ContextPass<'c> {
+    _context: PhantomData<ContextRef<'c>>,
+}
+
+impl<'c> RunExternalPass<'c> for ContextPass<'c> {
+    fn initialize(&mut self, _context: ContextRef<'c>) {}
+
+    fn run(&mut self, _operation: OperationRef<'c, '_>, _pass: ExternalPass<'_>) {}
+}
+
+#[test]
+fn constants() {
+    assert_eq!(CountPass::NAME, "CountPass");
+    assert_eq!(CountPass::ARGUMENT, "macro-count");
+    assert_eq!(CountPass::DESCRIPTION, "Counts functions.");
+    assert_eq!(CountPass::ANCHOR, "func.func");
+    assert_eq!(CountPass::dependent_dialects().len(), 1);
+    assert_eq!(ContextPass::ANCHOR, "");
+}
+
+#[test]
+fn type_id() {
+    assert_eq!(CountPass::type_id(), CountPass::type_id());
+    assert_ne!(CountPass::type_id(), ContextPass::type_id());
+}
+
+#[test]
+fn default() {
+    let pass = CountPass::default();
+
+    assert_eq!(pass.increment, 1);
+    assert_eq!(pass.name, "foo");
+    assert_eq!(pass.runs, 0);
+    assert_eq!(pass.functions.pass(), "macro-count");
+    assert_eq!(pass.functions.name(), "num-functions");
+    assert_eq!(pass.functions.description(), "Number of functions.");
+}
+
+#[test]
+fn parse_options() {
+    let mut pass = CountPass::default();
+
+    pass.parse_options("increment=2 label={bar baz}").unwrap();
+
+    assert_eq!(pass.increment, 2);
+    assert_eq!(pass.name, "bar baz");
+    assert!(pass.parse_options("runs=1").is_err());
+}
+
+#[test]
+fn print_options() {
+    let context = create_test_context();
+    let manager = PassManager::new(&context);
+    let mut pass = CountPass::default();
+
+    pass.parse_options("label=bar").unwrap();
+    manager.nested_under("func.func").add_pass(pass.into_pass());
+    manager
+        .nested_under("func.func")
+        .add_pass(ContextPass::default().into_pass());
+
+    assert_eq!(
+        manager.as_operation_pass_manager().to_string(),
+        "any(func.func(macro-count{increment=1 label=bar},macro-context))"
+    );
+}
+
+#[test]
+fn collect_statistics() {
+    let context = create_test_context();
+    let manager = PassManager::new(&context);
+    let mut pass = CountPass::default();
+    let mut module = Module::parse(
+        &context,
+        "func.func @foo() { return }\nfunc.func @bar() { return }",
+    )
+    .unwrap();
+
+    pass.parse_options("increment=3").unwrap();
+    manager.nested_under("func.func").add_pass(pass.into_pass());
+    manager.run(&mut module).unwrap();
+
+    let statistics = manager.statistics();
+
+    assert_eq!(statistics.len(), 1);
+    assert_eq!(statistics[0].name(), "num-functions");
+    assert_eq!(statistics[0].value(), 6);
+}
@@ -1,6 +1,7 @@
 use crate::{
     diagnostic::{Diagnostic, DiagnosticHandlerId},
     dialect::{Dialect, DialectRegistry},
+    ir::operation::remove_models,
     logical_result::LogicalResult,
     string_ref::StringRef,
 };
@@ -8,7 +9,7 @@ use mlir_sys::{
     mlirContextAppendDialectRegistry, mlirContextAttachDiagnosticHandler, mlirContextCreate,
     mlirContextDestroy, mlirContextDetachDiagnosticHandler, mlirContextEnableMultithreading,
     mlirContextEqual, mlirContextGetAllowUnregisteredDialects, mlirContextGetNumLoadedDialects,
-    mlirContextGetNumRegisteredDialects, mlirContextGetOrLoadDialect,
+    mlirContextGetNumRegisteredDialects, mlirContextGetNumThreads, mlirContextGetOrLoadDialect,
     mlirContextIsRegisteredOperation, mlirContextLoadAllAvailableDialects,
     mlirContextSetAllowUnregisteredDialects, mlirInferTypeOpInterfaceTypeID,
     mlirOperationImplementsInterfaceStatic, MlirContext, MlirDiagnostic, MlirLogicalResult,
@@ -64,6 +65,16 @@ impl Context {
         unsafe { mlirContextEnableMultithreading(self.raw, enabled) }
     }
 
+    /// Returns `true` if multi-threading is enabled with more than one thread.
+    pub fn is_multi_threading_enabled(&self) -> bool {
+        self.thread_count() > 1
+    }
+
+    /// Returns a number of threads, which is 1 if multi-threading is disabled.
+    pub fn thread_count(&self) -> usize {
+        unsafe { mlirContextGetNumThreads(self.raw) as usize }
+    }
+
     /// Returns `true` if unregistered dialects are allowed.
     pub fn allow_unregistered_dialects(&self) -> bool {
         unsafe { mlirContextGetAllowUnregisteredDialects(self.raw) }
@@ -140,6 +151,7 @@ impl Context {
 
 impl Drop for Context {
     fn drop(&mut self) {
+        remove_models(self.raw);
         unsafe { mlirContextDestroy(self.raw) };
     }
 }
@@ -271,6 +283,9 @@ mod tests {
         let context = Context::new();
 
         context.enable_multi_threading(false);
+
+        assert!(!context.is_multi_threading_enabled());
+        assert_eq!(context.thread_count(), 1);
     }
 
     #[test]
@@ -2,9 +2,11 @@
 
 pub mod arith;
 pub mod cf;
+pub mod external;
 pub mod func;
 mod handle;
 pub mod index;
+pub mod irdl;
 pub mod llvm;
 pub mod memref;
 mod registry;
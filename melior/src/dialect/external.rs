@@ -0,0 +1,478 @@
+//! Types and attributes defined in Rust.
+//!
+//! MLIR sees external types and attributes as IRDL ones parametrized by
+//! string attributes of their data in custom syntax, such as
+//! `!frontend.tagged<"42:foo">`. Therefore, printed IR is parsed back into the
+//! same types and attributes in any context where the dialect is loaded, and
+//! Rust parses data from the parameters on demand. Types and attributes are
+//! uniqued by the parameters, so data must be printed in canonical forms.
+//!
+//! The MLIR C API cannot register storage classes or printer and parser hooks
+//! of types and attributes. Therefore, custom syntax without the string
+//! parameters, such as `!frontend.tagged<42:foo>`, is printed and parsed by
+//! [`ExternalDialect`].
+
+use super::irdl::{AttributeDefinition, Constraint, DialectDefinition, TypeDefinition};
+use crate::{
+    context::Context,
+    ir::{
+        attribute::StringAttribute,
+        r#type::{id::Allocator, TypeId},
+        Attribute, Type,
+    },
+    Error,
+};
+use std::{
+    any::{self, Any},
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+const PARAMETER_CONSTRAINT: &str = "#builtin.string";
+
+/// Data of types or attributes defined in Rust.
+pub trait ExternalData: Any + Sized {
+    /// A name of types or attributes in a dialect.
+    const NAME: &'static str;
+
+    /// Prints data in custom syntax.
+    ///
+    /// Data are equal if and only if they are printed the same.
+    fn print(&self, formatter: &mut Formatter) -> fmt::Result;
+
+    /// Parses data in custom syntax.
+    fn parse(source: &str) -> Option<Self>;
+}
+
+/// A definition of a dialect with types and attributes defined in Rust.
+#[derive(Clone, Debug)]
+pub struct ExternalDialectDefinition {
+    definition: DialectDefinition,
+}
+
+impl ExternalDialectDefinition {
+    /// Creates a dialect definition.
+    pub fn new(name: &str) -> Self {
+        Self {
+            definition: DialectDefinition::new(name),
+        }
+    }
+
+    /// Adds a type.
+    pub fn add_type<T: ExternalData>(mut self) -> Self {
+        self.definition = self.definition.add_type(
+            TypeDefinition::new(T::NAME)
+                .add_parameter("data", Constraint::base(PARAMETER_CONSTRAINT)),
+        );
+        self
+    }
+
+    /// Adds an attribute.
+    pub fn add_attribute<T: ExternalData>(mut self) -> Self {
+        self.definition = self.definition.add_attribute(
+            AttributeDefinition::new(T::NAME)
+                .add_parameter("data", Constraint::base(PARAMETER_CONSTRAINT)),
+        );
+        self
+    }
+
+    /// Loads a dialect into a context.
+    pub fn load<'c>(&self, context: &'c Context) -> Result<ExternalDialect<'c>, Error> {
+        self.definition.load(context)?;
+
+        Ok(ExternalDialect {
+            context,
+            name: self.definition.name().into(),
+            type_ids: Default::default(),
+        })
+    }
+}
+
+/// A dialect with types and attributes defined in Rust.
+#[derive(Debug)]
+pub struct ExternalDialect<'c> {
+    context: &'c Context,
+    name: String,
+    type_ids: RefCell<TypeIds>,
+}
+
+impl<'c> ExternalDialect<'c> {
+    /// Returns a name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a type ID of data, which is allocated on their first use.
+    ///
+    /// The type ID identifies a data type in Rust. The MLIR C API cannot
+    /// attach it to types or attributes, which have type IDs of IRDL ones.
+    pub fn type_id<T: ExternalData>(&self) -> TypeId<'_> {
+        self.type_ids.borrow_mut().get::<T>()
+    }
+
+    /// Creates a type.
+    pub fn r#type<T: ExternalData>(&self, data: T) -> Result<Type<'c>, Error> {
+        let source = format!("!{}<{}>", self.qualify::<T>(), self.parameter(&data));
+
+        Type::parse(self.context, &source).ok_or(Error::TypeParse(source))
+    }
+
+    /// Returns data of a type.
+    pub fn type_data<T: ExternalData>(&self, r#type: Type<'c>) -> Result<T, Error> {
+        let source = r#type.to_string();
+
+        self.parse_parameter(&source, '!')
+            .ok_or(Error::TypeExpected(T::NAME, source))
+    }
+
+    /// Prints a type in custom syntax, such as `!frontend.tagged<foo>`.
+    pub fn print_type<T: ExternalData>(&self, r#type: Type<'c>) -> Result<String, Error> {
+        Ok(format!(
+            "!{}<{}>",
+            self.qualify::<T>(),
+            DataDisplay(&self.type_data::<T>(r#type)?)
+        ))
+    }
+
+    /// Parses a type in custom syntax.
+    pub fn parse_type<T: ExternalData>(&self, source: &str) -> Result<Type<'c>, Error> {
+        self.r#type(
+            self.parse_data::<T>(source, '!')
+                .ok_or_else(|| Error::TypeParse(source.into()))?,
+        )
+    }
+
+    /// Creates an attribute.
+    pub fn attribute<T: ExternalData>(&self, data: T) -> Result<Attribute<'c>, Error> {
+        let source = format!("#{}<{}>", self.qualify::<T>(), self.parameter(&data));
+
+        Attribute::parse(self.context, &source).ok_or(Error::AttributeParse(source))
+    }
+
+    /// Returns data of an attribute.
+    pub fn attribute_data<T: ExternalData>(&self, attribute: Attribute<'c>) -> Result<T, Error> {
+        let source = attribute.to_string();
+
+        self.parse_parameter(&source, '#')
+            .ok_or(Error::AttributeExpected(T::NAME, source))
+    }
+
+    /// Prints an attribute in custom syntax, such as `#frontend.tag<42>`.
+    pub fn print_attribute<T: ExternalData>(
+        &self,
+        attribute: Attribute<'c>,
+    ) -> Result<String, Error> {
+        Ok(format!(
+            "#{}<{}>",
+            self.qualify::<T>(),
+            DataDisplay(&self.attribute_data::<T>(attribute)?)
+        ))
+    }
+
+    /// Parses an attribute in custom syntax.
+    pub fn parse_attribute<T: ExternalData>(&self, source: &str) -> Result<Attribute<'c>, Error> {
+        self.attribute(
+            self.parse_data::<T>(source, '#')
+                .ok_or_else(|| Error::AttributeParse(source.into()))?,
+        )
+    }
+
+    fn qualify<T: ExternalData>(&self) -> String {
+        format!("{}.{}", self.name, T::NAME)
+    }
+
+    fn parameter<T: ExternalData>(&self, data: &T) -> StringAttribute<'c> {
+        StringAttribute::new(self.context, &DataDisplay(data).to_string())
+    }
+
+    fn parse_data<T: ExternalData>(&self, source: &str, sigil: char) -> Option<T> {
+        T::parse(self.strip_qualifier::<T>(source, sigil)?)
+    }
+
+    // Parses data from a string parameter printed by MLIR.
+    fn parse_parameter<T: ExternalData>(&self, source: &str, sigil: char) -> Option<T> {
+        T::parse(&unescape(
+            self.strip_qualifier::<T>(source, sigil)?
+                .strip_prefix('"')?
+                .strip_suffix('"')?,
+        )?)
+    }
+
+    fn strip_qualifier<'a, T: ExternalData>(
+        &self,
+        source: &'a str,
+        sigil: char,
+    ) -> Option<&'a str> {
+        source
+            .trim()
+            .strip_prefix(sigil)?
+            .strip_prefix(&self.qualify::<T>())?
+            .strip_prefix('<')?
+            .strip_suffix('>')
+    }
+}
+
+struct DataDisplay<'a, T>(&'a T);
+
+impl<T: ExternalData> Display for DataDisplay<'_, T> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.0.print(formatter)
+    }
+}
+
+// Unescapes a string literal printed by MLIR, which escapes quotes and
+// non-printable characters with hexadecimal digits, such as `\22` for `"`, and
+// backslashes with backslashes.
+fn unescape(source: &str) -> Option<String> {
+    let mut bytes = vec![];
+    let mut characters = source.bytes();
+
+    while let Some(character) = characters.next() {
+        if character != b'\\' {
+            bytes.push(character);
+            continue;
+        }
+
+        match characters.next()? {
+            b'\\' => bytes.push(b'\\'),
+            high => {
+                let digits = [high, characters.next()?];
+
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&digits).ok()?, 16).ok()?);
+            }
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+// Type IDs are dropped before their allocator.
+#[derive(Default)]
+struct TypeIds {
+    ids: HashMap<any::TypeId, TypeId<'static>>,
+    allocator: Allocator,
+}
+
+impl TypeIds {
+    fn get<T: ExternalData>(&mut self) -> TypeId<'static> {
+        let allocator = &mut self.allocator;
+
+        *self
+            .ids
+            .entry(any::TypeId::of::<T>())
+            // The type ID lives as long as the allocator in the same storage.
+            .or_insert_with(|| unsafe { TypeId::from_raw(allocator.allocate_type_id().to_raw()) })
+    }
+}
+
+impl fmt::Debug for TypeIds {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("TypeIds")
+            .field("ids", &self.ids.values())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::create_test_context;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct TaggedValue {
+        tag: u8,
+        name: String,
+    }
+
+    impl ExternalData for TaggedValue {
+        const NAME: &'static str = "tagged";
+
+        fn print(&self, formatter: &mut Formatter) -> fmt::Result {
+            write!(formatter, "{}:{}", self.tag, self.name)
+        }
+
+        fn parse(source: &str) -> Option<Self> {
+            let (tag, name) = source.split_once(':')?;
+
+            Some(Self {
+                tag: tag.parse().ok()?,
+                name: name.into(),
+            })
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct Tag(u8);
+
+    impl ExternalData for Tag {
+        const NAME: &'static str = "tag";
+
+        fn print(&self, formatter: &mut Formatter) -> fmt::Result {
+            write!(formatter, "{}", self.0)
+        }
+
+        fn parse(source: &str) -> Option<Self> {
+            Some(Self(source.parse().ok()?))
+        }
+    }
+
+    fn load(context: &Context) -> ExternalDialect<'_> {
+        ExternalDialectDefinition::new("frontend")
+            .add_type::<TaggedValue>()
+            .add_attribute::<Tag>()
+            .load(context)
+            .unwrap()
+    }
+
+    #[test]
+    fn r#type() {
+        let context = create_test_context();
+        let dialect = load(&context);
+        let value = TaggedValue {
+            tag: 42,
+            name: "foo \"bar\"".into(),
+        };
+
+        let r#type = dialect.r#type(value.clone()).unwrap();
+
+        assert_eq!(r#type, dialect.r#type(value.clone()).unwrap());
+        assert_ne!(
+            r#type,
+            dialect
+                .r#type(TaggedValue {
+                    tag: 7,
+                    name: "foo".into(),
+                })
+                .unwrap()
+        );
+        assert_eq!(dialect.type_data::<TaggedValue>(r#type).unwrap(), value);
+    }
+
+    #[test]
+    fn attribute() {
+        let context = create_test_context();
+        let dialect = load(&context);
+
+        let attribute = dialect.attribute(Tag(42)).unwrap();
+
+        assert_eq!(attribute, dialect.attribute(Tag(42)).unwrap());
+        assert_ne!(attribute, dialect.attribute(Tag(7)).unwrap());
+        assert_eq!(dialect.attribute_data::<Tag>(attribute).unwrap(), Tag(42));
+    }
+
+    #[test]
+    fn print_and_parse_type() {
+        let context = create_test_context();
+        let dialect = load(&context);
+        let r#type = dialect
+            .r#type(TaggedValue {
+                tag: 42,
+                name: "foo".into(),
+            })
+            .unwrap();
+
+        let source = dialect.print_type::<TaggedValue>(r#type).unwrap();
+
+        assert_eq!(source, "!frontend.tagged<42:foo>");
+        assert_eq!(dialect.parse_type::<TaggedValue>(&source).unwrap(), r#type);
+    }
+
+    #[test]
+    fn print_and_parse_attribute() {
+        let context = create_test_context();
+        let dialect = load(&context);
+        let attribute = dialect.attribute(Tag(42)).unwrap();
+
+        let source = dialect.print_attribute::<Tag>(attribute).unwrap();
+
+        assert_eq!(source, "#frontend.tag<42>");
+        assert_eq!(dialect.parse_attribute::<Tag>(&source).unwrap(), attribute);
+        assert!(dialect
+            .parse_attribute::<Tag>("#frontend.tag<foo>")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_printed_type_in_other_context() {
+        let context = create_test_context();
+        let dialect = load(&context);
+        let value = TaggedValue {
+            tag: 42,
+            name: "foo \"bar\" \\ baz".into(),
+        };
+        let source = dialect.r#type(value.clone()).unwrap().to_string();
+
+        let other_context = create_test_context();
+        let other_dialect = load(&other_context);
+        let r#type = Type::parse(&other_context, &source).unwrap();
+
+        assert_eq!(
+            other_dialect.type_data::<TaggedValue>(r#type).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn parse_printed_attribute_in_other_context() {
+        let context = create_test_context();
+        let dialect = load(&context);
+        let source = dialect.attribute(Tag(42)).unwrap().to_string();
+
+        assert_eq!(source, "#frontend.tag<\"42\">");
+
+        let other_context = create_test_context();
+        let other_dialect = load(&other_context);
+        let attribute = Attribute::parse(&other_context, &source).unwrap();
+
+        assert_eq!(
+            other_dialect.attribute_data::<Tag>(attribute).unwrap(),
+            Tag(42)
+        );
+    }
+
+    #[test]
+    fn type_id() {
+        let context = create_test_context();
+        let dialect = load(&context);
+
+        assert_eq!(
+            dialect.type_id::<TaggedValue>(),
+            dialect.type_id::<TaggedValue>()
+        );
+        assert_ne!(dialect.type_id::<TaggedValue>(), dialect.type_id::<Tag>());
+    }
+
+    #[test]
+    fn fail_to_get_data_of_other_type() {
+        let context = create_test_context();
+        let dialect = load(&context);
+
+        assert_eq!(
+            dialect.type_data::<TaggedValue>(Type::index(&context)),
+            Err(Error::TypeExpected("tagged", "index".into()))
+        );
+    }
+
+    #[test]
+    fn fail_to_get_data_of_invalid_parameter() {
+        let context = create_test_context();
+        let dialect = load(&context);
+        let attribute = Attribute::parse(&context, "#frontend.tag<\"foo\">").unwrap();
+
+        assert_eq!(
+            dialect.attribute_data::<Tag>(attribute),
+            Err(Error::AttributeExpected("tag", attribute.to_string()))
+        );
+    }
+
+    #[test]
+    fn fail_to_create_unregistered_type() {
+        let context = create_test_context();
+        let dialect = load(&context);
+
+        assert!(matches!(dialect.r#type(Tag(42)), Err(Error::TypeParse(_))));
+    }
+}
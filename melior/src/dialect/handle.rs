@@ -3,9 +3,9 @@ use crate::{context::Context, dialect::Dialect, string_ref::StringRef};
 use mlir_sys::{
     mlirDialectHandleGetNamespace, mlirDialectHandleInsertDialect, mlirDialectHandleLoadDialect,
     mlirDialectHandleRegisterDialect, mlirGetDialectHandle__async__, mlirGetDialectHandle__cf__,
-    mlirGetDialectHandle__func__, mlirGetDialectHandle__gpu__, mlirGetDialectHandle__linalg__,
-    mlirGetDialectHandle__llvm__, mlirGetDialectHandle__pdl__, mlirGetDialectHandle__quant__,
-    mlirGetDialectHandle__scf__, mlirGetDialectHandle__shape__,
+    mlirGetDialectHandle__func__, mlirGetDialectHandle__gpu__, mlirGetDialectHandle__irdl__,
+    mlirGetDialectHandle__linalg__, mlirGetDialectHandle__llvm__, mlirGetDialectHandle__pdl__,
+    mlirGetDialectHandle__quant__, mlirGetDialectHandle__scf__, mlirGetDialectHandle__shape__,
     mlirGetDialectHandle__sparse_tensor__, mlirGetDialectHandle__tensor__,
     mlirGetDialectHandle__transform__, MlirDialectHandle,
 };
@@ -37,6 +37,11 @@ impl DialectHandle {
         unsafe { Self::from_raw(mlirGetDialectHandle__gpu__()) }
     }
 
+    /// Creates a `irdl` dialect handle.
+    pub fn irdl() -> Self {
+        unsafe { Self::from_raw(mlirGetDialectHandle__irdl__()) }
+    }
+
     /// Creates a `linalg` dialect handle.
     pub fn linalg() -> Self {
         unsafe { Self::from_raw(mlirGetDialectHandle__linalg__()) }
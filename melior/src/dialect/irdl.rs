@@ -0,0 +1,938 @@
+//! `irdl` dialect and dialect definitions loaded at runtime.
+//!
+//! [`DialectDefinition`] describes a dialect with its types, attributes, and
+//! operations in Rust. It renders an IRDL module, loads it into a context, and
+//! returns an [`IrdlDialect`] whose operations can be built with operands,
+//! results, and attributes named after their definitions.
+
+use super::DialectHandle;
+use crate::{
+    context::Context,
+    ir::{
+        attribute::DenseI32ArrayAttribute, operation::OperationBuilder, Attribute, Identifier,
+        Location, Module, Operation, Type, Value,
+    },
+    utility::load_irdl_dialects,
+    Error,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A constraint on types or attributes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Any type or attribute.
+    Any,
+    /// A specific type or attribute in its textual form.
+    Is(String),
+    /// Any type or attribute with a base name, such as `!builtin.integer`.
+    BaseName(String),
+    /// Any type or attribute with a base defined in an IRDL dialect.
+    BaseReference { dialect: String, name: String },
+    /// A type or attribute defined in an IRDL dialect with constrained
+    /// parameters.
+    Parametric {
+        dialect: String,
+        name: String,
+        parameters: Vec<Constraint>,
+    },
+    /// Any of constraints.
+    AnyOf(Vec<Constraint>),
+    /// All of constraints.
+    AllOf(Vec<Constraint>),
+}
+
+impl Constraint {
+    /// Creates a constraint satisfied by anything.
+    pub const fn any() -> Self {
+        Self::Any
+    }
+
+    /// Creates a constraint satisfied by a type.
+    pub fn is_type(r#type: Type) -> Self {
+        Self::Is(r#type.to_string())
+    }
+
+    /// Creates a constraint satisfied by an attribute.
+    pub fn is_attribute(attribute: Attribute) -> Self {
+        Self::Is(attribute.to_string())
+    }
+
+    /// Creates a constraint satisfied by types or attributes with a base name.
+    pub fn base(name: &str) -> Self {
+        Self::BaseName(name.into())
+    }
+
+    /// Creates a constraint satisfied by a type or attribute defined in an IRDL
+    /// dialect.
+    pub fn base_reference(dialect: &str, name: &str) -> Self {
+        Self::BaseReference {
+            dialect: dialect.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Creates a constraint satisfied by a type or attribute defined in an IRDL
+    /// dialect with constrained parameters.
+    pub fn parametric(dialect: &str, name: &str, parameters: &[Self]) -> Self {
+        Self::Parametric {
+            dialect: dialect.into(),
+            name: name.into(),
+            parameters: parameters.to_vec(),
+        }
+    }
+
+    /// Creates a constraint satisfied by any of constraints.
+    pub fn any_of(constraints: &[Self]) -> Self {
+        Self::AnyOf(constraints.to_vec())
+    }
+
+    /// Creates a constraint satisfied by all of constraints.
+    pub fn all_of(constraints: &[Self]) -> Self {
+        Self::AllOf(constraints.to_vec())
+    }
+
+    // Writes constraint operations and returns an SSA value name of the result.
+    fn write(&self, writer: &mut ConstraintWriter<'_, '_>) -> Result<String, fmt::Error> {
+        let operation = match self {
+            Self::Any => "irdl.any".into(),
+            Self::Is(value) => format!("irdl.is {value}"),
+            Self::BaseName(name) => format!("irdl.base {name:?}"),
+            Self::BaseReference { dialect, name } => format!("irdl.base @{dialect}::@{name}"),
+            Self::Parametric {
+                dialect,
+                name,
+                parameters,
+            } => format!(
+                "irdl.parametric @{dialect}::@{name}<{}>",
+                writer.write_all(parameters)?
+            ),
+            Self::AnyOf(constraints) => format!("irdl.any_of({})", writer.write_all(constraints)?),
+            Self::AllOf(constraints) => format!("irdl.all_of({})", writer.write_all(constraints)?),
+        };
+
+        writer.write_operation(&operation)
+    }
+}
+
+struct ConstraintWriter<'a, 'b> {
+    formatter: &'a mut Formatter<'b>,
+    count: usize,
+}
+
+impl<'a, 'b> ConstraintWriter<'a, 'b> {
+    fn new(formatter: &'a mut Formatter<'b>) -> Self {
+        Self {
+            formatter,
+            count: 0,
+        }
+    }
+
+    fn write_all(&mut self, constraints: &[Constraint]) -> Result<String, fmt::Error> {
+        Ok(constraints
+            .iter()
+            .map(|constraint| constraint.write(self))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", "))
+    }
+
+    fn write_operation(&mut self, operation: &str) -> Result<String, fmt::Error> {
+        let name = format!("%{}", self.count);
+        self.count += 1;
+
+        writeln!(self.formatter, "    {name} = {operation}")?;
+
+        Ok(name)
+    }
+
+    fn write_named(
+        &mut self,
+        keyword: &str,
+        values: &[(String, Variadicity, Constraint)],
+    ) -> fmt::Result {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        let mut arguments = vec![];
+
+        for (name, variadicity, constraint) in values {
+            let value = constraint.write(self)?;
+
+            arguments.push(match variadicity {
+                Variadicity::Single => format!("{name}: {value}"),
+                Variadicity::Optional => format!("{name}: optional {value}"),
+                Variadicity::Variadic => format!("{name}: variadic {value}"),
+            });
+        }
+
+        writeln!(self.formatter, "    {keyword}({})", arguments.join(", "))
+    }
+}
+
+/// A variadicity of operands and results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Variadicity {
+    /// Exactly one value.
+    #[default]
+    Single,
+    /// Zero or one value.
+    Optional,
+    /// Any number of values.
+    Variadic,
+}
+
+impl Variadicity {
+    const fn accepts(self, count: usize) -> bool {
+        match self {
+            Self::Single => count == 1,
+            Self::Optional => count <= 1,
+            Self::Variadic => true,
+        }
+    }
+}
+
+/// A type definition.
+#[derive(Clone, Debug)]
+pub struct TypeDefinition {
+    name: String,
+    parameters: Vec<(String, Variadicity, Constraint)>,
+}
+
+impl TypeDefinition {
+    /// Creates a type definition.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            parameters: vec![],
+        }
+    }
+
+    /// Adds a parameter.
+    pub fn add_parameter(mut self, name: &str, constraint: Constraint) -> Self {
+        self.parameters
+            .push((name.into(), Variadicity::Single, constraint));
+        self
+    }
+}
+
+/// An attribute definition.
+#[derive(Clone, Debug)]
+pub struct AttributeDefinition {
+    name: String,
+    parameters: Vec<(String, Variadicity, Constraint)>,
+}
+
+impl AttributeDefinition {
+    /// Creates an attribute definition.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            parameters: vec![],
+        }
+    }
+
+    /// Adds a parameter.
+    pub fn add_parameter(mut self, name: &str, constraint: Constraint) -> Self {
+        self.parameters
+            .push((name.into(), Variadicity::Single, constraint));
+        self
+    }
+}
+
+/// An operation definition.
+#[derive(Clone, Debug)]
+pub struct OperationDefinition {
+    name: String,
+    operands: Vec<(String, Variadicity, Constraint)>,
+    results: Vec<(String, Variadicity, Constraint)>,
+    attributes: Vec<(String, Constraint)>,
+}
+
+impl OperationDefinition {
+    /// Creates an operation definition.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            operands: vec![],
+            results: vec![],
+            attributes: vec![],
+        }
+    }
+
+    /// Adds an operand.
+    pub fn add_operand(self, name: &str, constraint: Constraint) -> Self {
+        self.add_operand_with_variadicity(name, constraint, Variadicity::Single)
+    }
+
+    /// Adds an operand with variadicity.
+    pub fn add_operand_with_variadicity(
+        mut self,
+        name: &str,
+        constraint: Constraint,
+        variadicity: Variadicity,
+    ) -> Self {
+        self.operands.push((name.into(), variadicity, constraint));
+        self
+    }
+
+    /// Adds a result.
+    pub fn add_result(self, name: &str, constraint: Constraint) -> Self {
+        self.add_result_with_variadicity(name, constraint, Variadicity::Single)
+    }
+
+    /// Adds a result with variadicity.
+    pub fn add_result_with_variadicity(
+        mut self,
+        name: &str,
+        constraint: Constraint,
+        variadicity: Variadicity,
+    ) -> Self {
+        self.results.push((name.into(), variadicity, constraint));
+        self
+    }
+
+    /// Adds an attribute.
+    pub fn add_attribute(mut self, name: &str, constraint: Constraint) -> Self {
+        self.attributes.push((name.into(), constraint));
+        self
+    }
+}
+
+/// A dialect definition.
+#[derive(Clone, Debug)]
+pub struct DialectDefinition {
+    name: String,
+    types: Vec<TypeDefinition>,
+    attributes: Vec<AttributeDefinition>,
+    operations: Vec<OperationDefinition>,
+}
+
+impl DialectDefinition {
+    /// Creates a dialect definition.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            types: vec![],
+            attributes: vec![],
+            operations: vec![],
+        }
+    }
+
+    /// Returns a name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Adds a type definition.
+    pub fn add_type(mut self, definition: TypeDefinition) -> Self {
+        self.types.push(definition);
+        self
+    }
+
+    /// Adds an attribute definition.
+    pub fn add_attribute(mut self, definition: AttributeDefinition) -> Self {
+        self.attributes.push(definition);
+        self
+    }
+
+    /// Adds an operation definition.
+    pub fn add_operation(mut self, definition: OperationDefinition) -> Self {
+        self.operations.push(definition);
+        self
+    }
+
+    /// Converts a dialect definition into an IRDL module.
+    pub fn to_module<'c>(&self, context: &'c Context) -> Result<Module<'c>, Error> {
+        DialectHandle::irdl().load_dialect(context);
+
+        let source = self.to_string();
+        let mut messages = vec![];
+        let id = context.attach_diagnostic_handler(|diagnostic| {
+            messages.push(diagnostic.to_string());
+            true
+        });
+        let module = Module::parse(context, &source);
+        context.detach_diagnostic_handler(id);
+
+        module.ok_or_else(|| Error::LoadIrdlDialect(messages.join("\n")))
+    }
+
+    /// Loads a dialect into a context.
+    pub fn load<'c>(&self, context: &'c Context) -> Result<IrdlDialect<'c>, Error> {
+        let module = self.to_module(context)?;
+
+        let mut messages = vec![];
+        let id = context.attach_diagnostic_handler(|diagnostic| {
+            messages.push(diagnostic.to_string());
+            true
+        });
+        let loaded = load_irdl_dialects(&module);
+        context.detach_diagnostic_handler(id);
+
+        if loaded {
+            Ok(IrdlDialect {
+                context,
+                definition: self.clone(),
+            })
+        } else {
+            Err(Error::LoadIrdlDialect(messages.join("\n")))
+        }
+    }
+}
+
+impl Display for DialectDefinition {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(formatter, "irdl.dialect @{} {{", self.name)?;
+
+        for definition in &self.types {
+            writeln!(formatter, "  irdl.type @{} {{", definition.name)?;
+            ConstraintWriter::new(formatter)
+                .write_named("irdl.parameters", &definition.parameters)?;
+            writeln!(formatter, "  }}")?;
+        }
+
+        for definition in &self.attributes {
+            writeln!(formatter, "  irdl.attribute @{} {{", definition.name)?;
+            ConstraintWriter::new(formatter)
+                .write_named("irdl.parameters", &definition.parameters)?;
+            writeln!(formatter, "  }}")?;
+        }
+
+        for definition in &self.operations {
+            writeln!(formatter, "  irdl.operation @{} {{", definition.name)?;
+
+            let mut writer = ConstraintWriter::new(formatter);
+
+            writer.write_named("irdl.operands", &definition.operands)?;
+            writer.write_named("irdl.results", &definition.results)?;
+
+            if !definition.attributes.is_empty() {
+                let mut attributes = vec![];
+
+                for (name, constraint) in &definition.attributes {
+                    attributes.push(format!("{name:?} = {}", constraint.write(&mut writer)?));
+                }
+
+                writeln!(
+                    writer.formatter,
+                    "    irdl.attributes {{{}}}",
+                    attributes.join(", ")
+                )?;
+            }
+
+            writeln!(formatter, "  }}")?;
+        }
+
+        writeln!(formatter, "}}")
+    }
+}
+
+/// A dialect loaded from a dialect definition.
+#[derive(Debug)]
+pub struct IrdlDialect<'c> {
+    context: &'c Context,
+    definition: DialectDefinition,
+}
+
+impl<'c> IrdlDialect<'c> {
+    /// Returns a name.
+    pub fn name(&self) -> &str {
+        self.definition.name()
+    }
+
+    /// Parses a type of the dialect with parameters in a textual form.
+    pub fn r#type(&self, name: &str, parameters: &str) -> Option<Type<'c>> {
+        Type::parse(
+            self.context,
+            &if parameters.is_empty() {
+                format!("!{}.{name}", self.name())
+            } else {
+                format!("!{}.{name}<{parameters}>", self.name())
+            },
+        )
+    }
+
+    /// Parses an attribute of the dialect with parameters in a textual form.
+    pub fn attribute(&self, name: &str, parameters: &str) -> Option<Attribute<'c>> {
+        Attribute::parse(
+            self.context,
+            &if parameters.is_empty() {
+                format!("#{}.{name}", self.name())
+            } else {
+                format!("#{}.{name}<{parameters}>", self.name())
+            },
+        )
+    }
+
+    /// Returns an operation of the dialect.
+    pub fn operation(&self, name: &str) -> Result<IrdlOperation<'c, '_>, Error> {
+        self.definition
+            .operations
+            .iter()
+            .find(|definition| definition.name == name)
+            .map(|definition| IrdlOperation {
+                context: self.context,
+                dialect: self.name(),
+                definition,
+            })
+            .ok_or_else(|| Error::OperationNotFound(format!("{}.{name}", self.name())))
+    }
+}
+
+/// An operation defined in an IRDL dialect.
+#[derive(Clone, Copy, Debug)]
+pub struct IrdlOperation<'c, 'a> {
+    context: &'c Context,
+    dialect: &'a str,
+    definition: &'a OperationDefinition,
+}
+
+impl<'c, 'a> IrdlOperation<'c, 'a> {
+    /// Returns a full name.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.dialect, self.definition.name)
+    }
+
+    /// Creates a builder of an operation with operands, results, and
+    /// attributes named after their definitions.
+    pub fn builder<'b>(&self, location: Location<'c>) -> IrdlOperationBuilder<'c, 'a, 'b> {
+        IrdlOperationBuilder {
+            operation: *self,
+            location,
+            operands: vec![vec![]; self.definition.operands.len()],
+            results: vec![vec![]; self.definition.results.len()],
+            attributes: vec![],
+            error: None,
+        }
+    }
+
+    /// Builds an operation.
+    ///
+    /// Operands and results are given as segments, one for each of their
+    /// definitions in order.
+    pub fn build(
+        &self,
+        operands: &[&[Value<'c, '_>]],
+        results: &[&[Type<'c>]],
+        attributes: &[(&str, Attribute<'c>)],
+        location: Location<'c>,
+    ) -> Result<Operation<'c>, Error> {
+        self.check_segments(&self.definition.operands, operands)?;
+        self.check_segments(&self.definition.results, results)?;
+
+        let mut builder = OperationBuilder::new(&self.name(), location)
+            .add_operands(&operands.concat())
+            .add_results(&results.concat())
+            .add_attributes(
+                &attributes
+                    .iter()
+                    .map(|(name, attribute)| (Identifier::new(self.context, name), *attribute))
+                    .collect::<Vec<_>>(),
+            );
+
+        for (name, definitions, sizes) in [
+            (
+                "operandSegmentSizes",
+                &self.definition.operands,
+                operands
+                    .iter()
+                    .map(|values| values.len())
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "resultSegmentSizes",
+                &self.definition.results,
+                results.iter().map(|types| types.len()).collect(),
+            ),
+        ] {
+            if definitions
+                .iter()
+                .filter(|(_, variadicity, _)| *variadicity != Variadicity::Single)
+                .count()
+                > 1
+            {
+                builder = builder.add_attributes(&[(
+                    Identifier::new(self.context, name),
+                    DenseI32ArrayAttribute::new(
+                        self.context,
+                        &sizes.iter().map(|&size| size as i32).collect::<Vec<_>>(),
+                    )
+                    .into(),
+                )]);
+            }
+        }
+
+        builder.build()
+    }
+
+    fn check_segments<T>(
+        &self,
+        definitions: &[(String, Variadicity, Constraint)],
+        segments: &[&[T]],
+    ) -> Result<(), Error> {
+        if definitions.len() != segments.len() {
+            return Err(Error::SegmentCount {
+                operation: self.name(),
+                expected: definitions.len(),
+                actual: segments.len(),
+            });
+        }
+
+        for ((name, variadicity, _), segment) in definitions.iter().zip(segments) {
+            if !variadicity.accepts(segment.len()) {
+                return Err(Error::SegmentSize {
+                    operation: self.name(),
+                    segment: name.clone(),
+                    size: segment.len(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A builder of an operation defined in an IRDL dialect.
+///
+/// Segments not given are empty, which is valid only for optional and
+/// variadic ones.
+#[derive(Debug)]
+pub struct IrdlOperationBuilder<'c, 'a, 'b> {
+    operation: IrdlOperation<'c, 'a>,
+    location: Location<'c>,
+    operands: Vec<Vec<Value<'c, 'b>>>,
+    results: Vec<Vec<Type<'c>>>,
+    attributes: Vec<(&'a str, Attribute<'c>)>,
+    error: Option<Error>,
+}
+
+impl<'c, 'a, 'b> IrdlOperationBuilder<'c, 'a, 'b> {
+    /// Sets an operand of a segment.
+    pub fn operand(self, name: &str, value: Value<'c, 'b>) -> Self {
+        self.operands(name, &[value])
+    }
+
+    /// Sets operands of a segment.
+    pub fn operands(mut self, name: &str, values: &[Value<'c, 'b>]) -> Self {
+        let definition = self.operation.definition;
+
+        if let Some(index) = self.find_segment(&definition.operands, name) {
+            self.operands[index] = values.to_vec();
+        }
+
+        self
+    }
+
+    /// Sets a result type of a segment.
+    pub fn result(self, name: &str, r#type: Type<'c>) -> Self {
+        self.results(name, &[r#type])
+    }
+
+    /// Sets result types of a segment.
+    pub fn results(mut self, name: &str, types: &[Type<'c>]) -> Self {
+        let definition = self.operation.definition;
+
+        if let Some(index) = self.find_segment(&definition.results, name) {
+            self.results[index] = types.to_vec();
+        }
+
+        self
+    }
+
+    /// Sets an attribute.
+    pub fn attribute(mut self, name: &str, attribute: Attribute<'c>) -> Self {
+        let definition = self.operation.definition;
+
+        match definition
+            .attributes
+            .iter()
+            .find(|(definition, _)| definition == name)
+        {
+            Some((name, _)) => self.attributes.push((name.as_str(), attribute)),
+            None => self.fail(Error::AttributeNotFound(name.into())),
+        }
+
+        self
+    }
+
+    /// Builds an operation.
+    pub fn build(self) -> Result<Operation<'c>, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        self.operation.build(
+            &self.operands.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            &self.results.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            &self.attributes,
+            self.location,
+        )
+    }
+
+    fn find_segment(
+        &mut self,
+        definitions: &[(String, Variadicity, Constraint)],
+        name: &str,
+    ) -> Option<usize> {
+        let index = definitions
+            .iter()
+            .position(|(definition, _, _)| definition == name);
+
+        if index.is_none() {
+            self.fail(Error::SegmentNotFound {
+                operation: self.operation.name(),
+                segment: name.into(),
+            });
+        }
+
+        index
+    }
+
+    fn fail(&mut self, error: Error) {
+        self.error.get_or_insert(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dialect::func,
+        ir::{
+            attribute::{StringAttribute, TypeAttribute},
+            operation::OperationLike,
+            r#type::{FunctionType, IntegerType},
+            Block, BlockLike, Region, RegionLike, ValueLike,
+        },
+        test::create_test_context,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn cmath() -> DialectDefinition {
+        DialectDefinition::new("cmath")
+            .add_type(TypeDefinition::new("complex").add_parameter(
+                "element",
+                Constraint::any_of(&[Constraint::Is("f32".into()), Constraint::Is("f64".into())]),
+            ))
+            .add_operation(
+                OperationDefinition::new("norm")
+                    .add_operand(
+                        "complex",
+                        Constraint::parametric("cmath", "complex", &[Constraint::any()]),
+                    )
+                    .add_result("result", Constraint::any()),
+            )
+            .add_operation(
+                OperationDefinition::new("sum")
+                    .add_operand_with_variadicity(
+                        "values",
+                        Constraint::base("!builtin.integer"),
+                        Variadicity::Variadic,
+                    )
+                    .add_result("result", Constraint::base("!builtin.integer"))
+                    .add_attribute("tag", Constraint::base("#builtin.string")),
+            )
+    }
+
+    #[test]
+    fn display() {
+        insta::assert_snapshot!(cmath().to_string());
+    }
+
+    #[test]
+    fn to_module() {
+        let context = create_test_context();
+        let module = cmath().to_module(&context).unwrap();
+
+        assert!(module.as_operation().verify());
+    }
+
+    #[test]
+    fn load() {
+        let context = create_test_context();
+        let dialect = cmath().load(&context).unwrap();
+
+        assert_eq!(dialect.name(), "cmath");
+        assert!(dialect.r#type("complex", "f32").is_some());
+        assert!(context.is_registered_operation("cmath.norm"));
+    }
+
+    #[test]
+    fn build_operation() {
+        let context = create_test_context();
+        let dialect = cmath().load(&context).unwrap();
+        let location = Location::unknown(&context);
+        let integer_type = IntegerType::new(&context, 64).into();
+
+        let block = Block::new(&[(integer_type, location), (integer_type, location)]);
+
+        let operation = block.append_operation(
+            dialect
+                .operation("sum")
+                .unwrap()
+                .build(
+                    &[&[
+                        block.argument(0).unwrap().into(),
+                        block.argument(1).unwrap().into(),
+                    ]],
+                    &[&[integer_type]],
+                    &[("tag", StringAttribute::new(&context, "foo").into())],
+                    location,
+                )
+                .unwrap(),
+        );
+
+        block.append_operation(func::r#return(
+            &[operation.result(0).unwrap().into()],
+            location,
+        ));
+
+        let region = Region::new();
+        region.append_block(block);
+
+        let function = func::func(
+            &context,
+            StringAttribute::new(&context, "sum"),
+            TypeAttribute::new(
+                FunctionType::new(&context, &[integer_type, integer_type], &[integer_type]).into(),
+            ),
+            region,
+            &[],
+            location,
+        );
+
+        assert!(function.verify());
+    }
+
+    #[test]
+    fn build_operation_with_builder() {
+        let context = create_test_context();
+        let dialect = cmath().load(&context).unwrap();
+        let location = Location::unknown(&context);
+        let integer_type = IntegerType::new(&context, 64).into();
+        let block = Block::new(&[(integer_type, location), (integer_type, location)]);
+
+        let operation = dialect
+            .operation("sum")
+            .unwrap()
+            .builder(location)
+            .operands(
+                "values",
+                &[
+                    block.argument(0).unwrap().into(),
+                    block.argument(1).unwrap().into(),
+                ],
+            )
+            .result("result", integer_type)
+            .attribute("tag", StringAttribute::new(&context, "foo").into())
+            .build()
+            .unwrap();
+
+        assert_eq!(operation.operand_count(), 2);
+        assert_eq!(operation.result(0).unwrap().r#type(), integer_type);
+        assert!(operation.attribute("tag").is_ok());
+    }
+
+    #[test]
+    fn fail_to_build_operation_with_unknown_names() {
+        let context = create_test_context();
+        let dialect = cmath().load(&context).unwrap();
+        let location = Location::unknown(&context);
+        let operation = dialect.operation("sum").unwrap();
+
+        assert_eq!(
+            operation
+                .builder(location)
+                .results("foo", &[])
+                .build()
+                .unwrap_err(),
+            Error::SegmentNotFound {
+                operation: "cmath.sum".into(),
+                segment: "foo".into(),
+            }
+        );
+        assert_eq!(
+            operation
+                .builder(location)
+                .attribute("foo", StringAttribute::new(&context, "foo").into())
+                .build()
+                .unwrap_err(),
+            Error::AttributeNotFound("foo".into())
+        );
+    }
+
+    #[test]
+    fn fail_to_build_operation_with_missing_segment() {
+        let context = create_test_context();
+        let dialect = cmath().load(&context).unwrap();
+        let location = Location::unknown(&context);
+
+        assert_eq!(
+            dialect
+                .operation("norm")
+                .unwrap()
+                .builder(location)
+                .result("result", Type::float32(&context))
+                .build()
+                .unwrap_err(),
+            Error::SegmentSize {
+                operation: "cmath.norm".into(),
+                segment: "complex".into(),
+                size: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn fail_to_find_operation() {
+        let context = create_test_context();
+        let dialect = cmath().load(&context).unwrap();
+
+        assert_eq!(
+            dialect.operation("foo").unwrap_err(),
+            Error::OperationNotFound("cmath.foo".into())
+        );
+    }
+
+    #[test]
+    fn fail_to_build_operation_with_invalid_segments() {
+        let context = create_test_context();
+        let dialect = cmath().load(&context).unwrap();
+        let location = Location::unknown(&context);
+        let operation = dialect.operation("norm").unwrap();
+
+        assert_eq!(
+            operation.build(&[], &[], &[], location).unwrap_err(),
+            Error::SegmentCount {
+                operation: "cmath.norm".into(),
+                expected: 1,
+                actual: 0,
+            }
+        );
+        assert_eq!(
+            operation
+                .build(&[&[]], &[&[Type::float32(&context)]], &[], location)
+                .unwrap_err(),
+            Error::SegmentSize {
+                operation: "cmath.norm".into(),
+                segment: "complex".into(),
+                size: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn fail_to_load() {
+        let context = create_test_context();
+
+        assert!(matches!(
+            DialectDefinition::new("foo")
+                .add_operation(
+                    OperationDefinition::new("bar")
+                        .add_operand("value", Constraint::base_reference("foo", "baz"))
+                )
+                .load(&context),
+            Err(Error::LoadIrdlDialect(_))
+        ));
+    }
+}
@@ -8,34 +8,99 @@ use std::{
 /// A Melior error.
 #[derive(Debug, Eq, PartialEq)]
 pub enum Error {
+    AddAnalysisManager(&'static str),
+    ApplyConversion(String),
+    ApplyPatterns,
     AttributeExpected(&'static str, String),
     AttributeNotFound(String),
     AttributeParse(String),
     BlockArgumentExpected(String),
+    CreateExecutionEngine(String),
+    DumpObjectFile(String),
     ElementExpected {
         r#type: &'static str,
         value: String,
     },
+    GreedyRewriteConfig(&'static str),
+    InferReturnTypes(String),
+    InsertionPointNotSet,
+    Interpret {
+        operation: String,
+        location: String,
+        message: String,
+    },
     InvokeFunction,
+    LoadIrdlDialect(String),
+    MemRefShape {
+        expected: usize,
+        actual: usize,
+    },
+    MemRefSize(Vec<usize>),
+    ModuleParse,
     OperationBuild,
     OperandNotFound(&'static str),
+    OperationNotFound(String),
     OperationResultExpected(String),
     PositionOutOfBounds {
         name: &'static str,
         value: String,
         index: usize,
     },
+    ParsePassOption(String),
     ParsePassPipeline(String),
+    PassAnchorMismatch {
+        expected: String,
+        actual: String,
+    },
+    PassInstrumentation(String),
+    Reproducer(String),
+    ResultCount {
+        operation: String,
+        expected: usize,
+        actual: usize,
+    },
     ResultNotFound(&'static str),
     RunPass,
+    SymbolNotFound(String),
+    SegmentCount {
+        operation: String,
+        expected: usize,
+        actual: usize,
+    },
+    SegmentNotFound {
+        operation: String,
+        segment: String,
+    },
+    SegmentSize {
+        operation: String,
+        segment: String,
+        size: usize,
+    },
+    SignatureMismatch {
+        function: String,
+        expected: String,
+        actual: String,
+    },
     TypeExpected(&'static str, String),
+    TypeParse(String),
     UnknownDiagnosticSeverity(u32),
+    UnsupportedOperation {
+        operation: String,
+        location: String,
+    },
     Utf8(Utf8Error),
 }
 
 impl Display for Error {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         match self {
+            Self::AddAnalysisManager(reason) => {
+                write!(formatter, "failed to add analysis manager: {reason}")
+            }
+            Self::ApplyConversion(name) => {
+                write!(formatter, "failed to legalize operation {name}")
+            }
+            Self::ApplyPatterns => write!(formatter, "failed to apply patterns"),
             Self::AttributeExpected(r#type, attribute) => {
                 write!(formatter, "{type} attribute expected: {attribute}")
             }
@@ -48,35 +113,152 @@ impl Display for Error {
             Self::BlockArgumentExpected(value) => {
                 write!(formatter, "block argument expected: {value}")
             }
+            Self::CreateExecutionEngine(message) => {
+                write!(formatter, "failed to create execution engine:\n{message}")
+            }
+            Self::DumpObjectFile(path) => {
+                write!(formatter, "failed to dump object file: {path}")
+            }
             Self::ElementExpected { r#type, value } => {
                 write!(formatter, "element of {type} type expected: {value}")
             }
+            Self::GreedyRewriteConfig(reason) => {
+                write!(
+                    formatter,
+                    "unsupported greedy rewrite configuration: {reason}"
+                )
+            }
+            Self::InferReturnTypes(name) => {
+                write!(
+                    formatter,
+                    "failed to infer return types of operation {name}"
+                )
+            }
+            Self::InsertionPointNotSet => write!(formatter, "insertion point not set"),
+            Self::Interpret {
+                operation,
+                location,
+                message,
+            } => {
+                write!(
+                    formatter,
+                    "failed to interpret operation {operation} at {location}: {message}"
+                )
+            }
             Self::InvokeFunction => write!(formatter, "failed to invoke JIT-compiled function"),
+            Self::LoadIrdlDialect(message) => {
+                write!(formatter, "failed to load IRDL dialect:\n{message}")
+            }
+            Self::MemRefShape { expected, actual } => {
+                write!(
+                    formatter,
+                    "memref of {expected} elements expected but got {actual} elements"
+                )
+            }
+            Self::MemRefSize(sizes) => {
+                write!(
+                    formatter,
+                    "number of elements of memref of sizes {sizes:?} overflows"
+                )
+            }
+            Self::ModuleParse => write!(formatter, "failed to parse module"),
             Self::OperationBuild => {
                 write!(formatter, "operation build failed")
             }
             Self::OperandNotFound(name) => {
                 write!(formatter, "operand {name} not found")
             }
+            Self::OperationNotFound(name) => {
+                write!(formatter, "operation {name} not found")
+            }
             Self::OperationResultExpected(value) => {
                 write!(formatter, "operation result expected: {value}")
             }
+            Self::ParsePassOption(message) => {
+                write!(formatter, "failed to parse pass option: {message}")
+            }
             Self::ParsePassPipeline(message) => {
                 write!(formatter, "failed to parse pass pipeline:\n{message}")
             }
+            Self::PassAnchorMismatch { expected, actual } => {
+                write!(
+                    formatter,
+                    "pass manager anchored on {expected} cannot run on {actual}"
+                )
+            }
+            Self::PassInstrumentation(message) => {
+                write!(formatter, "pass instrumentation failed: {message}")
+            }
             Self::PositionOutOfBounds { name, value, index } => {
                 write!(formatter, "{name} position {index} out of bounds: {value}")
             }
+            Self::Reproducer(message) => write!(formatter, "crash reproducer failed: {message}"),
+            Self::ResultCount {
+                operation,
+                expected,
+                actual,
+            } => {
+                write!(
+                    formatter,
+                    "{expected} values expected to replace results of operation {operation} but got {actual}"
+                )
+            }
             Self::ResultNotFound(name) => {
                 write!(formatter, "result {name} not found")
             }
             Self::RunPass => write!(formatter, "failed to run pass"),
+            Self::SymbolNotFound(name) => write!(formatter, "symbol {name} not found"),
+            Self::SegmentCount {
+                operation,
+                expected,
+                actual,
+            } => {
+                write!(
+                    formatter,
+                    "{expected} segments expected for operation {operation} but got {actual}"
+                )
+            }
+            Self::SegmentNotFound { operation, segment } => {
+                write!(
+                    formatter,
+                    "segment {segment} of operation {operation} not found"
+                )
+            }
+            Self::SegmentSize {
+                operation,
+                segment,
+                size,
+            } => {
+                write!(
+                    formatter,
+                    "invalid size {size} of segment {segment} in operation {operation}"
+                )
+            }
+            Self::SignatureMismatch {
+                function,
+                expected,
+                actual,
+            } => {
+                write!(
+                    formatter,
+                    "signature {expected} expected for function {function} but got {actual}"
+                )
+            }
             Self::TypeExpected(r#type, actual) => {
                 write!(formatter, "{type} type expected: {actual}")
             }
+            Self::TypeParse(string) => {
+                write!(formatter, "failed to parse type: {string}")
+            }
             Self::UnknownDiagnosticSeverity(severity) => {
                 write!(formatter, "unknown diagnostic severity: {severity}")
             }
+            Self::UnsupportedOperation {
+                operation,
+                location,
+            } => {
+                write!(formatter, "unsupported operation {operation} at {location}")
+            }
             Self::Utf8(error) => {
                 write!(formatter, "{error}")
             }
@@ -1,43 +1,93 @@
-use crate::{ir::Module, logical_result::LogicalResult, string_ref::StringRef, Error};
+//! Execution engines.
+
+mod mem_ref;
+mod native_type;
+
+pub use self::{
+    mem_ref::{OwnedMemRef, ReturnedMemRef, StridedMemRef, UnrankedMemRef},
+    native_type::{NativeArguments, NativeFunction, NativeResult, NativeType},
+};
+use crate::{
+    ir::{
+        attribute::{StringAttribute, TypeAttribute},
+        block::BlockLike,
+        operation::OperationLike,
+        r#type::FunctionType,
+        Module, OperationRef, Type,
+    },
+    logical_result::LogicalResult,
+    string_ref::StringRef,
+    Error,
+};
 use mlir_sys::{
     mlirExecutionEngineCreate, mlirExecutionEngineDestroy, mlirExecutionEngineDumpToObjectFile,
     mlirExecutionEngineInvokePacked, mlirExecutionEngineLookup, mlirExecutionEngineRegisterSymbol,
-    MlirExecutionEngine,
+    mlirLLVMFunctionTypeGetInput, mlirLLVMFunctionTypeGetNumInputs,
+    mlirLLVMFunctionTypeGetReturnType, MlirExecutionEngine,
 };
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    mem::MaybeUninit,
+    ptr::NonNull,
+};
+
+const LLVM_VOID_TYPE: &str = "!llvm.void";
 
 /// An execution engine.
 pub struct ExecutionEngine {
     raw: MlirExecutionEngine,
+    signatures: HashMap<String, Signature>,
 }
 
 impl ExecutionEngine {
     /// Creates an execution engine.
+    ///
+    /// If a module fails to be translated or compiled, it returns an error
+    /// with diagnostics emitted during its creation.
     pub fn new(
         module: &Module,
         optimization_level: usize,
         shared_library_paths: &[&str],
         enable_object_dump: bool,
-    ) -> Self {
-        Self {
-            raw: unsafe {
-                mlirExecutionEngineCreate(
-                    module.to_raw(),
-                    optimization_level as i32,
-                    shared_library_paths.len() as i32,
-                    shared_library_paths
-                        .iter()
-                        .map(|&string| StringRef::new(string).to_raw())
-                        .collect::<Vec<_>>()
-                        .as_ptr(),
-                    enable_object_dump,
-                )
-            },
+    ) -> Result<Self, Error> {
+        let context = module.context();
+        let context = unsafe { context.to_ref() };
+        let mut messages = vec![];
+        let id = context.attach_diagnostic_handler(|diagnostic| {
+            messages.push(diagnostic.to_string());
+            true
+        });
+        let raw = unsafe {
+            mlirExecutionEngineCreate(
+                module.to_raw(),
+                optimization_level as i32,
+                shared_library_paths.len() as i32,
+                shared_library_paths
+                    .iter()
+                    .map(|&string| StringRef::new(string).to_raw())
+                    .collect::<Vec<_>>()
+                    .as_ptr(),
+                enable_object_dump,
+            )
+        };
+        context.detach_diagnostic_handler(id);
+
+        if raw.ptr.is_null() {
+            Err(Error::CreateExecutionEngine(messages.join("\n")))
+        } else {
+            Ok(Self {
+                raw,
+                signatures: collect_signatures(module),
+            })
         }
     }
 
     /// Searches a symbol in a module and returns a pointer to it.
-    pub fn lookup(&self, name: &str) -> *mut () {
-        unsafe { mlirExecutionEngineLookup(self.raw, StringRef::new(name).to_raw()) as *mut () }
+    pub fn lookup(&self, name: &str) -> Option<NonNull<()>> {
+        NonNull::new(unsafe {
+            mlirExecutionEngineLookup(self.raw, StringRef::new(name).to_raw()) as *mut ()
+        })
     }
 
     /// Invokes a function in a module. The `arguments` argument includes
@@ -62,6 +112,124 @@ impl ExecutionEngine {
         }
     }
 
+    /// Invokes a function in a module with typed arguments and a result.
+    ///
+    /// Arguments are given as a tuple of native types. Their types and a
+    /// result type are checked against a signature of a `llvm.func` or
+    /// `func.func` operation of the function in a module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use melior::{
+    ///     dialect::DialectRegistry,
+    ///     ir::Module,
+    ///     pipeline,
+    ///     utility::{register_all_dialects, register_all_llvm_translations, register_all_passes},
+    ///     Context, ExecutionEngine,
+    /// };
+    ///
+    /// let registry = DialectRegistry::new();
+    /// register_all_dialects(&registry);
+    ///
+    /// let context = Context::new();
+    /// context.append_dialect_registry(&registry);
+    /// context.load_all_available_dialects();
+    /// register_all_llvm_translations(&context);
+    /// register_all_passes();
+    ///
+    /// let mut module = Module::parse(
+    ///     &context,
+    ///     r#"
+    ///     func.func @scale(%x : i64, %y : f64) -> f64 attributes { llvm.emit_c_interface } {
+    ///         %0 = arith.sitofp %x : i64 to f64
+    ///         %1 = arith.mulf %0, %y : f64
+    ///         return %1 : f64
+    ///     }
+    ///     "#,
+    /// )
+    /// .unwrap();
+    ///
+    /// pipeline::scalar_to_llvm()
+    ///     .create_pass_manager(&context)
+    ///     .unwrap()
+    ///     .run(&mut module)
+    ///     .unwrap();
+    ///
+    /// let engine = ExecutionEngine::new(&module, 2, &[], false).unwrap();
+    ///
+    /// assert_eq!(engine.invoke::<(i64, f64), f64>("scale", (3, 0.5)), Ok(1.5));
+    /// assert!(engine.invoke::<(i64, f64), i64>("scale", (3, 0.5)).is_err());
+    /// ```
+    pub fn invoke<A: NativeArguments, R: NativeResult>(
+        &self,
+        name: &str,
+        mut arguments: A,
+    ) -> Result<R, Error> {
+        self.check_signature(name, A::types(), R::types())?;
+
+        let mut result = MaybeUninit::<R>::uninit();
+        let mut pointers = arguments.pointers();
+
+        if !R::types().is_empty() {
+            pointers.push(result.as_mut_ptr() as *mut ());
+        }
+
+        unsafe {
+            self.invoke_packed(name, &mut pointers)?;
+
+            Ok(result.assume_init())
+        }
+    }
+
+    /// Searches a function in a module and returns it as a native function
+    /// pointer, such as `extern "C" fn(i64) -> i64`.
+    ///
+    /// A function type is checked against a signature of a `llvm.func` or
+    /// `func.func` operation in a module. Wrapper functions of
+    /// `llvm.emit_c_interface` are looked up by their names, such as
+    /// `_mlir_ciface_foo`.
+    ///
+    /// # Safety
+    ///
+    /// A returned function must not be called after an execution engine is
+    /// dropped.
+    pub unsafe fn lookup_fn<F: NativeFunction>(&self, name: &str) -> Result<F, Error> {
+        self.check_signature(name, F::argument_types(), F::result_types())?;
+
+        let pointer = self
+            .lookup(name)
+            .ok_or_else(|| Error::SymbolNotFound(name.into()))?;
+
+        Ok(F::from_pointer(pointer.as_ptr()))
+    }
+
+    fn check_signature(
+        &self,
+        name: &str,
+        arguments: Vec<&str>,
+        results: Vec<&str>,
+    ) -> Result<(), Error> {
+        let signature = self
+            .signatures
+            .get(name)
+            .ok_or_else(|| Error::SymbolNotFound(name.into()))?;
+        let actual = Signature {
+            arguments: arguments.into_iter().map(Into::into).collect(),
+            results: results.into_iter().map(Into::into).collect(),
+        };
+
+        if &actual == signature {
+            Ok(())
+        } else {
+            Err(Error::SignatureMismatch {
+                function: name.into(),
+                expected: signature.to_string(),
+                actual: actual.to_string(),
+            })
+        }
+    }
+
     /// Register a symbol. This symbol will be accessible to the JIT'd codes.
     ///
     /// # Safety
@@ -85,10 +253,85 @@ impl Drop for ExecutionEngine {
     }
 }
 
+#[derive(Debug, Eq, PartialEq)]
+struct Signature {
+    arguments: Vec<String>,
+    results: Vec<String>,
+}
+
+impl Display for Signature {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "({}) -> ({})",
+            self.arguments.join(", "),
+            self.results.join(", ")
+        )
+    }
+}
+
+fn collect_signatures(module: &Module) -> HashMap<String, Signature> {
+    let mut signatures = HashMap::new();
+    let mut current = module.body().first_operation();
+
+    while let Some(operation) = current {
+        if let Some((name, signature)) = read_signature(operation) {
+            signatures.insert(name, signature);
+        }
+
+        current = operation.next_in_block();
+    }
+
+    signatures
+}
+
+fn read_signature(operation: OperationRef) -> Option<(String, Signature)> {
+    let name = StringAttribute::try_from(operation.attribute("sym_name").ok()?).ok()?;
+    let r#type = TypeAttribute::try_from(operation.attribute("function_type").ok()?)
+        .ok()?
+        .value();
+
+    let signature = match operation.name().as_string_ref().as_str().ok()? {
+        "func.func" => {
+            let r#type = FunctionType::try_from(r#type).ok()?;
+
+            Signature {
+                arguments: (0..r#type.input_count())
+                    .map(|index| Some(r#type.input(index).ok()?.to_string()))
+                    .collect::<Option<_>>()?,
+                results: (0..r#type.result_count())
+                    .map(|index| Some(r#type.result(index).ok()?.to_string()))
+                    .collect::<Option<_>>()?,
+            }
+        }
+        "llvm.func" => unsafe {
+            let result =
+                Type::from_raw(mlirLLVMFunctionTypeGetReturnType(r#type.to_raw())).to_string();
+
+            Signature {
+                arguments: (0..mlirLLVMFunctionTypeGetNumInputs(r#type.to_raw()))
+                    .map(|index| {
+                        Type::from_raw(mlirLLVMFunctionTypeGetInput(r#type.to_raw(), index))
+                            .to_string()
+                    })
+                    .collect(),
+                results: if result == LLVM_VOID_TYPE {
+                    vec![]
+                } else {
+                    vec![result]
+                },
+            }
+        },
+        _ => return None,
+    };
+
+    Some((name.value().into(), signature))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{pass, test::create_test_context};
+    use crate::{pass, test::create_test_context, Context};
 
     #[test]
     fn invoke_packed() {
@@ -112,7 +355,7 @@ mod tests {
 
         assert_eq!(pass_manager.run(&mut module), Ok(()));
 
-        let engine = ExecutionEngine::new(&module, 2, &[], false);
+        let engine = ExecutionEngine::new(&module, 2, &[], false).unwrap();
 
         let mut argument = 42;
         let mut result = -1;
@@ -134,6 +377,125 @@ mod tests {
         assert_eq!(result, 84);
     }
 
+    fn create_engine(context: &Context, source: &str) -> ExecutionEngine {
+        let mut module = Module::parse(context, source).unwrap();
+
+        let pass_manager = pass::PassManager::new(context);
+        pass_manager.add_pass(pass::conversion::create_to_llvm());
+
+        assert_eq!(pass_manager.run(&mut module), Ok(()));
+
+        ExecutionEngine::new(&module, 2, &[], false).unwrap()
+    }
+
+    const ADD_SOURCE: &str = r#"
+        module {
+            func.func @add(%arg0 : i32) -> i32 attributes { llvm.emit_c_interface } {
+                %res = arith.addi %arg0, %arg0 : i32
+                return %res : i32
+            }
+
+            func.func @scale(%arg0 : i64, %arg1 : f64) -> f64 {
+                %0 = arith.sitofp %arg0 : i64 to f64
+                %1 = arith.mulf %0, %arg1 : f64
+                return %1 : f64
+            }
+
+            func.func @nop() {
+                return
+            }
+        }
+    "#;
+
+    #[test]
+    fn invoke() {
+        let context = create_test_context();
+        let engine = create_engine(&context, ADD_SOURCE);
+
+        assert_eq!(engine.invoke::<(i32,), i32>("add", (42,)), Ok(84));
+        assert_eq!(engine.invoke::<(i64, f64), f64>("scale", (3, 0.5)), Ok(1.5));
+        assert_eq!(engine.invoke::<(), ()>("nop", ()), Ok(()));
+    }
+
+    #[test]
+    fn fail_to_invoke_with_mismatched_signature() {
+        let context = create_test_context();
+        let engine = create_engine(&context, ADD_SOURCE);
+
+        assert_eq!(
+            engine.invoke::<(i64,), i32>("add", (42,)),
+            Err(Error::SignatureMismatch {
+                function: "add".into(),
+                expected: "(i32) -> (i32)".into(),
+                actual: "(i64) -> (i32)".into(),
+            })
+        );
+        assert_eq!(
+            engine.invoke::<(i32,), ()>("add", (42,)),
+            Err(Error::SignatureMismatch {
+                function: "add".into(),
+                expected: "(i32) -> (i32)".into(),
+                actual: "(i32) -> ()".into(),
+            })
+        );
+        assert_eq!(
+            engine.invoke::<(i64, f64), f32>("scale", (3, 0.5)),
+            Err(Error::SignatureMismatch {
+                function: "scale".into(),
+                expected: "(i64, f64) -> (f64)".into(),
+                actual: "(i64, f64) -> (f32)".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn fail_to_invoke_unknown_function() {
+        let context = create_test_context();
+        let engine = create_engine(&context, ADD_SOURCE);
+
+        assert_eq!(
+            engine.invoke::<(), ()>("foo", ()),
+            Err(Error::SymbolNotFound("foo".into()))
+        );
+    }
+
+    #[test]
+    fn lookup_fn() {
+        let context = create_test_context();
+        let engine = create_engine(&context, ADD_SOURCE);
+
+        let add = unsafe { engine.lookup_fn::<extern "C" fn(i32) -> i32>("add") }.unwrap();
+        let add_interface =
+            unsafe { engine.lookup_fn::<extern "C" fn(i32) -> i32>("_mlir_ciface_add") }.unwrap();
+        let scale = unsafe { engine.lookup_fn::<extern "C" fn(i64, f64) -> f64>("scale") }.unwrap();
+
+        assert_eq!(add(21), 42);
+        assert_eq!(add_interface(21), 42);
+        assert_eq!(scale(4, 0.25), 1.0);
+        assert!(unsafe { engine.lookup_fn::<extern "C" fn(u32) -> f32>("add") }.is_err());
+    }
+
+    #[test]
+    fn lookup() {
+        let context = create_test_context();
+        let engine = create_engine(&context, ADD_SOURCE);
+
+        assert!(engine.lookup("add").is_some());
+        assert!(engine.lookup("_mlir_ciface_add").is_some());
+        assert!(engine.lookup("foo").is_none());
+    }
+
+    #[test]
+    fn fail_to_create() {
+        let context = create_test_context();
+        let module = Module::parse(&context, ADD_SOURCE).unwrap();
+
+        assert!(matches!(
+            ExecutionEngine::new(&module, 2, &[], false),
+            Err(Error::CreateExecutionEngine(message)) if !message.is_empty()
+        ));
+    }
+
     #[test]
     fn dump_to_object_file() {
         let context = create_test_context();
@@ -156,6 +518,8 @@ mod tests {
 
         assert_eq!(pass_manager.run(&mut module), Ok(()));
 
-        ExecutionEngine::new(&module, 2, &[], true).dump_to_object_file("/tmp/melior/test.o");
+        ExecutionEngine::new(&module, 2, &[], true)
+            .unwrap()
+            .dump_to_object_file("/tmp/melior/test.o");
     }
 }
@@ -0,0 +1,639 @@
+use crate::Error;
+use std::{ffi::c_void, marker::PhantomData, ptr, slice};
+
+/// A ranked memref descriptor of MLIR's C ABI.
+///
+/// A descriptor borrows a buffer of elements of `T` of rank `N`. It is passed
+/// to JIT-compiled functions which take `memref<?x?xf32>` and so on.
+///
+/// # Examples
+///
+/// ```
+/// use melior::execution_engine::StridedMemRef;
+///
+/// let mut data = vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+/// let memref = StridedMemRef::new(&mut data, [2, 3]).unwrap();
+///
+/// assert_eq!(memref.sizes(), [2, 3]);
+/// assert_eq!(memref.strides(), [3, 1]);
+/// assert_eq!(memref.get([1, 2]), Some(&6.0));
+/// assert_eq!(memref.get([2, 0]), None);
+/// ```
+#[derive(Debug)]
+#[repr(C)]
+pub struct StridedMemRef<'a, T, const N: usize> {
+    allocated: *mut T,
+    aligned: *mut T,
+    offset: i64,
+    sizes: [i64; N],
+    strides: [i64; N],
+    _data: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T, const N: usize> StridedMemRef<'a, T, N> {
+    /// Creates a memref descriptor of a row-major layout from a buffer and
+    /// sizes.
+    pub fn new(data: &'a mut [T], sizes: [usize; N]) -> Result<Self, Error> {
+        let length = sizes
+            .iter()
+            .try_fold(1usize, |length, &size| length.checked_mul(size))
+            .ok_or_else(|| Error::MemRefSize(sizes.into()))?;
+
+        if data.len() != length {
+            return Err(Error::MemRefShape {
+                expected: length,
+                actual: data.len(),
+            });
+        }
+
+        Ok(Self {
+            allocated: data.as_mut_ptr(),
+            aligned: data.as_mut_ptr(),
+            offset: 0,
+            sizes: sizes.map(|size| size as i64),
+            strides: row_major_strides(sizes).map(|stride| stride as i64),
+            _data: PhantomData,
+        })
+    }
+
+    /// Creates a memref descriptor from raw parts.
+    ///
+    /// # Safety
+    ///
+    /// All elements at an offset, sizes and strides from an aligned pointer
+    /// must be valid and exclusively borrowed for a lifetime `'a`.
+    pub unsafe fn from_raw_parts(
+        allocated: *mut T,
+        aligned: *mut T,
+        offset: usize,
+        sizes: [usize; N],
+        strides: [isize; N],
+    ) -> Self {
+        Self {
+            allocated,
+            aligned,
+            offset: offset as i64,
+            sizes: sizes.map(|size| size as i64),
+            strides: strides.map(|stride| stride as i64),
+            _data: PhantomData,
+        }
+    }
+
+    /// Returns an offset in elements.
+    pub const fn offset(&self) -> usize {
+        self.offset as usize
+    }
+
+    /// Returns sizes.
+    pub fn sizes(&self) -> [usize; N] {
+        self.sizes.map(|size| size as usize)
+    }
+
+    /// Returns strides in elements.
+    pub fn strides(&self) -> [isize; N] {
+        self.strides.map(|stride| stride as isize)
+    }
+
+    /// Returns a reference to an element at indices.
+    pub fn get(&self, indices: [usize; N]) -> Option<&T> {
+        Some(unsafe { &*self.aligned.offset(self.position(indices)?) })
+    }
+
+    /// Returns a mutable reference to an element at indices.
+    pub fn get_mut(&mut self, indices: [usize; N]) -> Option<&mut T> {
+        Some(unsafe { &mut *self.aligned.offset(self.position(indices)?) })
+    }
+
+    /// Returns elements as a slice if they are in a contiguous row-major
+    /// layout.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        self.is_contiguous()
+            .then(|| unsafe { slice::from_raw_parts(self.aligned.add(self.offset()), self.len()) })
+    }
+
+    /// Returns elements as a mutable slice if they are in a contiguous
+    /// row-major layout.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [T]> {
+        self.is_contiguous().then(|| unsafe {
+            slice::from_raw_parts_mut(self.aligned.add(self.offset()), self.len())
+        })
+    }
+
+    /// Copies elements into a vector in a row-major order.
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let sizes = self.sizes();
+        let mut elements = Vec::with_capacity(self.len());
+        let mut indices = [0; N];
+
+        for _ in 0..self.len() {
+            elements.extend(self.get(indices).cloned());
+
+            for dimension in (0..N).rev() {
+                indices[dimension] += 1;
+
+                if indices[dimension] < sizes[dimension] {
+                    break;
+                }
+
+                indices[dimension] = 0;
+            }
+        }
+
+        elements
+    }
+
+    /// Returns pointers to fields of a descriptor.
+    ///
+    /// A memref argument is expanded into its fields in a function signature
+    /// unless the function is a wrapper of `llvm.emit_c_interface`. Pointers
+    /// are passed to [`ExecutionEngine::invoke_packed`](super::ExecutionEngine::invoke_packed)
+    /// in place of an argument of such a function.
+    pub fn pointers(&mut self) -> Vec<*mut ()> {
+        let mut pointers = vec![
+            &mut self.allocated as *mut *mut T as *mut (),
+            &mut self.aligned as *mut *mut T as *mut (),
+            &mut self.offset as *mut i64 as *mut (),
+        ];
+
+        pointers.extend(
+            self.sizes
+                .iter_mut()
+                .chain(&mut self.strides)
+                .map(|value| value as *mut i64 as *mut ()),
+        );
+
+        pointers
+    }
+
+    fn len(&self) -> usize {
+        self.sizes().iter().product()
+    }
+
+    fn position(&self, indices: [usize; N]) -> Option<isize> {
+        let mut position = self.offset as isize;
+
+        for ((index, size), stride) in indices.into_iter().zip(self.sizes()).zip(self.strides()) {
+            if index >= size {
+                return None;
+            }
+
+            position += index as isize * stride;
+        }
+
+        Some(position)
+    }
+
+    fn is_contiguous(&self) -> bool {
+        self.len() == 0
+            || self
+                .sizes()
+                .into_iter()
+                .zip(self.strides())
+                .zip(row_major_strides(self.sizes()))
+                .all(|((size, stride), expected)| size == 1 || stride == expected as isize)
+    }
+}
+
+/// A ranked memref which owns a buffer of elements.
+///
+/// # Examples
+///
+/// ```
+/// use melior::execution_engine::OwnedMemRef;
+///
+/// let mut memref = OwnedMemRef::new(vec![1, 2, 3, 4, 5, 6], [3, 2]).unwrap();
+///
+/// *memref.mem_ref().get_mut([2, 0]).unwrap() = 42;
+///
+/// assert_eq!(memref.into_vec(), [1, 2, 3, 4, 42, 6]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct OwnedMemRef<T, const N: usize> {
+    data: Vec<T>,
+    sizes: [usize; N],
+}
+
+impl<T, const N: usize> OwnedMemRef<T, N> {
+    /// Creates a memref of a row-major layout from a vector and sizes.
+    pub fn new(data: Vec<T>, sizes: [usize; N]) -> Result<Self, Error> {
+        let mut memref = Self { data, sizes };
+
+        StridedMemRef::new(&mut memref.data, sizes)?;
+
+        Ok(memref)
+    }
+
+    /// Returns sizes.
+    pub const fn sizes(&self) -> [usize; N] {
+        self.sizes
+    }
+
+    /// Returns elements in a row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Returns a memref descriptor which borrows elements.
+    pub fn mem_ref(&mut self) -> StridedMemRef<'_, T, N> {
+        StridedMemRef::new(&mut self.data, self.sizes).expect("valid sizes")
+    }
+
+    /// Converts a memref into a vector of elements in a row-major order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+/// A ranked memref descriptor returned by JIT-compiled functions.
+///
+/// Elements are allocated by the functions, for example with `memref.alloc`,
+/// and are not freed when the descriptor is dropped.
+///
+/// A descriptor is passed to [`ExecutionEngine::invoke_packed`](super::ExecutionEngine::invoke_packed)
+/// as a result of a function returning a memref, such as `memref<?xf32>`.
+#[derive(Debug)]
+#[repr(C)]
+pub struct ReturnedMemRef<T, const N: usize> {
+    descriptor: StridedMemRef<'static, T, N>,
+}
+
+impl<T, const N: usize> ReturnedMemRef<T, N> {
+    /// Creates a descriptor to which a function returns a memref.
+    pub fn new() -> Self {
+        Self {
+            descriptor: StridedMemRef {
+                allocated: ptr::null_mut(),
+                aligned: ptr::null_mut(),
+                offset: 0,
+                sizes: [0; N],
+                strides: [0; N],
+                _data: PhantomData,
+            },
+        }
+    }
+
+    /// Returns a pointer to a descriptor.
+    ///
+    /// It is passed as a result of a function. A function of
+    /// `llvm.emit_c_interface` takes a pointer to the pointer instead.
+    pub fn pointer(&mut self) -> *mut () {
+        &mut self.descriptor as *mut StridedMemRef<T, N> as *mut ()
+    }
+
+    /// Returns a memref descriptor of returned elements.
+    ///
+    /// # Safety
+    ///
+    /// A memref must be returned by a function and its elements must not be
+    /// freed.
+    pub unsafe fn mem_ref(&mut self) -> &mut StridedMemRef<'_, T, N> {
+        &mut *(&mut self.descriptor as *mut StridedMemRef<T, N> as *mut StridedMemRef<'_, T, N>)
+    }
+
+    /// Frees returned elements allocated with `malloc`, which `memref.alloc`
+    /// is lowered to.
+    ///
+    /// # Safety
+    ///
+    /// A memref must be returned by a function and its elements must be
+    /// allocated with `malloc`.
+    pub unsafe fn free(self) {
+        extern "C" {
+            fn free(pointer: *mut c_void);
+        }
+
+        free(self.descriptor.allocated as *mut c_void)
+    }
+}
+
+impl<T, const N: usize> Default for ReturnedMemRef<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An unranked memref descriptor of MLIR's C ABI.
+///
+/// A descriptor borrows a ranked memref descriptor. It is passed to
+/// JIT-compiled functions which take `memref<*xf32>` and so on.
+#[derive(Debug)]
+#[repr(C)]
+pub struct UnrankedMemRef<'a, T> {
+    rank: i64,
+    descriptor: *mut (),
+    _data: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T> UnrankedMemRef<'a, T> {
+    /// Creates an unranked memref descriptor from a ranked one.
+    pub fn new<const N: usize>(memref: &'a mut StridedMemRef<'a, T, N>) -> Self {
+        Self {
+            rank: N as i64,
+            descriptor: memref as *mut StridedMemRef<T, N> as *mut (),
+            _data: PhantomData,
+        }
+    }
+
+    /// Returns a rank.
+    pub const fn rank(&self) -> usize {
+        self.rank as usize
+    }
+
+    /// Returns a ranked memref descriptor if it is of a rank `N`.
+    pub fn ranked<const N: usize>(&self) -> Option<&StridedMemRef<'a, T, N>> {
+        (self.rank() == N).then(|| unsafe { &*(self.descriptor as *const StridedMemRef<T, N>) })
+    }
+
+    /// Returns a mutable ranked memref descriptor if it is of a rank `N`.
+    pub fn ranked_mut<const N: usize>(&mut self) -> Option<&mut StridedMemRef<'a, T, N>> {
+        (self.rank() == N).then(|| unsafe { &mut *(self.descriptor as *mut StridedMemRef<T, N>) })
+    }
+
+    /// Returns pointers to fields of a descriptor.
+    ///
+    /// See [`StridedMemRef::pointers`].
+    pub fn pointers(&mut self) -> Vec<*mut ()> {
+        vec![
+            &mut self.rank as *mut i64 as *mut (),
+            &mut self.descriptor as *mut *mut () as *mut (),
+        ]
+    }
+}
+
+fn row_major_strides<const N: usize>(sizes: [usize; N]) -> [usize; N] {
+    let mut strides = [1; N];
+
+    for dimension in (0..N.saturating_sub(1)).rev() {
+        strides[dimension] = strides[dimension + 1] * sizes[dimension + 1];
+    }
+
+    strides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::Module, pipeline, test::create_test_context, utility::register_all_passes, Context,
+        ExecutionEngine,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn create_engine(context: &Context, source: &str) -> ExecutionEngine {
+        let mut module = Module::parse(context, source).unwrap();
+
+        register_all_passes();
+        pipeline::memref_to_llvm()
+            .create_pass_manager(context)
+            .unwrap()
+            .run(&mut module)
+            .unwrap();
+
+        ExecutionEngine::new(&module, 2, &[], false).unwrap()
+    }
+
+    #[test]
+    fn new() {
+        let mut data = (0..24).collect::<Vec<i32>>();
+        let memref = StridedMemRef::new(&mut data, [2, 3, 4]).unwrap();
+
+        assert_eq!(memref.offset(), 0);
+        assert_eq!(memref.sizes(), [2, 3, 4]);
+        assert_eq!(memref.strides(), [12, 4, 1]);
+        assert_eq!(memref.get([1, 2, 3]), Some(&23));
+        assert_eq!(memref.get([0, 3, 0]), None);
+        assert_eq!(memref.as_slice(), Some(&(0..24).collect::<Vec<_>>()[..]));
+        assert_eq!(memref.to_vec(), (0..24).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn new_scalar() {
+        let mut data = [42];
+        let memref = StridedMemRef::new(&mut data, []).unwrap();
+
+        assert_eq!(memref.get([]), Some(&42));
+        assert_eq!(memref.to_vec(), vec![42]);
+    }
+
+    #[test]
+    fn fail_to_create_with_mismatched_shape() {
+        let mut data = [0; 5];
+
+        assert_eq!(
+            StridedMemRef::new(&mut data, [2, 3]).unwrap_err(),
+            Error::MemRefShape {
+                expected: 6,
+                actual: 5
+            }
+        );
+    }
+
+    #[test]
+    fn fail_to_create_with_overflowing_sizes() {
+        assert_eq!(
+            StridedMemRef::<i32, 2>::new(&mut [], [usize::MAX, 2]).unwrap_err(),
+            Error::MemRefSize(vec![usize::MAX, 2])
+        );
+    }
+
+    #[test]
+    fn own_elements() {
+        let mut memref = OwnedMemRef::new((0..6).collect::<Vec<i32>>(), [2, 3]).unwrap();
+
+        assert_eq!(memref.sizes(), [2, 3]);
+        assert_eq!(memref.mem_ref().get([1, 0]), Some(&3));
+
+        *memref.mem_ref().get_mut([0, 2]).unwrap() = 42;
+
+        assert_eq!(memref.as_slice(), [0, 1, 42, 3, 4, 5]);
+        assert_eq!(memref.into_vec(), [0, 1, 42, 3, 4, 5]);
+        assert_eq!(
+            OwnedMemRef::new(vec![0; 5], [2, 3]).unwrap_err(),
+            Error::MemRefShape {
+                expected: 6,
+                actual: 5
+            }
+        );
+    }
+
+    #[test]
+    fn view_strided_elements() {
+        let mut data = (0..12).collect::<Vec<i64>>();
+        // A transposed view of the last 3 columns.
+        let mut memref = unsafe {
+            StridedMemRef::from_raw_parts(data.as_mut_ptr(), data.as_mut_ptr(), 1, [3, 3], [1, 4])
+        };
+
+        assert_eq!(memref.as_slice(), None);
+        assert_eq!(memref.to_vec(), vec![1, 5, 9, 2, 6, 10, 3, 7, 11]);
+
+        *memref.get_mut([2, 1]).unwrap() = 42;
+
+        assert_eq!(data[7], 42);
+    }
+
+    #[test]
+    fn view_through_unranked_memref() {
+        let mut data = vec![1, 2, 3, 4];
+        let mut memref = StridedMemRef::new(&mut data, [2, 2]).unwrap();
+        let mut unranked = UnrankedMemRef::new(&mut memref);
+
+        assert_eq!(unranked.rank(), 2);
+        assert!(unranked.ranked::<1>().is_none());
+        assert_eq!(unranked.ranked::<2>().unwrap().get([1, 0]), Some(&3));
+
+        *unranked.ranked_mut::<2>().unwrap().get_mut([0, 1]).unwrap() = 42;
+
+        assert_eq!(data, [1, 42, 3, 4]);
+    }
+
+    const SUM_SOURCE: &str = r#"
+        func.func @sum(%memref : memref<?x?xf32>) -> f32 attributes { llvm.emit_c_interface } {
+            %c0 = arith.constant 0 : index
+            %c1 = arith.constant 1 : index
+            %zero = arith.constant 0.0 : f32
+            %rows = memref.dim %memref, %c0 : memref<?x?xf32>
+            %columns = memref.dim %memref, %c1 : memref<?x?xf32>
+            %result = scf.for %i = %c0 to %rows step %c1 iter_args(%row_sum = %zero) -> (f32) {
+                %next = scf.for %j = %c0 to %columns step %c1 iter_args(%sum = %row_sum) -> (f32) {
+                    %value = memref.load %memref[%i, %j] : memref<?x?xf32>
+                    %next = arith.addf %sum, %value : f32
+                    scf.yield %next : f32
+                }
+                scf.yield %next : f32
+            }
+            return %result : f32
+        }
+
+        func.func @double(%memref : memref<*xf32>) attributes { llvm.emit_c_interface } {
+            %c0 = arith.constant 0 : index
+            %c1 = arith.constant 1 : index
+            %ranked = memref.cast %memref : memref<*xf32> to memref<?xf32>
+            %size = memref.dim %ranked, %c0 : memref<?xf32>
+            scf.for %i = %c0 to %size step %c1 {
+                %value = memref.load %ranked[%i] : memref<?xf32>
+                %doubled = arith.addf %value, %value : f32
+                memref.store %doubled, %ranked[%i] : memref<?xf32>
+            }
+            return
+        }
+    "#;
+
+    #[test]
+    fn invoke_with_memref() {
+        let context = create_test_context();
+        let engine = create_engine(&context, SUM_SOURCE);
+        let mut data = vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let mut memref = StridedMemRef::new(&mut data, [2, 3]).unwrap();
+        let mut result = 0.0f32;
+
+        let mut arguments = memref.pointers();
+        arguments.push(&mut result as *mut f32 as *mut ());
+
+        unsafe { engine.invoke_packed("sum", &mut arguments) }.unwrap();
+
+        assert_eq!(result, 21.0);
+    }
+
+    #[test]
+    fn invoke_with_memref_through_c_interface() {
+        let context = create_test_context();
+        let engine = create_engine(&context, SUM_SOURCE);
+        let mut data = vec![1.0f32, 2.0, 3.0, 4.0];
+        let mut memref = StridedMemRef::new(&mut data, [2, 2]).unwrap();
+        let mut pointer = &mut memref as *mut StridedMemRef<f32, 2>;
+        let mut result = 0.0f32;
+
+        unsafe {
+            engine.invoke_packed(
+                "_mlir_ciface_sum",
+                &mut [
+                    &mut pointer as *mut *mut StridedMemRef<f32, 2> as *mut (),
+                    &mut result as *mut f32 as *mut (),
+                ],
+            )
+        }
+        .unwrap();
+
+        assert_eq!(result, 10.0);
+    }
+
+    #[test]
+    fn invoke_with_unranked_memref() {
+        let context = create_test_context();
+        let engine = create_engine(&context, SUM_SOURCE);
+        let mut data = vec![1.0f32, 2.0, 3.0];
+        let mut memref = StridedMemRef::new(&mut data, [3]).unwrap();
+        let mut unranked = UnrankedMemRef::new(&mut memref);
+
+        unsafe { engine.invoke_packed("double", &mut unranked.pointers()) }.unwrap();
+
+        assert_eq!(
+            unranked.ranked::<1>().unwrap().to_vec(),
+            vec![2.0, 4.0, 6.0]
+        );
+        assert_eq!(data, [2.0, 4.0, 6.0]);
+    }
+
+    const RANGE_SOURCE: &str = r#"
+        func.func @range(%size : index) -> memref<?xf32> attributes { llvm.emit_c_interface } {
+            %c0 = arith.constant 0 : index
+            %c1 = arith.constant 1 : index
+            %memref = memref.alloc(%size) : memref<?xf32>
+            scf.for %i = %c0 to %size step %c1 {
+                %integer = arith.index_cast %i : index to i64
+                %value = arith.sitofp %integer : i64 to f32
+                memref.store %value, %memref[%i] : memref<?xf32>
+            }
+            return %memref : memref<?xf32>
+        }
+    "#;
+
+    #[test]
+    fn invoke_with_returned_memref() {
+        let context = create_test_context();
+        let engine = create_engine(&context, RANGE_SOURCE);
+        let mut size = 3usize;
+        let mut memref = ReturnedMemRef::<f32, 1>::new();
+
+        unsafe {
+            engine.invoke_packed(
+                "range",
+                &mut [&mut size as *mut usize as *mut (), memref.pointer()],
+            )
+        }
+        .unwrap();
+
+        assert_eq!(unsafe { memref.mem_ref() }.sizes(), [3]);
+        assert_eq!(unsafe { memref.mem_ref() }.to_vec(), vec![0.0, 1.0, 2.0]);
+
+        unsafe { memref.free() };
+    }
+
+    #[test]
+    fn invoke_with_returned_memref_through_c_interface() {
+        let context = create_test_context();
+        let engine = create_engine(&context, RANGE_SOURCE);
+        let mut size = 2usize;
+        let mut memref = ReturnedMemRef::<f32, 1>::new();
+        let mut pointer = memref.pointer();
+
+        unsafe {
+            engine.invoke_packed(
+                "_mlir_ciface_range",
+                &mut [
+                    &mut pointer as *mut *mut () as *mut (),
+                    &mut size as *mut usize as *mut (),
+                ],
+            )
+        }
+        .unwrap();
+
+        assert_eq!(unsafe { memref.mem_ref() }.to_vec(), vec![0.0, 1.0]);
+
+        unsafe { memref.free() };
+    }
+}
@@ -0,0 +1,128 @@
+use std::mem::transmute_copy;
+
+/// A native type of arguments and results of JIT-compiled functions.
+///
+/// # Safety
+///
+/// A type must have the same layout and calling convention as values of an
+/// MLIR type of [`TYPE`](NativeType::TYPE).
+pub unsafe trait NativeType: Copy {
+    /// A textual form of an MLIR type.
+    const TYPE: &'static str;
+}
+
+macro_rules! impl_native_type {
+    ($type:ty, $name:literal) => {
+        unsafe impl NativeType for $type {
+            const TYPE: &'static str = $name;
+        }
+    };
+}
+
+impl_native_type!(bool, "i1");
+impl_native_type!(i8, "i8");
+impl_native_type!(i16, "i16");
+impl_native_type!(i32, "i32");
+impl_native_type!(i64, "i64");
+impl_native_type!(u8, "i8");
+impl_native_type!(u16, "i16");
+impl_native_type!(u32, "i32");
+impl_native_type!(u64, "i64");
+impl_native_type!(f32, "f32");
+impl_native_type!(f64, "f64");
+
+/// Native arguments of JIT-compiled functions.
+///
+/// It is implemented for tuples of native types.
+pub trait NativeArguments {
+    /// Returns textual forms of MLIR types of arguments.
+    fn types() -> Vec<&'static str>;
+
+    /// Returns pointers to arguments.
+    fn pointers(&mut self) -> Vec<*mut ()>;
+}
+
+/// A native result of JIT-compiled functions.
+///
+/// It is implemented for native types and `()` of no result.
+pub trait NativeResult {
+    /// Returns textual forms of MLIR types of results.
+    fn types() -> Vec<&'static str>;
+}
+
+impl NativeResult for () {
+    fn types() -> Vec<&'static str> {
+        vec![]
+    }
+}
+
+impl<T: NativeType> NativeResult for T {
+    fn types() -> Vec<&'static str> {
+        vec![T::TYPE]
+    }
+}
+
+/// A native function pointer type of JIT-compiled functions, such as
+/// `extern "C" fn(i64) -> i64`.
+///
+/// # Safety
+///
+/// A type must be a function pointer type of native arguments and a native
+/// result.
+pub unsafe trait NativeFunction: Copy {
+    /// Returns textual forms of MLIR types of arguments.
+    fn argument_types() -> Vec<&'static str>;
+
+    /// Returns textual forms of MLIR types of results.
+    fn result_types() -> Vec<&'static str>;
+
+    /// Converts a pointer into a function.
+    ///
+    /// # Safety
+    ///
+    /// A pointer must point to a function of the same signature.
+    unsafe fn from_pointer(pointer: *mut ()) -> Self;
+}
+
+macro_rules! impl_native_function {
+    ($($type:ident),*) => {
+        impl<$($type: NativeType),*> NativeArguments for ($($type,)*) {
+            fn types() -> Vec<&'static str> {
+                vec![$($type::TYPE),*]
+            }
+
+            #[allow(non_snake_case)]
+            fn pointers(&mut self) -> Vec<*mut ()> {
+                let ($($type,)*) = self;
+
+                vec![$($type as *mut $type as *mut ()),*]
+            }
+        }
+
+        unsafe impl<$($type: NativeType,)* R: NativeResult> NativeFunction
+            for extern "C" fn($($type),*) -> R
+        {
+            fn argument_types() -> Vec<&'static str> {
+                vec![$($type::TYPE),*]
+            }
+
+            fn result_types() -> Vec<&'static str> {
+                R::types()
+            }
+
+            unsafe fn from_pointer(pointer: *mut ()) -> Self {
+                transmute_copy(&pointer)
+            }
+        }
+    };
+}
+
+impl_native_function!();
+impl_native_function!(A);
+impl_native_function!(A, B);
+impl_native_function!(A, B, C);
+impl_native_function!(A, B, C, D);
+impl_native_function!(A, B, C, D, E);
+impl_native_function!(A, B, C, D, E, F);
+impl_native_function!(A, B, C, D, E, F, G);
+impl_native_function!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,1253 @@
+//! A reference interpreter.
+//!
+//! The interpreter executes functions in modules without JIT compilation. It
+//! supports a subset of operations in the `arith`, `index`, `scf`, `cf`,
+//! `func`, and `memref` dialects, and fails on the other operations.
+
+use crate::{
+    ir::{
+        attribute::{
+            Attribute, BoolAttribute, DenseI32ArrayAttribute, FlatSymbolRefAttribute,
+            FloatAttribute, IntegerAttribute, StringAttribute,
+        },
+        operation::OperationLike,
+        r#type::{IntegerType, MemRefType},
+        BlockLike, BlockRef, Module, OperationRef, RegionLike, RegionRef, ShapedTypeLike, Type,
+        TypeLike, ValueLike,
+    },
+    Error,
+};
+use std::{cell::RefCell, collections::HashMap, ffi::c_void, rc::Rc};
+
+const INDEX_PREDICATES: [&str; 10] = [
+    "eq", "ne", "slt", "sle", "sgt", "sge", "ult", "ule", "ugt", "uge",
+];
+
+type Environment = HashMap<*const c_void, Value>;
+type IntegerFunction = fn(u64, u64, u32) -> Option<u64>;
+type FloatFunction = fn(f64, f64) -> f64;
+
+/// A runtime value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// An integer.
+    ///
+    /// Its bits beyond its width are always zero.
+    Integer { width: u32, bits: u64 },
+    /// An index.
+    Index(i64),
+    /// A 32-bit floating point number.
+    F32(f32),
+    /// A 64-bit floating point number.
+    F64(f64),
+    /// A memory reference.
+    MemRef(MemRef),
+}
+
+impl Value {
+    /// Creates an integer truncated to a width.
+    pub const fn integer(width: u32, value: i64) -> Self {
+        Self::Integer {
+            width,
+            bits: value as u64 & mask(width),
+        }
+    }
+
+    /// Creates a boolean.
+    pub const fn bool(value: bool) -> Self {
+        Self::integer(1, value as i64)
+    }
+
+    /// Returns a signed value of an integer or an index.
+    pub const fn to_i64(&self) -> Option<i64> {
+        match self {
+            Self::Integer { width, bits } => Some(sign_extend(*bits, *width)),
+            Self::Index(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns an unsigned value of an integer or an index.
+    pub const fn to_u64(&self) -> Option<u64> {
+        match self {
+            Self::Integer { bits, .. } => Some(*bits),
+            Self::Index(value) => Some(*value as u64),
+            _ => None,
+        }
+    }
+
+    /// Returns a value of a floating point number.
+    pub const fn to_f64(&self) -> Option<f64> {
+        match self {
+            Self::F32(value) => Some(*value as f64),
+            Self::F64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns a value of a boolean.
+    pub const fn to_bool(&self) -> Option<bool> {
+        match self {
+            Self::Integer { width: 1, bits } => Some(*bits != 0),
+            _ => None,
+        }
+    }
+
+    /// Returns a memory reference.
+    pub const fn as_mem_ref(&self) -> Option<&MemRef> {
+        match self {
+            Self::MemRef(mem_ref) => Some(mem_ref),
+            _ => None,
+        }
+    }
+
+    fn with_bits(&self, bits: u64) -> Self {
+        match self {
+            Self::Integer { width, .. } => Self::Integer {
+                width: *width,
+                bits: bits & mask(*width),
+            },
+            _ => Self::Index(bits as i64),
+        }
+    }
+
+    fn with_f64(&self, value: f64) -> Self {
+        match self {
+            Self::F32(_) => Self::F32(value as f32),
+            _ => Self::F64(value),
+        }
+    }
+}
+
+/// A memory reference.
+///
+/// Its elements are stored in row-major order. Cloned memory references share
+/// their buffers.
+#[derive(Clone, Debug)]
+pub struct MemRef {
+    shape: Vec<usize>,
+    elements: Rc<RefCell<Vec<Value>>>,
+}
+
+impl MemRef {
+    /// Creates a memory reference filled with a value.
+    ///
+    /// It fails if the number of elements overflows.
+    pub fn new(shape: &[usize], value: Value) -> Result<Self, Error> {
+        let count = element_count(shape).ok_or_else(|| Error::MemRefSize(shape.to_vec()))?;
+
+        Ok(Self {
+            shape: shape.to_vec(),
+            elements: Rc::new(RefCell::new(vec![value; count])),
+        })
+    }
+
+    /// Creates a memory reference from elements.
+    ///
+    /// Returns `None` if the number of elements does not match a shape.
+    pub fn from_elements(shape: &[usize], elements: Vec<Value>) -> Option<Self> {
+        (element_count(shape) == Some(elements.len())).then(|| Self {
+            shape: shape.to_vec(),
+            elements: Rc::new(RefCell::new(elements)),
+        })
+    }
+
+    /// Returns a shape.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns elements.
+    pub fn elements(&self) -> Vec<Value> {
+        self.elements.borrow().clone()
+    }
+
+    /// Loads an element.
+    pub fn load(&self, indices: &[usize]) -> Option<Value> {
+        self.elements.borrow().get(self.offset(indices)?).cloned()
+    }
+
+    /// Stores an element.
+    ///
+    /// Returns `None` if indices are out of bounds.
+    pub fn store(&self, indices: &[usize], value: Value) -> Option<()> {
+        let offset = self.offset(indices)?;
+
+        self.elements.borrow_mut()[offset] = value;
+
+        Some(())
+    }
+
+    fn offset(&self, indices: &[usize]) -> Option<usize> {
+        if indices.len() != self.shape.len() {
+            return None;
+        }
+
+        indices
+            .iter()
+            .zip(&self.shape)
+            .try_fold(0, |offset, (&index, &size)| {
+                (index < size).then_some(offset * size + index)
+            })
+    }
+}
+
+fn element_count(shape: &[usize]) -> Option<usize> {
+    shape
+        .iter()
+        .try_fold(1usize, |count, &size| count.checked_mul(size))
+}
+
+impl PartialEq for MemRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && *self.elements.borrow() == *other.elements.borrow()
+    }
+}
+
+enum Exit {
+    Yield(Vec<Value>),
+    Condition(bool, Vec<Value>),
+}
+
+enum Flow<'c, 'a> {
+    Next,
+    Branch(BlockRef<'c, 'a>, Vec<Value>),
+    Exit(Exit),
+}
+
+/// A reference interpreter.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{
+///     dialect::DialectRegistry,
+///     interpreter::{Interpreter, Value},
+///     ir::Module,
+///     utility::register_all_dialects,
+///     Context,
+/// };
+///
+/// let registry = DialectRegistry::new();
+/// register_all_dialects(&registry);
+///
+/// let context = Context::new();
+/// context.append_dialect_registry(&registry);
+/// context.load_all_available_dialects();
+///
+/// let module = Module::parse(
+///     &context,
+///     r#"
+///     func.func @add(%x : i32, %y : i32) -> i32 {
+///         %z = arith.addi %x, %y : i32
+///         return %z : i32
+///     }
+///     "#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     Interpreter::new(&module).call("add", &[Value::integer(32, 1), Value::integer(32, 2)]),
+///     Ok(vec![Value::integer(32, 3)])
+/// );
+/// ```
+pub struct Interpreter<'c, 'm> {
+    module: &'m Module<'c>,
+}
+
+impl<'c, 'm> Interpreter<'c, 'm> {
+    /// Creates an interpreter.
+    pub const fn new(module: &'m Module<'c>) -> Self {
+        Self { module }
+    }
+
+    /// Calls a function.
+    pub fn call(&self, name: &str, arguments: &[Value]) -> Result<Vec<Value>, Error> {
+        let function = self.function(name)?;
+
+        self.run_region_to_yield(
+            function,
+            function.region(0)?,
+            arguments.to_vec(),
+            &mut Environment::new(),
+        )
+    }
+
+    fn function(&self, name: &str) -> Result<OperationRef<'c, 'm>, Error> {
+        let mut operation = self.module.body().first_operation();
+
+        while let Some(function) = operation {
+            if function.name().as_string_ref().as_str() == Ok("func.func")
+                && function
+                    .attribute("sym_name")
+                    .ok()
+                    .and_then(|name| StringAttribute::try_from(name).ok())
+                    .map(|name| name.value())
+                    == Some(name)
+            {
+                return Ok(function);
+            }
+
+            operation = function.next_in_block();
+        }
+
+        Err(Error::SymbolNotFound(name.into()))
+    }
+
+    fn run_region<'a>(
+        &self,
+        parent: OperationRef<'c, 'a>,
+        region: RegionRef<'c, 'a>,
+        mut arguments: Vec<Value>,
+        environment: &mut Environment,
+    ) -> Result<Exit, Error> {
+        let mut block = region
+            .first_block()
+            .ok_or_else(|| fail(parent, "empty region"))?;
+
+        loop {
+            if block.argument_count() != arguments.len() {
+                return Err(fail(
+                    parent,
+                    format!(
+                        "{} block arguments expected but got {}",
+                        block.argument_count(),
+                        arguments.len()
+                    ),
+                ));
+            }
+
+            for (index, value) in arguments.into_iter().enumerate() {
+                environment.insert(key(&block.argument(index)?), value);
+            }
+
+            let mut operation = block.first_operation();
+
+            loop {
+                let current = operation.ok_or_else(|| fail(parent, "terminator expected"))?;
+
+                match self.run_operation(current, environment)? {
+                    Flow::Next => operation = current.next_in_block(),
+                    Flow::Branch(successor, values) => {
+                        block = successor;
+                        arguments = values;
+                        break;
+                    }
+                    Flow::Exit(exit) => return Ok(exit),
+                }
+            }
+        }
+    }
+
+    fn run_region_to_yield<'a>(
+        &self,
+        parent: OperationRef<'c, 'a>,
+        region: RegionRef<'c, 'a>,
+        arguments: Vec<Value>,
+        environment: &mut Environment,
+    ) -> Result<Vec<Value>, Error> {
+        match self.run_region(parent, region, arguments, environment)? {
+            Exit::Yield(values) => Ok(values),
+            Exit::Condition(..) => Err(fail(parent, "unexpected scf.condition")),
+        }
+    }
+
+    fn run_operation<'a>(
+        &self,
+        operation: OperationRef<'c, 'a>,
+        environment: &mut Environment,
+    ) -> Result<Flow<'c, 'a>, Error> {
+        let name = operation.name();
+        let name = name.as_string_ref().as_str()?;
+        let operands = operation
+            .operands()
+            .map(|operand| {
+                environment
+                    .get(&key(&operand))
+                    .cloned()
+                    .ok_or_else(|| fail(operation, "undefined operand"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let results = if let Some(function) = integer_function(name) {
+            let [lhs, rhs] = operands.as_slice() else {
+                return Err(fail(operation, "two operands expected"));
+            };
+            let (width, lhs_bits) = integer_bits(operation, lhs)?;
+            let (_, rhs_bits) = integer_bits(operation, rhs)?;
+
+            vec![lhs.with_bits(
+                function(lhs_bits, rhs_bits, width)
+                    .ok_or_else(|| fail(operation, "division by zero"))?,
+            )]
+        } else if let Some(function) = float_function(name) {
+            let [lhs, rhs] = operands.as_slice() else {
+                return Err(fail(operation, "two operands expected"));
+            };
+
+            vec![lhs.with_f64(function(float(operation, lhs)?, float(operation, rhs)?))]
+        } else {
+            match name {
+                "arith.constant" | "index.constant" => vec![constant(operation)?],
+                "index.bool.constant" => vec![Value::bool(
+                    BoolAttribute::try_from(operation.attribute("value")?)?.value(),
+                )],
+                "arith.cmpi" | "index.cmp" => {
+                    let predicate = if name == "arith.cmpi" {
+                        IntegerAttribute::try_from(operation.attribute("predicate")?)?.value()
+                    } else {
+                        // The C API does not expose values of enum attributes, so compare
+                        // them with uniqued attributes of predicates instead.
+                        let predicate = operation.attribute("pred")?;
+                        let context = unsafe { operation.context().to_ref() };
+
+                        INDEX_PREDICATES
+                            .iter()
+                            .position(|name| {
+                                Attribute::parse(context, &format!("#index<cmp_predicate {name}>"))
+                                    == Some(predicate)
+                            })
+                            .ok_or_else(|| fail(operation, "unknown predicate"))?
+                            as i64
+                    };
+                    let (width, lhs) = integer_bits(operation, &operands[0])?;
+                    let (_, rhs) = integer_bits(operation, &operands[1])?;
+
+                    vec![Value::bool(
+                        compare_integers(predicate, lhs, rhs, width)
+                            .ok_or_else(|| fail(operation, "unknown predicate"))?,
+                    )]
+                }
+                "arith.cmpf" => {
+                    let predicate =
+                        IntegerAttribute::try_from(operation.attribute("predicate")?)?.value();
+
+                    vec![Value::bool(
+                        compare_floats(
+                            predicate,
+                            float(operation, &operands[0])?,
+                            float(operation, &operands[1])?,
+                        )
+                        .ok_or_else(|| fail(operation, "unknown predicate"))?,
+                    )]
+                }
+                "arith.select" => vec![if boolean(operation, &operands[0])? {
+                    operands[1].clone()
+                } else {
+                    operands[2].clone()
+                }],
+                "arith.negf" => {
+                    vec![operands[0].with_f64(-float(operation, &operands[0])?)]
+                }
+                "arith.index_cast" | "arith.extsi" | "arith.trunci" | "index.casts" => {
+                    vec![integer_from_i64(
+                        operation,
+                        result_type(operation)?,
+                        integer(operation, &operands[0])?,
+                    )?]
+                }
+                "arith.index_castui" | "arith.extui" | "index.castu" => vec![integer_from_i64(
+                    operation,
+                    result_type(operation)?,
+                    unsigned_integer(operation, &operands[0])? as i64,
+                )?],
+                "arith.sitofp" => vec![float_from_f64(
+                    operation,
+                    result_type(operation)?,
+                    integer(operation, &operands[0])? as f64,
+                )?],
+                "arith.uitofp" => vec![float_from_f64(
+                    operation,
+                    result_type(operation)?,
+                    unsigned_integer(operation, &operands[0])? as f64,
+                )?],
+                "arith.fptosi" => vec![integer_from_i64(
+                    operation,
+                    result_type(operation)?,
+                    float(operation, &operands[0])? as i64,
+                )?],
+                "arith.fptoui" => vec![integer_from_i64(
+                    operation,
+                    result_type(operation)?,
+                    float(operation, &operands[0])? as u64 as i64,
+                )?],
+                "arith.extf" | "arith.truncf" => vec![float_from_f64(
+                    operation,
+                    result_type(operation)?,
+                    float(operation, &operands[0])?,
+                )?],
+                "scf.for" => self.run_for(operation, operands, environment)?,
+                "scf.if" => {
+                    let region = operation.region(if boolean(operation, &operands[0])? {
+                        0
+                    } else {
+                        1
+                    })?;
+
+                    if region.first_block().is_some() {
+                        self.run_region_to_yield(operation, region, vec![], environment)?
+                    } else {
+                        vec![]
+                    }
+                }
+                "scf.while" => self.run_while(operation, operands, environment)?,
+                "scf.execute_region" => {
+                    self.run_region_to_yield(operation, operation.region(0)?, vec![], environment)?
+                }
+                "scf.yield" | "func.return" => return Ok(Flow::Exit(Exit::Yield(operands))),
+                "scf.condition" => {
+                    return Ok(Flow::Exit(Exit::Condition(
+                        boolean(operation, &operands[0])?,
+                        operands[1..].to_vec(),
+                    )))
+                }
+                "cf.br" => return Ok(Flow::Branch(operation.successor(0)?, operands)),
+                "cf.cond_br" => {
+                    let sizes = DenseI32ArrayAttribute::try_from(
+                        operation.attribute("operandSegmentSizes")?,
+                    )?;
+                    let count = sizes.element(1)? as usize + 1;
+
+                    return Ok(if boolean(operation, &operands[0])? {
+                        Flow::Branch(operation.successor(0)?, operands[1..count].to_vec())
+                    } else {
+                        Flow::Branch(operation.successor(1)?, operands[count..].to_vec())
+                    });
+                }
+                "cf.assert" => {
+                    if !boolean(operation, &operands[0])? {
+                        return Err(fail(
+                            operation,
+                            StringAttribute::try_from(operation.attribute("msg")?)?.value(),
+                        ));
+                    }
+
+                    vec![]
+                }
+                "func.call" => self.call(
+                    FlatSymbolRefAttribute::try_from(operation.attribute("callee")?)?.value(),
+                    &operands,
+                )?,
+                "memref.alloc" | "memref.alloca" => {
+                    let r#type = MemRefType::try_from(result_type(operation)?)?;
+                    let mut sizes = operands.iter();
+                    let shape = (0..r#type.rank())
+                        .map(|index| {
+                            let size = r#type.dim_size(index)?;
+
+                            if size as i64 == i64::MIN {
+                                sizes
+                                    .next()
+                                    .and_then(Value::to_i64)
+                                    .and_then(|size| usize::try_from(size).ok())
+                                    .ok_or_else(|| {
+                                        fail(operation, "non-negative dynamic size expected")
+                                    })
+                            } else {
+                                Ok(size)
+                            }
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+
+                    vec![Value::MemRef(
+                        MemRef::new(&shape, zero(operation, r#type.element())?)
+                            .map_err(|error| fail(operation, error.to_string()))?,
+                    )]
+                }
+                "memref.dealloc" => vec![],
+                "memref.cast" => vec![operands[0].clone()],
+                "memref.dim" => {
+                    let index = unsigned_integer(operation, &operands[1])? as usize;
+
+                    vec![Value::Index(
+                        *mem_ref(operation, &operands[0])?
+                            .shape()
+                            .get(index)
+                            .ok_or_else(|| fail(operation, "dimension out of bounds"))?
+                            as i64,
+                    )]
+                }
+                "memref.load" => vec![mem_ref(operation, &operands[0])?
+                    .load(&indices(operation, &operands[1..])?)
+                    .ok_or_else(|| fail(operation, "index out of bounds"))?],
+                "memref.store" => {
+                    mem_ref(operation, &operands[1])?
+                        .store(&indices(operation, &operands[2..])?, operands[0].clone())
+                        .ok_or_else(|| fail(operation, "index out of bounds"))?;
+
+                    vec![]
+                }
+                "memref.copy" => {
+                    let source = mem_ref(operation, &operands[0])?;
+                    let target = mem_ref(operation, &operands[1])?;
+
+                    if source.shape() != target.shape() {
+                        return Err(fail(operation, "shape mismatch"));
+                    }
+
+                    let elements = source.elements();
+                    *target.elements.borrow_mut() = elements;
+
+                    vec![]
+                }
+                _ => return Err(unsupported(operation)),
+            }
+        };
+
+        if results.len() != operation.result_count() {
+            return Err(fail(
+                operation,
+                format!(
+                    "{} results expected but got {}",
+                    operation.result_count(),
+                    results.len()
+                ),
+            ));
+        }
+
+        for (result, value) in operation.results().zip(results) {
+            environment.insert(key(&result), value);
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn run_for(
+        &self,
+        operation: OperationRef<'c, '_>,
+        operands: Vec<Value>,
+        environment: &mut Environment,
+    ) -> Result<Vec<Value>, Error> {
+        let [lower, upper, step, values @ ..] = operands.as_slice() else {
+            return Err(fail(operation, "bounds and a step expected"));
+        };
+        let upper = integer(operation, upper)?;
+        let step = integer(operation, step)?;
+        let mut values = values.to_vec();
+
+        if step <= 0 {
+            return Err(fail(operation, "positive step expected"));
+        }
+
+        let mut index = integer(operation, lower)?;
+
+        while index < upper {
+            let mut arguments = vec![lower.with_bits(index as u64)];
+            arguments.extend(values);
+
+            values =
+                self.run_region_to_yield(operation, operation.region(0)?, arguments, environment)?;
+
+            // The next index exceeds any upper bound on overflow.
+            let Some(next) = index.checked_add(step) else {
+                break;
+            };
+
+            index = next;
+        }
+
+        Ok(values)
+    }
+
+    fn run_while(
+        &self,
+        operation: OperationRef<'c, '_>,
+        mut values: Vec<Value>,
+        environment: &mut Environment,
+    ) -> Result<Vec<Value>, Error> {
+        loop {
+            match self.run_region(operation, operation.region(0)?, values, environment)? {
+                Exit::Condition(true, arguments) => {
+                    values = self.run_region_to_yield(
+                        operation,
+                        operation.region(1)?,
+                        arguments,
+                        environment,
+                    )?;
+                }
+                Exit::Condition(false, arguments) => return Ok(arguments),
+                Exit::Yield(_) => return Err(fail(operation, "scf.condition expected")),
+            }
+        }
+    }
+}
+
+const fn mask(width: u32) -> u64 {
+    if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+const fn sign_extend(bits: u64, width: u32) -> i64 {
+    if width == 0 {
+        0
+    } else if width >= 64 {
+        bits as i64
+    } else {
+        let shift = 64 - width;
+
+        ((bits << shift) as i64) >> shift
+    }
+}
+
+fn key<'c>(value: &impl ValueLike<'c>) -> *const c_void {
+    value.to_raw().ptr
+}
+
+fn integer_function(name: &str) -> Option<IntegerFunction> {
+    let function: IntegerFunction = match name {
+        "arith.addi" | "index.add" => |lhs, rhs, _| Some(lhs.wrapping_add(rhs)),
+        "arith.subi" | "index.sub" => |lhs, rhs, _| Some(lhs.wrapping_sub(rhs)),
+        "arith.muli" | "index.mul" => |lhs, rhs, _| Some(lhs.wrapping_mul(rhs)),
+        "arith.divsi" | "index.divs" => |lhs, rhs, width| {
+            let (lhs, rhs) = (sign_extend(lhs, width), sign_extend(rhs, width));
+
+            (rhs != 0).then(|| lhs.wrapping_div(rhs) as u64)
+        },
+        "arith.divui" | "index.divu" => |lhs, rhs, _| lhs.checked_div(rhs),
+        "arith.remsi" | "index.rems" => |lhs, rhs, width| {
+            let (lhs, rhs) = (sign_extend(lhs, width), sign_extend(rhs, width));
+
+            (rhs != 0).then(|| lhs.wrapping_rem(rhs) as u64)
+        },
+        "arith.remui" | "index.remu" => |lhs, rhs, _| lhs.checked_rem(rhs),
+        "arith.andi" | "index.and" => |lhs, rhs, _| Some(lhs & rhs),
+        "arith.ori" | "index.or" => |lhs, rhs, _| Some(lhs | rhs),
+        "arith.xori" | "index.xor" => |lhs, rhs, _| Some(lhs ^ rhs),
+        "arith.shli" | "index.shl" => {
+            |lhs, rhs, width| Some(if rhs < width as u64 { lhs << rhs } else { 0 })
+        }
+        "arith.shrui" | "index.shru" => {
+            |lhs, rhs, width| Some(if rhs < width as u64 { lhs >> rhs } else { 0 })
+        }
+        "arith.shrsi" | "index.shrs" => {
+            |lhs, rhs, width| Some((sign_extend(lhs, width) >> rhs.min(width as u64 - 1)) as u64)
+        }
+        "arith.maxsi" | "index.maxs" => {
+            |lhs, rhs, width| Some(sign_extend(lhs, width).max(sign_extend(rhs, width)) as u64)
+        }
+        "arith.minsi" | "index.mins" => {
+            |lhs, rhs, width| Some(sign_extend(lhs, width).min(sign_extend(rhs, width)) as u64)
+        }
+        "arith.maxui" | "index.maxu" => |lhs, rhs, _| Some(lhs.max(rhs)),
+        "arith.minui" | "index.minu" => |lhs, rhs, _| Some(lhs.min(rhs)),
+        _ => return None,
+    };
+
+    Some(function)
+}
+
+fn float_function(name: &str) -> Option<FloatFunction> {
+    let function: FloatFunction = match name {
+        "arith.addf" => |lhs, rhs| lhs + rhs,
+        "arith.subf" => |lhs, rhs| lhs - rhs,
+        "arith.mulf" => |lhs, rhs| lhs * rhs,
+        "arith.divf" => |lhs, rhs| lhs / rhs,
+        "arith.remf" => |lhs, rhs| lhs % rhs,
+        "arith.maximumf" => |lhs, rhs| {
+            if lhs.is_nan() || rhs.is_nan() {
+                f64::NAN
+            } else {
+                lhs.max(rhs)
+            }
+        },
+        "arith.minimumf" => |lhs, rhs| {
+            if lhs.is_nan() || rhs.is_nan() {
+                f64::NAN
+            } else {
+                lhs.min(rhs)
+            }
+        },
+        "arith.maxnumf" => f64::max,
+        "arith.minnumf" => f64::min,
+        _ => return None,
+    };
+
+    Some(function)
+}
+
+fn compare_integers(predicate: i64, lhs: u64, rhs: u64, width: u32) -> Option<bool> {
+    let (signed_lhs, signed_rhs) = (sign_extend(lhs, width), sign_extend(rhs, width));
+
+    Some(match predicate {
+        0 => lhs == rhs,
+        1 => lhs != rhs,
+        2 => signed_lhs < signed_rhs,
+        3 => signed_lhs <= signed_rhs,
+        4 => signed_lhs > signed_rhs,
+        5 => signed_lhs >= signed_rhs,
+        6 => lhs < rhs,
+        7 => lhs <= rhs,
+        8 => lhs > rhs,
+        9 => lhs >= rhs,
+        _ => return None,
+    })
+}
+
+fn compare_floats(predicate: i64, lhs: f64, rhs: f64) -> Option<bool> {
+    let unordered = lhs.is_nan() || rhs.is_nan();
+
+    Some(match predicate {
+        0 => false,
+        1 => !unordered && lhs == rhs,
+        2 => !unordered && lhs > rhs,
+        3 => !unordered && lhs >= rhs,
+        4 => !unordered && lhs < rhs,
+        5 => !unordered && lhs <= rhs,
+        6 => !unordered && lhs != rhs,
+        7 => !unordered,
+        8 => unordered || lhs == rhs,
+        9 => unordered || lhs > rhs,
+        10 => unordered || lhs >= rhs,
+        11 => unordered || lhs < rhs,
+        12 => unordered || lhs <= rhs,
+        13 => unordered || lhs != rhs,
+        14 => unordered,
+        15 => true,
+        _ => return None,
+    })
+}
+
+fn constant(operation: OperationRef) -> Result<Value, Error> {
+    let r#type = result_type(operation)?;
+    let attribute = operation.attribute("value")?;
+
+    if r#type.is_index() || r#type.is_integer() {
+        integer_from_i64(
+            operation,
+            r#type,
+            IntegerAttribute::try_from(attribute)?.value(),
+        )
+    } else if r#type.is_f32() || r#type.is_f64() {
+        float_from_f64(
+            operation,
+            r#type,
+            FloatAttribute::try_from(attribute)?.value(),
+        )
+    } else {
+        Err(unsupported(operation))
+    }
+}
+
+fn zero(operation: OperationRef, r#type: Type) -> Result<Value, Error> {
+    if r#type.is_index() || r#type.is_integer() {
+        integer_from_i64(operation, r#type, 0)
+    } else {
+        float_from_f64(operation, r#type, 0.0)
+    }
+}
+
+fn integer_from_i64(operation: OperationRef, r#type: Type, value: i64) -> Result<Value, Error> {
+    if r#type.is_index() {
+        Ok(Value::Index(value))
+    } else {
+        let width = IntegerType::try_from(r#type)?.width();
+
+        if width > 64 {
+            Err(fail(operation, format!("unsupported type {type}")))
+        } else {
+            Ok(Value::integer(width, value))
+        }
+    }
+}
+
+fn float_from_f64(operation: OperationRef, r#type: Type, value: f64) -> Result<Value, Error> {
+    if r#type.is_f32() {
+        Ok(Value::F32(value as f32))
+    } else if r#type.is_f64() {
+        Ok(Value::F64(value))
+    } else {
+        Err(fail(operation, format!("unsupported type {type}")))
+    }
+}
+
+fn result_type<'c>(operation: OperationRef<'c, '_>) -> Result<Type<'c>, Error> {
+    Ok(operation.result(0)?.r#type())
+}
+
+fn integer_bits(operation: OperationRef, value: &Value) -> Result<(u32, u64), Error> {
+    match value {
+        Value::Integer { width, bits } => Ok((*width, *bits)),
+        Value::Index(value) => Ok((64, *value as u64)),
+        _ => Err(fail(operation, "integer expected")),
+    }
+}
+
+fn integer(operation: OperationRef, value: &Value) -> Result<i64, Error> {
+    value
+        .to_i64()
+        .ok_or_else(|| fail(operation, "integer expected"))
+}
+
+fn unsigned_integer(operation: OperationRef, value: &Value) -> Result<u64, Error> {
+    value
+        .to_u64()
+        .ok_or_else(|| fail(operation, "integer expected"))
+}
+
+fn float(operation: OperationRef, value: &Value) -> Result<f64, Error> {
+    value
+        .to_f64()
+        .ok_or_else(|| fail(operation, "floating point number expected"))
+}
+
+fn boolean(operation: OperationRef, value: &Value) -> Result<bool, Error> {
+    value
+        .to_bool()
+        .ok_or_else(|| fail(operation, "boolean expected"))
+}
+
+fn mem_ref<'a>(operation: OperationRef, value: &'a Value) -> Result<&'a MemRef, Error> {
+    value
+        .as_mem_ref()
+        .ok_or_else(|| fail(operation, "memory reference expected"))
+}
+
+fn indices(operation: OperationRef, values: &[Value]) -> Result<Vec<usize>, Error> {
+    values
+        .iter()
+        .map(|value| Ok(unsigned_integer(operation, value)? as usize))
+        .collect()
+}
+
+fn operation_name(operation: OperationRef) -> String {
+    operation
+        .name()
+        .as_string_ref()
+        .as_str()
+        .unwrap_or_default()
+        .into()
+}
+
+fn fail(operation: OperationRef, message: impl Into<String>) -> Error {
+    Error::Interpret {
+        operation: operation_name(operation),
+        location: operation.location().to_string(),
+        message: message.into(),
+    }
+}
+
+fn unsupported(operation: OperationRef) -> Error {
+    Error::UnsupportedOperation {
+        operation: operation_name(operation),
+        location: operation.location().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::create_test_context;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    const SOURCE: &str = indoc!(
+        "
+        func.func @factorial(%n : index) -> i64 {
+            %c1 = arith.constant 1 : index
+            %one = arith.constant 1 : i64
+            %end = arith.addi %n, %c1 : index
+            %result = scf.for %i = %c1 to %end step %c1 iter_args(%acc = %one) -> (i64) {
+                %x = arith.index_cast %i : index to i64
+                %next = arith.muli %acc, %x : i64
+                scf.yield %next : i64
+            }
+            return %result : i64
+        }
+
+        func.func @fibonacci(%n : i32) -> i32 {
+            %c2 = arith.constant 2 : i32
+            %less = arith.cmpi slt, %n, %c2 : i32
+            cf.cond_br %less, ^bb1, ^bb2
+        ^bb1:
+            return %n : i32
+        ^bb2:
+            %c1 = arith.constant 1 : i32
+            %a = arith.subi %n, %c1 : i32
+            %b = arith.subi %n, %c2 : i32
+            %x = func.call @fibonacci(%a) : (i32) -> i32
+            %y = func.call @fibonacci(%b) : (i32) -> i32
+            %z = arith.addi %x, %y : i32
+            return %z : i32
+        }
+
+        func.func @gcd(%a : i64, %b : i64) -> i64 {
+            %c0 = arith.constant 0 : i64
+            %result:2 = scf.while (%x = %a, %y = %b) : (i64, i64) -> (i64, i64) {
+                %nonzero = arith.cmpi ne, %y, %c0 : i64
+                scf.condition(%nonzero) %x, %y : i64, i64
+            } do {
+            ^bb0(%x : i64, %y : i64):
+                %remainder = arith.remui %x, %y : i64
+                scf.yield %y, %remainder : i64, i64
+            }
+            return %result#0 : i64
+        }
+
+        func.func @sum(%memref : memref<?xf64>) -> f64 {
+            %c0 = arith.constant 0 : index
+            %c1 = arith.constant 1 : index
+            %zero = arith.constant 0.0 : f64
+            %size = memref.dim %memref, %c0 : memref<?xf64>
+            %sum = scf.for %i = %c0 to %size step %c1 iter_args(%acc = %zero) -> (f64) {
+                %x = memref.load %memref[%i] : memref<?xf64>
+                %y = arith.addf %acc, %x : f64
+                scf.yield %y : f64
+            }
+            return %sum : f64
+        }
+
+        func.func @store(%x : i8) -> i8 {
+            %c0 = arith.constant 0 : index
+            %memref = memref.alloc() : memref<1xi8>
+            %y = arith.addi %x, %x : i8
+            memref.store %y, %memref[%c0] : memref<1xi8>
+            %z = memref.load %memref[%c0] : memref<1xi8>
+            memref.dealloc %memref : memref<1xi8>
+            return %z : i8
+        }
+
+        func.func @abs(%x : i32) -> i32 {
+            %c0 = arith.constant 0 : i32
+            %negative = arith.cmpi slt, %x, %c0 : i32
+            %y = scf.if %negative -> i32 {
+                %y = arith.subi %c0, %x : i32
+                scf.yield %y : i32
+            } else {
+                scf.yield %x : i32
+            }
+            return %y : i32
+        }
+
+        func.func @max(%x : index, %y : index) -> index {
+            %less = index.cmp ult(%x, %y)
+            %z = scf.if %less -> index {
+                scf.yield %y : index
+            } else {
+                scf.yield %x : index
+            }
+            return %z : index
+        }
+
+        func.func @divide(%x : i32, %y : i32) -> i32 {
+            %z = arith.divsi %x, %y : i32
+            return %z : i32
+        }
+
+        func.func @sqrt(%x : f64) -> f64 {
+            %y = math.sqrt %x : f64
+            return %y : f64
+        }
+
+        func.func @count(%lower : i64, %upper : i64, %step : i64) -> i64 {
+            %c0 = arith.constant 0 : i64
+            %c1 = arith.constant 1 : i64
+            %count = scf.for %i = %lower to %upper step %step iter_args(%acc = %c0) -> (i64) : i64 {
+                %next = arith.addi %acc, %c1 : i64
+                scf.yield %next : i64
+            }
+            return %count : i64
+        }
+
+        func.func @allocate(%size : index) -> index {
+            %c1 = arith.constant 1 : index
+            %memref = memref.alloc(%size, %size) : memref<?x?xi8>
+            %x = memref.dim %memref, %c1 : memref<?x?xi8>
+            memref.dealloc %memref : memref<?x?xi8>
+            return %x : index
+        }
+        "
+    );
+
+    #[test]
+    fn factorial() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module).call("factorial", &[Value::Index(5)]),
+            Ok(vec![Value::integer(64, 120)])
+        );
+    }
+
+    #[test]
+    fn fibonacci() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module).call("fibonacci", &[Value::integer(32, 10)]),
+            Ok(vec![Value::integer(32, 55)])
+        );
+    }
+
+    #[test]
+    fn gcd() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module)
+                .call("gcd", &[Value::integer(64, 48), Value::integer(64, 18)]),
+            Ok(vec![Value::integer(64, 6)])
+        );
+    }
+
+    #[test]
+    fn sum() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let memref = MemRef::from_elements(
+            &[3],
+            vec![Value::F64(1.0), Value::F64(2.0), Value::F64(3.5)],
+        )
+        .unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module).call("sum", &[Value::MemRef(memref)]),
+            Ok(vec![Value::F64(6.5)])
+        );
+    }
+
+    #[test]
+    fn store() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module).call("store", &[Value::integer(8, 100)]),
+            Ok(vec![Value::integer(8, -56)])
+        );
+    }
+
+    #[test]
+    fn abs() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let interpreter = Interpreter::new(&module);
+
+        assert_eq!(
+            interpreter.call("abs", &[Value::integer(32, -42)]),
+            Ok(vec![Value::integer(32, 42)])
+        );
+        assert_eq!(
+            interpreter.call("abs", &[Value::integer(32, 42)]),
+            Ok(vec![Value::integer(32, 42)])
+        );
+    }
+
+    #[test]
+    fn max() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let interpreter = Interpreter::new(&module);
+
+        assert_eq!(
+            interpreter.call("max", &[Value::Index(1), Value::Index(2)]),
+            Ok(vec![Value::Index(2)])
+        );
+        assert_eq!(
+            interpreter.call("max", &[Value::Index(3), Value::Index(2)]),
+            Ok(vec![Value::Index(3)])
+        );
+    }
+
+    #[test]
+    fn fail_to_divide_by_zero() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert!(matches!(
+            Interpreter::new(&module).call("divide", &[Value::integer(32, 1), Value::integer(32, 0)]),
+            Err(Error::Interpret { operation, message, .. })
+                if operation == "arith.divsi" && message == "division by zero"
+        ));
+    }
+
+    #[test]
+    fn fail_to_run_unsupported_operation() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert!(matches!(
+            Interpreter::new(&module).call("sqrt", &[Value::F64(4.0)]),
+            Err(Error::UnsupportedOperation { operation, .. }) if operation == "math.sqrt"
+        ));
+    }
+
+    #[test]
+    fn fail_to_find_function() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module).call("foo", &[]),
+            Err(Error::SymbolNotFound("foo".into()))
+        );
+    }
+
+    #[test]
+    fn count_to_maximum() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module).call(
+                "count",
+                &[
+                    Value::integer(64, i64::MAX - 1),
+                    Value::integer(64, i64::MAX),
+                    Value::integer(64, 2)
+                ]
+            ),
+            Ok(vec![Value::integer(64, 1)])
+        );
+    }
+
+    #[test]
+    fn allocate() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert_eq!(
+            Interpreter::new(&module).call("allocate", &[Value::Index(2)]),
+            Ok(vec![Value::Index(2)])
+        );
+    }
+
+    #[test]
+    fn fail_to_allocate_negative_size() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert!(matches!(
+            Interpreter::new(&module).call("allocate", &[Value::Index(-1)]),
+            Err(Error::Interpret { operation, message, .. })
+                if operation == "memref.alloc" && message == "non-negative dynamic size expected"
+        ));
+    }
+
+    #[test]
+    fn fail_to_allocate_overflowing_size() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        assert!(matches!(
+            Interpreter::new(&module).call("allocate", &[Value::Index(i64::MAX)]),
+            Err(Error::Interpret { operation, .. }) if operation == "memref.alloc"
+        ));
+        assert_eq!(
+            MemRef::new(&[usize::MAX, 2], Value::Index(0)),
+            Err(Error::MemRefSize(vec![usize::MAX, 2]))
+        );
+    }
+
+    #[test]
+    fn integer() {
+        assert_eq!(Value::integer(8, -1).to_u64(), Some(255));
+        assert_eq!(Value::integer(8, 255).to_i64(), Some(-1));
+        assert_eq!(Value::bool(true).to_bool(), Some(true));
+    }
+}
@@ -8,6 +8,7 @@ mod location;
 mod module;
 pub mod operation;
 mod region;
+mod rewriter;
 pub mod r#type;
 mod value;
 
@@ -21,5 +22,6 @@ pub use self::{
     operation::{Operation, OperationRef},
     r#type::{ShapedTypeLike, Type, TypeLike},
     region::{Region, RegionLike, RegionRef},
+    rewriter::{InsertionGuard, RewriteListener, Rewriter},
     value::{Value, ValueLike},
 };
@@ -9,6 +9,7 @@ use mlir_sys::{
 use std::{
     ffi::c_void,
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     marker::PhantomData,
 };
 
@@ -51,6 +52,13 @@ impl PartialEq for AffineMap<'_> {
 
 impl Eq for AffineMap<'_> {}
 
+// Affine maps are uniqued in contexts, so their pointers identify them.
+impl Hash for AffineMap<'_> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
 impl Display for AffineMap<'_> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let mut data = (formatter, Ok(()));
@@ -31,6 +31,7 @@ use mlir_sys::{
 use std::{
     ffi::c_void,
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     marker::PhantomData,
 };
 
@@ -102,6 +103,13 @@ impl PartialEq for Attribute<'_> {
 
 impl Eq for Attribute<'_> {}
 
+// Attributes are uniqued in contexts, so their pointers identify them.
+impl Hash for Attribute<'_> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
 impl Display for Attribute<'_> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let mut data = (formatter, Ok(()));
@@ -146,6 +154,7 @@ mod tests {
         ir::{Type, TypeLike},
         test::create_test_context,
     };
+    use std::collections::HashSet;
 
     #[test]
     fn parse() {
@@ -318,6 +327,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hash() {
+        let context = create_test_context();
+
+        let attributes = HashSet::from([
+            Attribute::parse(&context, "unit").unwrap(),
+            Attribute::unit(&context),
+            Attribute::parse(&context, "42").unwrap(),
+        ]);
+
+        assert_eq!(attributes.len(), 2);
+        assert!(attributes.contains(&Attribute::parse(&context, "42").unwrap()));
+    }
+
+    #[test]
+    fn hash_subtype() {
+        let context = create_test_context();
+
+        let attributes = HashSet::from([
+            StringAttribute::new(&context, "foo"),
+            StringAttribute::new(&context, "foo"),
+            StringAttribute::new(&context, "bar"),
+        ]);
+
+        assert_eq!(attributes.len(), 2);
+    }
+
     #[test]
     fn display() {
         let context = create_test_context();
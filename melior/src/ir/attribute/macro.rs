@@ -39,6 +39,20 @@ macro_rules! attribute_traits {
                 std::fmt::Display::fmt(self, formatter)
             }
         }
+
+        impl<'c> PartialEq for $name<'c> {
+            fn eq(&self, other: &Self) -> bool {
+                self.attribute == other.attribute
+            }
+        }
+
+        impl<'c> Eq for $name<'c> {}
+
+        impl<'c> std::hash::Hash for $name<'c> {
+            fn hash<H: std::hash::Hasher>(&self, hasher: &mut H) {
+                std::hash::Hash::hash(&self.attribute, hasher);
+            }
+        }
     };
 }
 
@@ -70,5 +84,19 @@ macro_rules! attribute_traits_no_try_from {
                 std::fmt::Display::fmt(self, formatter)
             }
         }
+
+        impl<'c> PartialEq for $name<'c> {
+            fn eq(&self, other: &Self) -> bool {
+                self.attribute == other.attribute
+            }
+        }
+
+        impl<'c> Eq for $name<'c> {}
+
+        impl<'c> std::hash::Hash for $name<'c> {
+            fn hash<H: std::hash::Hasher>(&self, hasher: &mut H) {
+                std::hash::Hash::hash(&self.attribute, hasher);
+            }
+        }
     };
 }
@@ -6,7 +6,11 @@ use mlir_sys::{
     mlirIdentifierEqual, mlirIdentifierGet, mlirIdentifierGetContext, mlirIdentifierStr,
     MlirIdentifier,
 };
-use std::marker::PhantomData;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
 
 /// An identifier.
 #[derive(Clone, Copy, Debug)]
@@ -62,9 +66,36 @@ impl PartialEq for Identifier<'_> {
 
 impl Eq for Identifier<'_> {}
 
+// Identifiers are uniqued in contexts, so their pointers identify them.
+impl Hash for Identifier<'_> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
+impl PartialOrd for Identifier<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Identifiers are ordered by their names.
+///
+/// Identifiers with the same name from different contexts are ordered by their
+/// addresses to stay consistent with equality.
+impl Ord for Identifier<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_string_ref()
+            .as_bytes()
+            .cmp(other.as_string_ref().as_bytes())
+            .then_with(|| self.raw.ptr.cmp(&other.raw.ptr))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn new() {
@@ -95,4 +126,38 @@ mod tests {
             Identifier::new(&context, "bar")
         );
     }
+
+    #[test]
+    fn hash() {
+        let context = Context::new();
+
+        let identifiers = HashSet::from([
+            Identifier::new(&context, "foo"),
+            Identifier::new(&context, "foo"),
+            Identifier::new(&context, "bar"),
+        ]);
+
+        assert_eq!(identifiers.len(), 2);
+        assert!(identifiers.contains(&Identifier::new(&context, "bar")));
+    }
+
+    #[test]
+    fn order() {
+        let context = Context::new();
+
+        let mut identifiers = vec![
+            Identifier::new(&context, "foo"),
+            Identifier::new(&context, "bar"),
+            Identifier::new(&context, "baz"),
+        ];
+        identifiers.sort();
+
+        assert_eq!(
+            identifiers
+                .iter()
+                .map(|identifier| identifier.as_string_ref().as_str().unwrap())
+                .collect::<Vec<_>>(),
+            ["bar", "baz", "foo"]
+        );
+    }
 }
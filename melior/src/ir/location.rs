@@ -12,6 +12,7 @@ use mlir_sys::{
 use std::{
     ffi::c_void,
     fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
     marker::PhantomData,
 };
 
@@ -97,6 +98,15 @@ impl PartialEq for Location<'_> {
     }
 }
 
+impl Eq for Location<'_> {}
+
+// Locations are uniqued in contexts, so their pointers identify them.
+impl Hash for Location<'_> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
 impl Display for Location<'_> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let mut data = (formatter, Ok(()));
@@ -117,6 +127,7 @@ impl Display for Location<'_> {
 mod tests {
     use super::*;
     use pretty_assertions::{assert_eq, assert_ne};
+    use std::collections::HashSet;
 
     #[test]
     fn new() {
@@ -182,6 +193,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hash() {
+        let context = Context::new();
+
+        let locations = HashSet::from([
+            Location::unknown(&context),
+            Location::unknown(&context),
+            Location::new(&context, "foo", 42, 42),
+        ]);
+
+        assert_eq!(locations.len(), 2);
+        assert!(locations.contains(&Location::new(&context, "foo", 42, 42)));
+    }
+
     #[test]
     fn display() {
         let context = Context::new();
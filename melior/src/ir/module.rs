@@ -7,7 +7,7 @@ use mlir_sys::{
     mlirModuleCreateEmpty, mlirModuleCreateParse, mlirModuleDestroy, mlirModuleFromOperation,
     mlirModuleGetBody, mlirModuleGetContext, mlirModuleGetOperation, MlirModule,
 };
-use std::{ffi::CString, marker::PhantomData};
+use std::{ffi::CString, marker::PhantomData, mem::forget};
 
 /// A module.
 #[derive(Debug)]
@@ -86,6 +86,15 @@ impl<'c> Module<'c> {
     pub const fn to_raw(&self) -> MlirModule {
         self.raw
     }
+
+    /// Converts a module into a raw object.
+    pub const fn into_raw(self) -> MlirModule {
+        let module = self.raw;
+
+        forget(self);
+
+        module
+    }
 }
 
 impl Drop for Module<'_> {
@@ -1,12 +1,21 @@
 //! Operations and operation builders.
 
 mod builder;
+mod interface;
 mod operation_like;
 mod printing_flags;
 mod result;
 
+pub(crate) use self::interface::remove_models;
 pub use self::{
     builder::OperationBuilder,
+    interface::{
+        BranchOpInterface, BranchOpModel, CallOpInterface, CallOpModel, CallableOpInterface,
+        CallableOpModel, Callee, Implementation, InferShapedTypeOpInterface, InferTypeOpInterface,
+        LoopBounds, LoopLikeOpInterface, LoopLikeOpModel, MemoryEffect, MemoryEffectKind,
+        MemoryEffectOpInterface, MemoryEffectOpModel, OperationInterface, RegionBranchOpInterface,
+        RegionBranchOpModel, RegionBranchPoint,
+    },
     operation_like::{OperationLike, OperationMutLike, WalkOrder, WalkResult},
     printing_flags::OperationPrintingFlags,
     result::OperationResult,
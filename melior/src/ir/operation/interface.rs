@@ -0,0 +1,224 @@
+//! Operation interfaces.
+//!
+//! Interfaces whose type IDs are exposed by the MLIR C API are queried
+//! natively. Other interfaces are emulated by models registered per context
+//! and per operation name or dialect namespace, and operations are viewed
+//! through them.
+
+mod branch;
+mod call;
+mod callable;
+mod infer_type;
+mod loop_like;
+mod memory_effect;
+mod region_branch;
+
+pub use self::{
+    branch::{BranchOpInterface, BranchOpModel},
+    call::{CallOpInterface, CallOpModel, Callee},
+    callable::{CallableOpInterface, CallableOpModel},
+    infer_type::{InferShapedTypeOpInterface, InferTypeOpInterface},
+    loop_like::{LoopBounds, LoopLikeOpInterface, LoopLikeOpModel},
+    memory_effect::{MemoryEffect, MemoryEffectKind, MemoryEffectOpInterface, MemoryEffectOpModel},
+    region_branch::{RegionBranchOpInterface, RegionBranchOpModel, RegionBranchPoint},
+};
+use super::{OperationLike, OperationRef};
+use crate::{
+    context::Context,
+    ir::{attribute::DenseI32ArrayAttribute, Value},
+    Error,
+};
+use mlir_sys::MlirContext;
+use std::{
+    any::{self, Any},
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+// Models of interfaces keyed by contexts and model types.
+type Registries = HashMap<(usize, any::TypeId), Box<dyn Any + Send>>;
+
+static REGISTRIES: OnceLock<Mutex<Registries>> = OnceLock::new();
+
+/// An operation interface.
+pub trait OperationInterface {
+    /// Returns if an operation of a name implements an interface in a context.
+    fn implementation(context: &Context, name: &str) -> Implementation;
+}
+
+/// A status of an interface implemented by operations.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Implementation {
+    /// An interface is implemented.
+    Implemented,
+    /// An interface is not implemented.
+    NotImplemented,
+    /// It is unknown if an interface is implemented, such as for unregistered
+    /// operations or ones without emulated models.
+    Unknown,
+}
+
+/// A registry of interface models keyed by operation names or dialect
+/// namespaces.
+///
+/// Models are registered per context and builtin ones are registered on their
+/// first use in each context.
+struct ModelRegistry<M: 'static> {
+    builtins: fn() -> Vec<(&'static str, M)>,
+}
+
+impl<M: Copy + Send> ModelRegistry<M> {
+    const fn new(builtins: fn() -> Vec<(&'static str, M)>) -> Self {
+        Self { builtins }
+    }
+
+    fn with_models<T>(&self, context: &Context, f: impl FnOnce(&mut HashMap<String, M>) -> T) -> T {
+        let mut registries = registries()
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+
+        f(registries
+            .entry((context.to_raw().ptr as usize, any::TypeId::of::<M>()))
+            .or_insert_with(|| {
+                Box::new(
+                    (self.builtins)()
+                        .into_iter()
+                        .map(|(name, model)| (name.to_owned(), model))
+                        .collect::<HashMap<_, _>>(),
+                )
+            })
+            .downcast_mut()
+            .expect("models of a model type"))
+    }
+
+    fn register(&self, context: &Context, name: &str, model: M) {
+        self.with_models(context, |models| models.insert(name.into(), model));
+    }
+
+    fn get(&self, context: &Context, name: &str) -> Option<M> {
+        self.with_models(context, |models| {
+            models
+                .get(name)
+                .or_else(|| models.get(name.split_once('.')?.0))
+                .copied()
+        })
+    }
+
+    fn get_for(&self, operation: OperationRef) -> Option<M> {
+        self.get(
+            unsafe { operation.context().to_ref() },
+            operation.name().as_string_ref().as_str().ok()?,
+        )
+    }
+
+    fn implementation(&self, context: &Context, name: &str) -> Implementation {
+        if self.get(context, name).is_some() {
+            Implementation::Implemented
+        } else {
+            Implementation::Unknown
+        }
+    }
+}
+
+fn registries() -> &'static Mutex<Registries> {
+    REGISTRIES.get_or_init(Default::default)
+}
+
+/// Removes models registered in a context.
+pub(crate) fn remove_models(context: MlirContext) {
+    registries()
+        .lock()
+        .unwrap_or_else(|error| error.into_inner())
+        .retain(|&(key, _), _| key != context.ptr as usize);
+}
+
+/// Returns operands in a segment of an operation with the
+/// `operandSegmentSizes` attribute.
+fn operand_segment<'c, 'a>(
+    operation: OperationRef<'c, 'a>,
+    index: usize,
+) -> Result<Vec<Value<'c, 'a>>, Error> {
+    let sizes = DenseI32ArrayAttribute::try_from(operation.attribute("operandSegmentSizes")?)?;
+    let start = (0..index)
+        .map(|index| Ok(sizes.element(index)? as usize))
+        .sum::<Result<usize, Error>>()?;
+
+    (start..start + sizes.element(index)? as usize)
+        .map(|index| operation.operand(index))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{Module, Type, ValueLike},
+        test::{create_test_context, find_operation},
+    };
+
+    #[test]
+    fn register_dialect_model() {
+        let context = create_test_context();
+        let registry = ModelRegistry::<usize>::new(|| vec![("foo", 1), ("bar.baz", 2)]);
+
+        assert_eq!(registry.get(&context, "foo.qux"), Some(1));
+        assert_eq!(registry.get(&context, "bar.baz"), Some(2));
+        assert_eq!(registry.get(&context, "bar.qux"), None);
+
+        registry.register(&context, "bar", 3);
+
+        assert_eq!(registry.get(&context, "bar.qux"), Some(3));
+        assert_eq!(registry.get(&context, "bar.baz"), Some(2));
+        assert_eq!(
+            registry.implementation(&context, "bar.qux"),
+            Implementation::Implemented
+        );
+        assert_eq!(
+            registry.implementation(&context, "qux.qux"),
+            Implementation::Unknown
+        );
+    }
+
+    #[test]
+    fn register_model_per_context() {
+        let context = create_test_context();
+        let other_context = create_test_context();
+        let registry = ModelRegistry::<u8>::new(Vec::new);
+
+        registry.register(&context, "foo", 1);
+
+        assert_eq!(registry.get(&context, "foo.bar"), Some(1));
+        assert_eq!(registry.get(&other_context, "foo.bar"), None);
+    }
+
+    #[test]
+    fn get_operand_segment() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%x: i1, %y: index, %z: index) {
+              cf.cond_br %x, ^bb1(%y, %z : index, index), ^bb2(%z : index)
+            ^bb1(%a: index, %b: index):
+              return
+            ^bb2(%c: index):
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let operation = find_operation(&module, "cf.cond_br");
+
+        assert_eq!(operand_segment(operation, 0).unwrap().len(), 1);
+        assert_eq!(
+            operand_segment(operation, 1)
+                .unwrap()
+                .iter()
+                .map(|value| value.r#type())
+                .collect::<Vec<_>>(),
+            vec![Type::index(&context); 2]
+        );
+        assert_eq!(operand_segment(operation, 2).unwrap().len(), 1);
+        assert!(operand_segment(operation, 3).is_err());
+    }
+}
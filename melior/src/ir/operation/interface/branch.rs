@@ -0,0 +1,226 @@
+use super::{operand_segment, Implementation, ModelRegistry, OperationInterface};
+use crate::{
+    context::Context,
+    ir::{
+        attribute::DenseI32ArrayAttribute,
+        operation::{OperationLike, OperationRef},
+        Value,
+    },
+    Error,
+};
+
+static MODELS: ModelRegistry<BranchOpModel> = ModelRegistry::new(|| {
+    let branch = BranchOpModel {
+        successor_operands: |operation, index| {
+            if index == 0 {
+                Ok(operation.operands().collect())
+            } else {
+                Err(out_of_bounds(operation, index))
+            }
+        },
+    };
+    let conditional_branch = BranchOpModel {
+        successor_operands: |operation, index| {
+            if index < 2 {
+                operand_segment(operation, index + 1)
+            } else {
+                Err(out_of_bounds(operation, index))
+            }
+        },
+    };
+
+    vec![
+        ("cf.br", branch),
+        ("cf.cond_br", conditional_branch),
+        (
+            "cf.switch",
+            BranchOpModel {
+                successor_operands: |operation, index| {
+                    if index == 0 {
+                        return operand_segment(operation, 1);
+                    }
+
+                    let sizes = DenseI32ArrayAttribute::try_from(
+                        operation.attribute("case_operand_segments")?,
+                    )?;
+
+                    if index > sizes.len() {
+                        return Err(out_of_bounds(operation, index));
+                    }
+
+                    let operands = operand_segment(operation, 2)?;
+                    let start = (0..index - 1)
+                        .map(|index| Ok(sizes.element(index)? as usize))
+                        .sum::<Result<usize, Error>>()?;
+                    let end = start + sizes.element(index - 1)? as usize;
+
+                    Ok(operands
+                        .get(start..end)
+                        .ok_or_else(|| out_of_bounds(operation, index))?
+                        .to_vec())
+                },
+            },
+        ),
+        ("llvm.br", branch),
+        ("llvm.cond_br", conditional_branch),
+    ]
+});
+
+fn out_of_bounds(operation: OperationRef, index: usize) -> Error {
+    Error::PositionOutOfBounds {
+        name: "successor",
+        value: operation.to_string(),
+        index,
+    }
+}
+
+/// A model of `BranchOpInterface`.
+#[derive(Clone, Copy)]
+pub struct BranchOpModel {
+    /// Returns operands passed to a successor at a position.
+    pub successor_operands:
+        for<'c, 'a> fn(OperationRef<'c, 'a>, usize) -> Result<Vec<Value<'c, 'a>>, Error>,
+}
+
+/// A view of an operation as `BranchOpInterface`.
+///
+/// Models of `cf.br`, `cf.cond_br`, `cf.switch`, `llvm.br`, and
+/// `llvm.cond_br` are registered by default.
+#[derive(Clone, Copy)]
+pub struct BranchOpInterface<'c, 'a> {
+    operation: OperationRef<'c, 'a>,
+    model: BranchOpModel,
+}
+
+impl<'c, 'a> BranchOpInterface<'c, 'a> {
+    /// Creates a view of an operation if it has a registered model.
+    pub fn new(operation: OperationRef<'c, 'a>) -> Option<Self> {
+        Some(Self {
+            model: MODELS.get_for(operation)?,
+            operation,
+        })
+    }
+
+    /// Registers a model for an operation name or a dialect namespace in a
+    /// context.
+    pub fn register(context: &Context, name: &str, model: BranchOpModel) {
+        MODELS.register(context, name, model)
+    }
+
+    /// Returns an operation.
+    pub fn operation(&self) -> OperationRef<'c, 'a> {
+        self.operation
+    }
+
+    /// Returns operands passed to a successor at a position.
+    pub fn successor_operands(&self, index: usize) -> Result<Vec<Value<'c, 'a>>, Error> {
+        (self.model.successor_operands)(self.operation, index)
+    }
+}
+
+impl OperationInterface for BranchOpInterface<'_, '_> {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        MODELS.implementation(context, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{BlockLike, Module, RegionLike},
+        test::{create_test_context, find_operation},
+    };
+
+    #[test]
+    fn branch() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%x: index) {
+              cf.br ^bb1(%x : index)
+            ^bb1(%y: index):
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let branch = BranchOpInterface::new(find_operation(&module, "cf.br")).unwrap();
+
+        assert_eq!(branch.successor_operands(0).unwrap().len(), 1);
+        assert!(branch.successor_operands(1).is_err());
+    }
+
+    #[test]
+    fn conditional_branch() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%c: i1, %x: index, %y: index) {
+              cf.cond_br %c, ^bb1(%x, %y : index, index), ^bb2(%y : index)
+            ^bb1(%a: index, %b: index):
+              return
+            ^bb2(%d: index):
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let block = find_operation(&module, "func.func")
+            .region(0)
+            .unwrap()
+            .first_block()
+            .unwrap();
+        let branch = BranchOpInterface::new(find_operation(&module, "cf.cond_br")).unwrap();
+
+        assert_eq!(
+            branch.successor_operands(0).unwrap(),
+            vec![
+                Value::from(block.argument(1).unwrap()),
+                block.argument(2).unwrap().into()
+            ]
+        );
+        assert_eq!(
+            branch.successor_operands(1).unwrap(),
+            vec![Value::from(block.argument(2).unwrap())]
+        );
+        assert!(branch.successor_operands(2).is_err());
+    }
+
+    #[test]
+    fn switch() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%c: i32, %x: index, %y: index) {
+              cf.switch %c : i32, [
+                default: ^bb1(%x : index),
+                42: ^bb2(%x, %y : index, index),
+                43: ^bb1(%y : index)
+              ]
+            ^bb1(%a: index):
+              return
+            ^bb2(%b: index, %d: index):
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let block = find_operation(&module, "func.func")
+            .region(0)
+            .unwrap()
+            .first_block()
+            .unwrap();
+        let x = Value::from(block.argument(1).unwrap());
+        let y = Value::from(block.argument(2).unwrap());
+        let branch = BranchOpInterface::new(find_operation(&module, "cf.switch")).unwrap();
+
+        assert_eq!(branch.successor_operands(0).unwrap(), vec![x]);
+        assert_eq!(branch.successor_operands(1).unwrap(), vec![x, y]);
+        assert_eq!(branch.successor_operands(2).unwrap(), vec![y]);
+        assert!(branch.successor_operands(3).is_err());
+    }
+}
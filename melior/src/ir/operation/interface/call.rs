@@ -0,0 +1,265 @@
+use super::{operand_segment, Implementation, ModelRegistry, OperationInterface};
+use crate::{
+    context::Context,
+    ir::{
+        attribute::FlatSymbolRefAttribute,
+        operation::{OperationLike, OperationRef},
+        Value,
+    },
+};
+
+static MODELS: ModelRegistry<CallOpModel> = ModelRegistry::new(|| {
+    vec![
+        (
+            "func.call",
+            CallOpModel {
+                callee: |operation| {
+                    Some(Callee::Symbol(
+                        operation.attribute("callee").ok()?.try_into().ok()?,
+                    ))
+                },
+                arguments: |operation| operation.operands().collect(),
+            },
+        ),
+        (
+            "func.call_indirect",
+            CallOpModel {
+                callee: |operation| Some(Callee::Value(operation.operand(0).ok()?)),
+                arguments: |operation| operation.operands().skip(1).collect(),
+            },
+        ),
+        (
+            "llvm.call",
+            CallOpModel {
+                callee: |operation| match operation.attribute("callee") {
+                    Ok(attribute) => Some(Callee::Symbol(attribute.try_into().ok()?)),
+                    Err(_) => Some(Callee::Value(operation.operand(0).ok()?)),
+                },
+                arguments: |operation| {
+                    let operands = llvm_callee_operands(operation);
+
+                    if operation.has_attribute("callee") {
+                        operands
+                    } else {
+                        operands.into_iter().skip(1).collect()
+                    }
+                },
+            },
+        ),
+    ]
+});
+
+/// Returns callee operands of an `llvm.call` operation excluding operand
+/// bundles.
+fn llvm_callee_operands<'c, 'a>(operation: OperationRef<'c, 'a>) -> Vec<Value<'c, 'a>> {
+    if operation.has_attribute("operandSegmentSizes") {
+        operand_segment(operation, 0).unwrap_or_default()
+    } else {
+        operation.operands().collect()
+    }
+}
+
+/// A callee of a call operation.
+#[derive(Clone, Copy, Debug)]
+pub enum Callee<'c, 'a> {
+    /// A symbol of a callee function.
+    Symbol(FlatSymbolRefAttribute<'c>),
+    /// A value of a callee function.
+    Value(Value<'c, 'a>),
+}
+
+/// A model of `CallOpInterface`.
+#[derive(Clone, Copy)]
+pub struct CallOpModel {
+    /// Returns a callee.
+    pub callee: for<'c, 'a> fn(OperationRef<'c, 'a>) -> Option<Callee<'c, 'a>>,
+    /// Returns arguments passed to a callee.
+    pub arguments: for<'c, 'a> fn(OperationRef<'c, 'a>) -> Vec<Value<'c, 'a>>,
+}
+
+/// A view of an operation as `CallOpInterface`.
+///
+/// Models of `func.call`, `func.call_indirect`, and `llvm.call` are
+/// registered by default.
+#[derive(Clone, Copy)]
+pub struct CallOpInterface<'c, 'a> {
+    operation: OperationRef<'c, 'a>,
+    model: CallOpModel,
+}
+
+impl<'c, 'a> CallOpInterface<'c, 'a> {
+    /// Creates a view of an operation if it has a registered model.
+    pub fn new(operation: OperationRef<'c, 'a>) -> Option<Self> {
+        Some(Self {
+            model: MODELS.get_for(operation)?,
+            operation,
+        })
+    }
+
+    /// Registers a model for an operation name or a dialect namespace in a
+    /// context.
+    pub fn register(context: &Context, name: &str, model: CallOpModel) {
+        MODELS.register(context, name, model)
+    }
+
+    /// Returns an operation.
+    pub fn operation(&self) -> OperationRef<'c, 'a> {
+        self.operation
+    }
+
+    /// Returns a callee.
+    pub fn callee(&self) -> Option<Callee<'c, 'a>> {
+        (self.model.callee)(self.operation)
+    }
+
+    /// Returns arguments passed to a callee.
+    pub fn arguments(&self) -> Vec<Value<'c, 'a>> {
+        (self.model.arguments)(self.operation)
+    }
+}
+
+impl OperationInterface for CallOpInterface<'_, '_> {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        MODELS.implementation(context, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{BlockLike, Module, RegionLike},
+        test::{create_test_context, find_operation},
+    };
+
+    #[test]
+    fn call() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func private @bar(index) -> index
+
+            func.func @foo(%x: index) -> index {
+              %y = func.call @bar(%x) : (index) -> index
+              return %y : index
+            }
+            "#,
+        )
+        .unwrap();
+        let call = CallOpInterface::new(find_operation(&module, "func.call")).unwrap();
+
+        assert!(matches!(
+            call.callee(),
+            Some(Callee::Symbol(symbol)) if symbol.value() == "bar"
+        ));
+        assert_eq!(call.arguments().len(), 1);
+        assert_eq!(
+            CallOpInterface::implementation(&context, "func.call"),
+            Implementation::Implemented
+        );
+        assert_eq!(
+            CallOpInterface::implementation(&context, "func.return"),
+            Implementation::Unknown
+        );
+        assert!(CallOpInterface::new(find_operation(&module, "func.return")).is_none());
+        assert!(find_operation(&module, "func.call").implements::<CallOpInterface>());
+    }
+
+    #[test]
+    fn call_indirect() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%f: (index) -> index, %x: index) -> index {
+              %y = func.call_indirect %f(%x) : (index) -> index
+              return %y : index
+            }
+            "#,
+        )
+        .unwrap();
+        let call = CallOpInterface::new(find_operation(&module, "func.call_indirect")).unwrap();
+        let function = find_operation(&module, "func.func");
+        let block = function.region(0).unwrap().first_block().unwrap();
+
+        assert!(matches!(
+            call.callee(),
+            Some(Callee::Value(value)) if value == Value::from(block.argument(0).unwrap())
+        ));
+        assert_eq!(
+            call.arguments(),
+            vec![Value::from(block.argument(1).unwrap())]
+        );
+    }
+
+    #[test]
+    fn llvm_call() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            llvm.func @bar(i64) -> i64
+
+            llvm.func @foo(%x: i64) -> i64 {
+              %y = llvm.call @bar(%x) : (i64) -> i64
+              llvm.return %y : i64
+            }
+            "#,
+        )
+        .unwrap();
+        let call = CallOpInterface::new(find_operation(&module, "llvm.call")).unwrap();
+
+        assert!(matches!(
+            call.callee(),
+            Some(Callee::Symbol(symbol)) if symbol.value() == "bar"
+        ));
+        assert_eq!(call.arguments().len(), 1);
+    }
+
+    #[test]
+    fn register_model() {
+        let context = create_test_context();
+        context.set_allow_unregistered_dialects(true);
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%x: index) {
+              "foo.invoke"(%x) {callee = @bar} : (index) -> ()
+              return
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert!(CallOpInterface::new(find_operation(&module, "foo.invoke")).is_none());
+
+        CallOpInterface::register(
+            &context,
+            "foo.invoke",
+            CallOpModel {
+                callee: |operation| {
+                    Some(Callee::Symbol(
+                        operation.attribute("callee").ok()?.try_into().ok()?,
+                    ))
+                },
+                arguments: |operation| operation.operands().collect(),
+            },
+        );
+
+        let call = CallOpInterface::new(find_operation(&module, "foo.invoke")).unwrap();
+
+        assert!(matches!(
+            call.callee(),
+            Some(Callee::Symbol(symbol)) if symbol.value() == "bar"
+        ));
+        assert_eq!(call.arguments().len(), 1);
+        assert!(CallOpInterface::new(find_operation(&module, "foo.invoke")).is_some());
+
+        let other_context = create_test_context();
+        other_context.set_allow_unregistered_dialects(true);
+        let module = Module::parse(&other_context, &module.as_operation().to_string()).unwrap();
+
+        assert!(CallOpInterface::new(find_operation(&module, "foo.invoke")).is_none());
+    }
+}
@@ -0,0 +1,242 @@
+use super::{Implementation, ModelRegistry, OperationInterface};
+use crate::{
+    context::Context,
+    ir::{
+        attribute::TypeAttribute,
+        operation::{OperationLike, OperationRef},
+        r#type::FunctionType,
+        RegionLike, RegionRef, Type, TypeLike,
+    },
+};
+use mlir_sys::{
+    mlirLLVMFunctionTypeGetInput, mlirLLVMFunctionTypeGetNumInputs,
+    mlirLLVMFunctionTypeGetReturnType,
+};
+
+const LLVM_VOID_TYPE: &str = "!llvm.void";
+
+static MODELS: ModelRegistry<CallableOpModel> = ModelRegistry::new(|| {
+    vec![
+        (
+            "func.func",
+            CallableOpModel {
+                callable_region,
+                argument_types: |operation| {
+                    function_type(operation)
+                        .map(|r#type| {
+                            (0..r#type.input_count())
+                                .flat_map(|index| r#type.input(index))
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                },
+                result_types: |operation| {
+                    function_type(operation)
+                        .map(|r#type| {
+                            (0..r#type.result_count())
+                                .flat_map(|index| r#type.result(index))
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                },
+            },
+        ),
+        (
+            "llvm.func",
+            CallableOpModel {
+                callable_region,
+                argument_types: |operation| {
+                    let Some(r#type) = llvm_function_type(operation) else {
+                        return vec![];
+                    };
+
+                    unsafe {
+                        (0..mlirLLVMFunctionTypeGetNumInputs(r#type.to_raw()))
+                            .map(|index| {
+                                Type::from_raw(mlirLLVMFunctionTypeGetInput(r#type.to_raw(), index))
+                            })
+                            .collect()
+                    }
+                },
+                result_types: |operation| {
+                    let Some(r#type) = llvm_function_type(operation) else {
+                        return vec![];
+                    };
+                    let result = unsafe {
+                        Type::from_raw(mlirLLVMFunctionTypeGetReturnType(r#type.to_raw()))
+                    };
+
+                    if result.to_string() == LLVM_VOID_TYPE {
+                        vec![]
+                    } else {
+                        vec![result]
+                    }
+                },
+            },
+        ),
+    ]
+});
+
+fn callable_region<'c, 'a>(operation: OperationRef<'c, 'a>) -> Option<RegionRef<'c, 'a>> {
+    let region = operation.region(0).ok()?;
+
+    region.first_block().map(|_| region)
+}
+
+fn function_type<'c>(operation: OperationRef<'c, '_>) -> Option<FunctionType<'c>> {
+    TypeAttribute::try_from(operation.attribute("function_type").ok()?)
+        .ok()?
+        .value()
+        .try_into()
+        .ok()
+}
+
+fn llvm_function_type<'c>(operation: OperationRef<'c, '_>) -> Option<Type<'c>> {
+    Some(
+        TypeAttribute::try_from(operation.attribute("function_type").ok()?)
+            .ok()?
+            .value(),
+    )
+}
+
+/// A model of `CallableOpInterface`.
+#[derive(Clone, Copy)]
+pub struct CallableOpModel {
+    /// Returns a region of a callable body, or `None` for an external
+    /// callable.
+    pub callable_region: for<'c, 'a> fn(OperationRef<'c, 'a>) -> Option<RegionRef<'c, 'a>>,
+    /// Returns argument types.
+    pub argument_types: for<'c> fn(OperationRef<'c, '_>) -> Vec<Type<'c>>,
+    /// Returns result types.
+    pub result_types: for<'c> fn(OperationRef<'c, '_>) -> Vec<Type<'c>>,
+}
+
+/// A view of an operation as `CallableOpInterface`.
+///
+/// Models of `func.func` and `llvm.func` are registered by default.
+#[derive(Clone, Copy)]
+pub struct CallableOpInterface<'c, 'a> {
+    operation: OperationRef<'c, 'a>,
+    model: CallableOpModel,
+}
+
+impl<'c, 'a> CallableOpInterface<'c, 'a> {
+    /// Creates a view of an operation if it has a registered model.
+    pub fn new(operation: OperationRef<'c, 'a>) -> Option<Self> {
+        Some(Self {
+            model: MODELS.get_for(operation)?,
+            operation,
+        })
+    }
+
+    /// Registers a model for an operation name or a dialect namespace in a
+    /// context.
+    pub fn register(context: &Context, name: &str, model: CallableOpModel) {
+        MODELS.register(context, name, model)
+    }
+
+    /// Returns an operation.
+    pub fn operation(&self) -> OperationRef<'c, 'a> {
+        self.operation
+    }
+
+    /// Returns a region of a callable body, or `None` for an external
+    /// callable.
+    pub fn callable_region(&self) -> Option<RegionRef<'c, 'a>> {
+        (self.model.callable_region)(self.operation)
+    }
+
+    /// Returns argument types.
+    pub fn argument_types(&self) -> Vec<Type<'c>> {
+        (self.model.argument_types)(self.operation)
+    }
+
+    /// Returns result types.
+    pub fn result_types(&self) -> Vec<Type<'c>> {
+        (self.model.result_types)(self.operation)
+    }
+}
+
+impl OperationInterface for CallableOpInterface<'_, '_> {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        MODELS.implementation(context, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{r#type::IntegerType, BlockLike, Module},
+        test::{create_test_context, find_operation},
+    };
+
+    #[test]
+    fn func() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%x: index, %y: i64) -> index {
+              return %x : index
+            }
+            "#,
+        )
+        .unwrap();
+        let callable = CallableOpInterface::new(find_operation(&module, "func.func")).unwrap();
+
+        assert!(callable.callable_region().is_some());
+        assert_eq!(
+            callable.argument_types(),
+            vec![Type::index(&context), IntegerType::new(&context, 64).into()]
+        );
+        assert_eq!(callable.result_types(), vec![Type::index(&context)]);
+    }
+
+    #[test]
+    fn external_func() {
+        let context = create_test_context();
+        let module = Module::parse(&context, "func.func private @foo(index)").unwrap();
+        let callable = CallableOpInterface::new(find_operation(&module, "func.func")).unwrap();
+
+        assert!(callable.callable_region().is_none());
+        assert_eq!(callable.argument_types(), vec![Type::index(&context)]);
+        assert_eq!(callable.result_types(), vec![]);
+    }
+
+    #[test]
+    fn llvm_func() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            llvm.func @foo(%x: i64) -> i64 {
+              llvm.return %x : i64
+            }
+
+            llvm.func @bar(i64)
+            "#,
+        )
+        .unwrap();
+        let callable = CallableOpInterface::new(find_operation(&module, "llvm.func")).unwrap();
+        let r#type = Type::from(IntegerType::new(&context, 64));
+
+        assert!(callable.callable_region().is_some());
+        assert_eq!(callable.argument_types(), vec![r#type]);
+        assert_eq!(callable.result_types(), vec![r#type]);
+
+        let callable = CallableOpInterface::new(
+            module
+                .body()
+                .first_operation()
+                .unwrap()
+                .next_in_block()
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert!(callable.callable_region().is_none());
+        assert_eq!(callable.argument_types(), vec![r#type]);
+        assert_eq!(callable.result_types(), vec![]);
+    }
+}
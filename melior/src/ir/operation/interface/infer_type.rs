@@ -0,0 +1,190 @@
+use super::{Implementation, OperationInterface};
+use crate::{
+    context::Context,
+    ir::{r#type::TypeId, Attribute, AttributeLike, Location, Region, Type, Value},
+    string_ref::StringRef,
+    Error,
+};
+use mlir_sys::{
+    mlirInferShapedTypeOpInterfaceTypeID, mlirInferTypeOpInterfaceInferReturnTypes,
+    mlirInferTypeOpInterfaceTypeID, mlirOperationImplementsInterfaceStatic, MlirType,
+};
+use std::{ffi::c_void, ptr::null_mut, slice};
+
+/// An `InferTypeOpInterface` interface.
+///
+/// Operations implementing this interface can infer their result types from
+/// their operands, attributes, and regions.
+#[derive(Clone, Copy, Debug)]
+pub struct InferTypeOpInterface;
+
+impl InferTypeOpInterface {
+    /// Returns a type ID of the interface.
+    pub fn type_id() -> TypeId<'static> {
+        unsafe { TypeId::from_raw(mlirInferTypeOpInterfaceTypeID()) }
+    }
+
+    /// Infers result types of an operation.
+    pub fn infer_return_types<'c>(
+        context: &'c Context,
+        name: &str,
+        location: Location<'c>,
+        operands: &[Value<'c, '_>],
+        attributes: Option<Attribute<'c>>,
+        regions: &[Region<'c>],
+    ) -> Result<Vec<Type<'c>>, Error> {
+        unsafe extern "C" fn callback(count: isize, types: *mut MlirType, data: *mut c_void) {
+            let data = &mut *(data as *mut Vec<MlirType>);
+
+            if count > 0 {
+                data.extend_from_slice(slice::from_raw_parts(types, count as usize));
+            }
+        }
+
+        let attributes = match attributes {
+            Some(attributes) => attributes,
+            None => unsafe { Attribute::null() },
+        };
+        let mut types = Vec::<MlirType>::new();
+
+        let result = unsafe {
+            mlirInferTypeOpInterfaceInferReturnTypes(
+                StringRef::new(name).to_raw(),
+                context.to_raw(),
+                location.to_raw(),
+                operands.len() as isize,
+                operands.as_ptr() as *mut _,
+                attributes.to_raw(),
+                null_mut(),
+                regions.len() as isize,
+                regions.as_ptr() as *mut _,
+                Some(callback),
+                &mut types as *mut _ as *mut c_void,
+            )
+        };
+
+        if result.value == 0 {
+            Err(Error::InferReturnTypes(name.into()))
+        } else {
+            Ok(types
+                .into_iter()
+                .map(|r#type| unsafe { Type::from_raw(r#type) })
+                .collect())
+        }
+    }
+}
+
+impl OperationInterface for InferTypeOpInterface {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        implementation(context, name, Self::type_id())
+    }
+}
+
+/// An `InferShapedTypeOpInterface` interface.
+///
+/// Operations implementing this interface can infer shapes of their results.
+#[derive(Clone, Copy, Debug)]
+pub struct InferShapedTypeOpInterface;
+
+impl InferShapedTypeOpInterface {
+    /// Returns a type ID of the interface.
+    pub fn type_id() -> TypeId<'static> {
+        unsafe { TypeId::from_raw(mlirInferShapedTypeOpInterfaceTypeID()) }
+    }
+}
+
+impl OperationInterface for InferShapedTypeOpInterface {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        implementation(context, name, Self::type_id())
+    }
+}
+
+fn implementation(context: &Context, name: &str, id: TypeId) -> Implementation {
+    if !context.is_registered_operation(name) {
+        Implementation::Unknown
+    } else if unsafe {
+        mlirOperationImplementsInterfaceStatic(
+            StringRef::new(name).to_raw(),
+            context.to_raw(),
+            id.to_raw(),
+        )
+    } {
+        Implementation::Implemented
+    } else {
+        Implementation::NotImplemented
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dialect::arith,
+        ir::{operation::OperationLike, Block, BlockLike},
+        test::create_test_context,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn infer_return_types() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+
+        assert_eq!(
+            InferTypeOpInterface::infer_return_types(
+                &context,
+                "arith.addi",
+                location,
+                &[
+                    block.argument(0).unwrap().into(),
+                    block.argument(1).unwrap().into()
+                ],
+                None,
+                &[],
+            )
+            .unwrap(),
+            vec![r#type]
+        );
+    }
+
+    #[test]
+    fn implements() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let r#type = Type::index(&context);
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+
+        let operation = block.append_operation(arith::addi(
+            block.argument(0).unwrap().into(),
+            block.argument(1).unwrap().into(),
+            location,
+        ));
+
+        assert!(operation.implements::<InferTypeOpInterface>());
+        assert!(!operation.implements::<InferShapedTypeOpInterface>());
+    }
+
+    #[test]
+    fn implemented_by() {
+        let context = create_test_context();
+
+        assert_eq!(
+            InferTypeOpInterface::implementation(&context, "arith.addi"),
+            Implementation::Implemented
+        );
+        assert_eq!(
+            InferTypeOpInterface::implementation(&context, "memref.store"),
+            Implementation::NotImplemented
+        );
+        assert_eq!(
+            InferShapedTypeOpInterface::implementation(&context, "arith.addi"),
+            Implementation::NotImplemented
+        );
+        assert_eq!(
+            InferTypeOpInterface::implementation(&context, "foo.bar"),
+            Implementation::Unknown
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use super::{operand_segment, Implementation, ModelRegistry, OperationInterface};
+use crate::{
+    context::Context,
+    ir::{
+        operation::{OperationLike, OperationRef},
+        BlockLike, RegionLike, RegionRef, Value,
+    },
+};
+
+static MODELS: ModelRegistry<LoopLikeOpModel> = ModelRegistry::new(|| {
+    vec![
+        (
+            "affine.for",
+            LoopLikeOpModel {
+                loop_regions: |operation| operation.regions().collect(),
+                induction_variables: |operation| block_arguments(operation, 1),
+                bounds: |_| None,
+            },
+        ),
+        (
+            "scf.for",
+            LoopLikeOpModel {
+                loop_regions: |operation| operation.regions().collect(),
+                induction_variables: |operation| block_arguments(operation, 1),
+                bounds: |operation| {
+                    Some(LoopBounds {
+                        lower: vec![operation.operand(0).ok()?],
+                        upper: vec![operation.operand(1).ok()?],
+                        steps: vec![operation.operand(2).ok()?],
+                    })
+                },
+            },
+        ),
+        (
+            "scf.parallel",
+            LoopLikeOpModel {
+                loop_regions: |operation| operation.regions().collect(),
+                induction_variables: |operation| {
+                    block_arguments(operation, operand_segment(operation, 0).ok()?.len())
+                },
+                bounds: |operation| {
+                    Some(LoopBounds {
+                        lower: operand_segment(operation, 0).ok()?,
+                        upper: operand_segment(operation, 1).ok()?,
+                        steps: operand_segment(operation, 2).ok()?,
+                    })
+                },
+            },
+        ),
+        (
+            "scf.while",
+            LoopLikeOpModel {
+                loop_regions: |operation| operation.regions().collect(),
+                induction_variables: |_| None,
+                bounds: |_| None,
+            },
+        ),
+    ]
+});
+
+fn block_arguments<'c, 'a>(
+    operation: OperationRef<'c, 'a>,
+    count: usize,
+) -> Option<Vec<Value<'c, 'a>>> {
+    let block = operation.region(0).ok()?.first_block()?;
+
+    (0..count)
+        .map(|index| Some(block.argument(index).ok()?.into()))
+        .collect()
+}
+
+/// Bounds of a loop.
+#[derive(Clone, Debug)]
+pub struct LoopBounds<'c, 'a> {
+    /// Lower bounds of induction variables.
+    pub lower: Vec<Value<'c, 'a>>,
+    /// Upper bounds of induction variables.
+    pub upper: Vec<Value<'c, 'a>>,
+    /// Steps of induction variables.
+    pub steps: Vec<Value<'c, 'a>>,
+}
+
+/// A model of `LoopLikeOpInterface`.
+#[derive(Clone, Copy)]
+pub struct LoopLikeOpModel {
+    /// Returns regions of a loop body.
+    pub loop_regions: for<'c, 'a> fn(OperationRef<'c, 'a>) -> Vec<RegionRef<'c, 'a>>,
+    /// Returns induction variables, or `None` if a loop has none.
+    pub induction_variables: for<'c, 'a> fn(OperationRef<'c, 'a>) -> Option<Vec<Value<'c, 'a>>>,
+    /// Returns bounds, or `None` if they are not values.
+    pub bounds: for<'c, 'a> fn(OperationRef<'c, 'a>) -> Option<LoopBounds<'c, 'a>>,
+}
+
+/// A view of an operation as `LoopLikeOpInterface`.
+///
+/// Models of `affine.for`, `scf.for`, `scf.parallel`, and `scf.while` are
+/// registered by default.
+#[derive(Clone, Copy)]
+pub struct LoopLikeOpInterface<'c, 'a> {
+    operation: OperationRef<'c, 'a>,
+    model: LoopLikeOpModel,
+}
+
+impl<'c, 'a> LoopLikeOpInterface<'c, 'a> {
+    /// Creates a view of an operation if it has a registered model.
+    pub fn new(operation: OperationRef<'c, 'a>) -> Option<Self> {
+        Some(Self {
+            model: MODELS.get_for(operation)?,
+            operation,
+        })
+    }
+
+    /// Registers a model for an operation name or a dialect namespace in a
+    /// context.
+    pub fn register(context: &Context, name: &str, model: LoopLikeOpModel) {
+        MODELS.register(context, name, model)
+    }
+
+    /// Returns an operation.
+    pub fn operation(&self) -> OperationRef<'c, 'a> {
+        self.operation
+    }
+
+    /// Returns regions of a loop body.
+    pub fn loop_regions(&self) -> Vec<RegionRef<'c, 'a>> {
+        (self.model.loop_regions)(self.operation)
+    }
+
+    /// Returns induction variables, or `None` if a loop has none.
+    pub fn induction_variables(&self) -> Option<Vec<Value<'c, 'a>>> {
+        (self.model.induction_variables)(self.operation)
+    }
+
+    /// Returns bounds, or `None` if they are not values.
+    pub fn bounds(&self) -> Option<LoopBounds<'c, 'a>> {
+        (self.model.bounds)(self.operation)
+    }
+}
+
+impl OperationInterface for LoopLikeOpInterface<'_, '_> {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        MODELS.implementation(context, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::Module,
+        test::{create_test_context, find_operation},
+    };
+
+    #[test]
+    fn r#for() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%l: index, %u: index, %s: index) {
+              scf.for %i = %l to %u step %s {
+              }
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let block = find_operation(&module, "func.func")
+            .region(0)
+            .unwrap()
+            .first_block()
+            .unwrap();
+        let operation = find_operation(&module, "scf.for");
+        let r#loop = LoopLikeOpInterface::new(operation).unwrap();
+        let bounds = r#loop.bounds().unwrap();
+
+        assert_eq!(r#loop.loop_regions(), vec![operation.region(0).unwrap()]);
+        assert_eq!(
+            r#loop.induction_variables(),
+            Some(vec![Value::from(
+                operation
+                    .region(0)
+                    .unwrap()
+                    .first_block()
+                    .unwrap()
+                    .argument(0)
+                    .unwrap()
+            )])
+        );
+        assert_eq!(bounds.lower, vec![Value::from(block.argument(0).unwrap())]);
+        assert_eq!(bounds.upper, vec![Value::from(block.argument(1).unwrap())]);
+        assert_eq!(bounds.steps, vec![Value::from(block.argument(2).unwrap())]);
+    }
+
+    #[test]
+    fn parallel() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%l: index, %u: index, %s: index) {
+              scf.parallel (%i, %j) = (%l, %l) to (%u, %u) step (%s, %s) {
+              }
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let r#loop = LoopLikeOpInterface::new(find_operation(&module, "scf.parallel")).unwrap();
+        let bounds = r#loop.bounds().unwrap();
+
+        assert_eq!(r#loop.induction_variables().unwrap().len(), 2);
+        assert_eq!(bounds.lower.len(), 2);
+        assert_eq!(bounds.upper.len(), 2);
+        assert_eq!(bounds.steps.len(), 2);
+    }
+
+    #[test]
+    fn r#while() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%c: i1) {
+              scf.while : () -> () {
+                scf.condition(%c)
+              } do {
+                scf.yield
+              }
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let r#loop = LoopLikeOpInterface::new(find_operation(&module, "scf.while")).unwrap();
+
+        assert_eq!(r#loop.loop_regions().len(), 2);
+        assert_eq!(r#loop.induction_variables(), None);
+        assert!(r#loop.bounds().is_none());
+    }
+}
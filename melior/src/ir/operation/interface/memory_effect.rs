@@ -0,0 +1,282 @@
+use super::{Implementation, ModelRegistry, OperationInterface};
+use crate::{
+    context::Context,
+    ir::{
+        operation::{OperationLike, OperationRef},
+        Value,
+    },
+};
+
+static MODELS: ModelRegistry<MemoryEffectOpModel> = ModelRegistry::new(|| {
+    let pure = MemoryEffectOpModel {
+        effects: |_| vec![],
+    };
+    let load = MemoryEffectOpModel {
+        effects: |operation| operand_effects(operation, &[(MemoryEffectKind::Read, 0)]),
+    };
+    let store = MemoryEffectOpModel {
+        effects: |operation| operand_effects(operation, &[(MemoryEffectKind::Write, 1)]),
+    };
+    let allocate = MemoryEffectOpModel {
+        effects: |operation| {
+            vec![MemoryEffect::new(
+                MemoryEffectKind::Allocate,
+                operation.result(0).ok().map(Value::from),
+            )]
+        },
+    };
+
+    vec![
+        // Operations in these dialects are approximated as pure without
+        // checking their names. Register models for operations with memory
+        // effects in a context to override them.
+        ("arith", pure),
+        ("index", pure),
+        ("math", pure),
+        ("cf.br", pure),
+        ("cf.cond_br", pure),
+        ("func.return", pure),
+        ("scf.yield", pure),
+        ("memref.alloc", allocate),
+        ("memref.alloca", allocate),
+        ("memref.cast", pure),
+        (
+            "memref.copy",
+            MemoryEffectOpModel {
+                effects: |operation| {
+                    operand_effects(
+                        operation,
+                        &[(MemoryEffectKind::Read, 0), (MemoryEffectKind::Write, 1)],
+                    )
+                },
+            },
+        ),
+        (
+            "memref.dealloc",
+            MemoryEffectOpModel {
+                effects: |operation| operand_effects(operation, &[(MemoryEffectKind::Free, 0)]),
+            },
+        ),
+        ("memref.dim", pure),
+        ("memref.load", load),
+        ("memref.store", store),
+        ("llvm.load", load),
+        ("llvm.store", store),
+    ]
+});
+
+fn operand_effects<'c, 'a>(
+    operation: OperationRef<'c, 'a>,
+    effects: &[(MemoryEffectKind, usize)],
+) -> Vec<MemoryEffect<'c, 'a>> {
+    effects
+        .iter()
+        .map(|&(kind, index)| MemoryEffect::new(kind, operation.operand(index).ok()))
+        .collect()
+}
+
+/// A kind of memory effects.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MemoryEffectKind {
+    /// An allocation of a resource.
+    Allocate,
+    /// A release of a resource.
+    Free,
+    /// A read from a resource.
+    Read,
+    /// A write to a resource.
+    Write,
+}
+
+/// A memory effect of an operation.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryEffect<'c, 'a> {
+    kind: MemoryEffectKind,
+    value: Option<Value<'c, 'a>>,
+}
+
+impl<'c, 'a> MemoryEffect<'c, 'a> {
+    /// Creates a memory effect on a value, or on an unknown resource if the
+    /// value is `None`.
+    pub const fn new(kind: MemoryEffectKind, value: Option<Value<'c, 'a>>) -> Self {
+        Self { kind, value }
+    }
+
+    /// Returns a kind.
+    pub const fn kind(&self) -> MemoryEffectKind {
+        self.kind
+    }
+
+    /// Returns a value the effect is on.
+    pub const fn value(&self) -> Option<Value<'c, 'a>> {
+        self.value
+    }
+}
+
+/// A model of `MemoryEffectOpInterface`.
+#[derive(Clone, Copy)]
+pub struct MemoryEffectOpModel {
+    /// Returns memory effects.
+    pub effects: for<'c, 'a> fn(OperationRef<'c, 'a>) -> Vec<MemoryEffect<'c, 'a>>,
+}
+
+/// A view of an operation as `MemoryEffectOpInterface`.
+///
+/// Models of common operations of the `cf`, `func`, `llvm`, `memref`, and
+/// `scf` dialects are registered by default. All operations of the `arith`,
+/// `index`, and `math` dialects are also approximated as effect-free by
+/// dialect-wide models, which hold for their upstream operations but are not
+/// derived from their definitions.
+#[derive(Clone, Copy)]
+pub struct MemoryEffectOpInterface<'c, 'a> {
+    operation: OperationRef<'c, 'a>,
+    model: MemoryEffectOpModel,
+}
+
+impl<'c, 'a> MemoryEffectOpInterface<'c, 'a> {
+    /// Creates a view of an operation if it has a registered model.
+    pub fn new(operation: OperationRef<'c, 'a>) -> Option<Self> {
+        Some(Self {
+            model: MODELS.get_for(operation)?,
+            operation,
+        })
+    }
+
+    /// Registers a model for an operation name or a dialect namespace in a
+    /// context.
+    pub fn register(context: &Context, name: &str, model: MemoryEffectOpModel) {
+        MODELS.register(context, name, model)
+    }
+
+    /// Returns an operation.
+    pub fn operation(&self) -> OperationRef<'c, 'a> {
+        self.operation
+    }
+
+    /// Returns memory effects.
+    pub fn effects(&self) -> Vec<MemoryEffect<'c, 'a>> {
+        (self.model.effects)(self.operation)
+    }
+
+    /// Returns memory effects on a value.
+    pub fn effects_on(&self, value: Value<'c, 'a>) -> Vec<MemoryEffect<'c, 'a>> {
+        self.effects()
+            .into_iter()
+            .filter(|effect| effect.value() == Some(value))
+            .collect()
+    }
+
+    /// Returns `true` if an operation has no memory effect.
+    pub fn is_effect_free(&self) -> bool {
+        self.effects().is_empty()
+    }
+}
+
+impl OperationInterface for MemoryEffectOpInterface<'_, '_> {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        MODELS.implementation(context, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{BlockLike, Module, RegionLike},
+        test::{create_test_context, find_operation},
+    };
+
+    #[test]
+    fn load_and_store() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%x: memref<4xf32>, %y: memref<4xf32>, %i: index) {
+              %v = memref.load %x[%i] : memref<4xf32>
+              memref.store %v, %y[%i] : memref<4xf32>
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let block = find_operation(&module, "func.func")
+            .region(0)
+            .unwrap()
+            .first_block()
+            .unwrap();
+        let x = Value::from(block.argument(0).unwrap());
+        let y = Value::from(block.argument(1).unwrap());
+
+        let load = MemoryEffectOpInterface::new(find_operation(&module, "memref.load")).unwrap();
+        let effects = load.effects();
+
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].kind(), MemoryEffectKind::Read);
+        assert_eq!(effects[0].value(), Some(x));
+        assert_eq!(load.effects_on(y).len(), 0);
+
+        let store = MemoryEffectOpInterface::new(find_operation(&module, "memref.store")).unwrap();
+
+        assert_eq!(store.effects_on(x).len(), 0);
+        assert_eq!(store.effects_on(y)[0].kind(), MemoryEffectKind::Write);
+        assert!(!store.is_effect_free());
+    }
+
+    #[test]
+    fn allocate_and_free() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo() {
+              %x = memref.alloc() : memref<4xf32>
+              memref.dealloc %x : memref<4xf32>
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let allocation = find_operation(&module, "memref.alloc");
+        let x = Value::from(allocation.result(0).unwrap());
+
+        let effects = MemoryEffectOpInterface::new(allocation).unwrap().effects();
+
+        assert_eq!(effects[0].kind(), MemoryEffectKind::Allocate);
+        assert_eq!(effects[0].value(), Some(x));
+
+        let effects = MemoryEffectOpInterface::new(find_operation(&module, "memref.dealloc"))
+            .unwrap()
+            .effects();
+
+        assert_eq!(effects[0].kind(), MemoryEffectKind::Free);
+        assert_eq!(effects[0].value(), Some(x));
+    }
+
+    #[test]
+    fn effect_free() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%x: index) -> index {
+              %y = arith.addi %x, %x : index
+              return %y : index
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert!(
+            MemoryEffectOpInterface::new(find_operation(&module, "arith.addi"))
+                .unwrap()
+                .is_effect_free()
+        );
+        assert!(
+            MemoryEffectOpInterface::new(find_operation(&module, "func.return"))
+                .unwrap()
+                .is_effect_free()
+        );
+        assert!(MemoryEffectOpInterface::new(find_operation(&module, "func.func")).is_none());
+    }
+}
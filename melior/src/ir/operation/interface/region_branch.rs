@@ -0,0 +1,202 @@
+use super::{Implementation, ModelRegistry, OperationInterface};
+use crate::{
+    context::Context,
+    ir::{
+        operation::{OperationLike, OperationRef},
+        RegionLike,
+    },
+};
+
+static MODELS: ModelRegistry<RegionBranchOpModel> = ModelRegistry::new(|| {
+    let conditional = RegionBranchOpModel {
+        successor_regions: |operation, point| match point {
+            RegionBranchPoint::Parent => {
+                let has_else = operation
+                    .region(1)
+                    .is_ok_and(|region| region.first_block().is_some());
+
+                vec![
+                    RegionBranchPoint::Region(0),
+                    if has_else {
+                        RegionBranchPoint::Region(1)
+                    } else {
+                        RegionBranchPoint::Parent
+                    },
+                ]
+            }
+            RegionBranchPoint::Region(_) => vec![RegionBranchPoint::Parent],
+        },
+    };
+    let r#loop = RegionBranchOpModel {
+        successor_regions: |_, _| vec![RegionBranchPoint::Region(0), RegionBranchPoint::Parent],
+    };
+
+    vec![
+        ("affine.for", r#loop),
+        ("affine.if", conditional),
+        (
+            "scf.execute_region",
+            RegionBranchOpModel {
+                successor_regions: |_, point| match point {
+                    RegionBranchPoint::Parent => vec![RegionBranchPoint::Region(0)],
+                    RegionBranchPoint::Region(_) => vec![RegionBranchPoint::Parent],
+                },
+            },
+        ),
+        ("scf.for", r#loop),
+        ("scf.if", conditional),
+        ("scf.parallel", r#loop),
+        (
+            "scf.while",
+            RegionBranchOpModel {
+                successor_regions: |_, point| match point {
+                    RegionBranchPoint::Parent | RegionBranchPoint::Region(1) => {
+                        vec![RegionBranchPoint::Region(0)]
+                    }
+                    RegionBranchPoint::Region(_) => {
+                        vec![RegionBranchPoint::Region(1), RegionBranchPoint::Parent]
+                    }
+                },
+            },
+        ),
+    ]
+});
+
+/// A point of control flow between an operation and its regions.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegionBranchPoint {
+    /// An operation itself, which is entered from or returned to.
+    Parent,
+    /// A region of an operation at a position.
+    Region(usize),
+}
+
+/// A model of `RegionBranchOpInterface`.
+#[derive(Clone, Copy)]
+pub struct RegionBranchOpModel {
+    /// Returns points that control can flow to from a point.
+    pub successor_regions: fn(OperationRef, RegionBranchPoint) -> Vec<RegionBranchPoint>,
+}
+
+/// A view of an operation as `RegionBranchOpInterface`.
+///
+/// Models of `affine.for`, `affine.if`, `scf.execute_region`, `scf.for`,
+/// `scf.if`, `scf.parallel`, and `scf.while` are registered by default.
+#[derive(Clone, Copy)]
+pub struct RegionBranchOpInterface<'c, 'a> {
+    operation: OperationRef<'c, 'a>,
+    model: RegionBranchOpModel,
+}
+
+impl<'c, 'a> RegionBranchOpInterface<'c, 'a> {
+    /// Creates a view of an operation if it has a registered model.
+    pub fn new(operation: OperationRef<'c, 'a>) -> Option<Self> {
+        Some(Self {
+            model: MODELS.get_for(operation)?,
+            operation,
+        })
+    }
+
+    /// Registers a model for an operation name or a dialect namespace in a
+    /// context.
+    pub fn register(context: &Context, name: &str, model: RegionBranchOpModel) {
+        MODELS.register(context, name, model)
+    }
+
+    /// Returns an operation.
+    pub fn operation(&self) -> OperationRef<'c, 'a> {
+        self.operation
+    }
+
+    /// Returns points that control can flow to from a point.
+    pub fn successor_regions(&self, point: RegionBranchPoint) -> Vec<RegionBranchPoint> {
+        (self.model.successor_regions)(self.operation, point)
+    }
+}
+
+impl OperationInterface for RegionBranchOpInterface<'_, '_> {
+    fn implementation(context: &Context, name: &str) -> Implementation {
+        MODELS.implementation(context, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::Module,
+        test::{create_test_context, find_operation},
+    };
+    use RegionBranchPoint::{Parent, Region};
+
+    #[test]
+    fn r#if() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%c: i1) {
+              scf.if %c {
+              }
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let operation = RegionBranchOpInterface::new(find_operation(&module, "scf.if")).unwrap();
+
+        assert_eq!(operation.successor_regions(Parent), vec![Region(0), Parent]);
+        assert_eq!(operation.successor_regions(Region(0)), vec![Parent]);
+    }
+
+    #[test]
+    fn if_else() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%c: i1) {
+              scf.if %c {
+              } else {
+              }
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let operation = RegionBranchOpInterface::new(find_operation(&module, "scf.if")).unwrap();
+
+        assert_eq!(
+            operation.successor_regions(Parent),
+            vec![Region(0), Region(1)]
+        );
+        assert_eq!(operation.successor_regions(Region(1)), vec![Parent]);
+    }
+
+    #[test]
+    fn r#while() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            r#"
+            func.func @foo(%c: i1) {
+              scf.while : () -> () {
+                scf.condition(%c)
+              } do {
+                scf.yield
+              }
+              return
+            }
+            "#,
+        )
+        .unwrap();
+        let operation = RegionBranchOpInterface::new(find_operation(&module, "scf.while")).unwrap();
+
+        assert_eq!(operation.successor_regions(Parent), vec![Region(0)]);
+        assert_eq!(
+            operation.successor_regions(Region(0)),
+            vec![Region(1), Parent]
+        );
+        assert_eq!(operation.successor_regions(Region(1)), vec![Region(0)]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{ffi::c_void, fmt::Display};
+use std::{borrow::Borrow, ffi::c_void, fmt::Display};
 
 use mlir_sys::{
     mlirOperationDump, mlirOperationGetAttribute, mlirOperationGetAttributeByName,
@@ -6,8 +6,8 @@ use mlir_sys::{
     mlirOperationGetNextInBlock, mlirOperationGetNumAttributes, mlirOperationGetNumOperands,
     mlirOperationGetNumRegions, mlirOperationGetNumResults, mlirOperationGetNumSuccessors,
     mlirOperationGetOperand, mlirOperationGetParentOperation, mlirOperationGetRegion,
-    mlirOperationGetResult, mlirOperationGetSuccessor, mlirOperationPrintWithFlags,
-    mlirOperationRemoveAttributeByName, mlirOperationRemoveFromParent,
+    mlirOperationGetResult, mlirOperationGetSuccessor, mlirOperationImplementsInterface,
+    mlirOperationPrintWithFlags, mlirOperationRemoveAttributeByName, mlirOperationRemoveFromParent,
     mlirOperationSetAttributeByName, mlirOperationVerify, mlirOperationWalk, MlirOperation,
     MlirWalkOrder_MlirWalkPostOrder, MlirWalkOrder_MlirWalkPreOrder, MlirWalkResult,
     MlirWalkResult_MlirWalkResultAdvance, MlirWalkResult_MlirWalkResultInterrupt,
@@ -15,12 +15,15 @@ use mlir_sys::{
 };
 
 use crate::{
-    ir::{Attribute, AttributeLike, BlockRef, Identifier, Location, RegionRef, Value},
+    ir::{
+        r#type::TypeId, Attribute, AttributeLike, BlockRef, Identifier, Location, RegionRef, Value,
+    },
     ContextRef, Error, StringRef,
 };
 
 use super::{
-    print_string_callback, OperationPrintingFlags, OperationRef, OperationRefMut, OperationResult,
+    print_string_callback, Implementation, OperationInterface, OperationPrintingFlags,
+    OperationRef, OperationRefMut, OperationResult,
 };
 
 /// Order in which to traverse an operation tree.
@@ -236,6 +239,19 @@ pub trait OperationLike<'c: 'a, 'a>: Display + 'a {
         unsafe { OperationRef::from_option_raw(mlirOperationGetParentOperation(self.to_raw())) }
     }
 
+    /// Returns `true` if an operation is known to implement an interface.
+    fn implements<I: OperationInterface>(&self) -> bool {
+        self.name().as_string_ref().as_str().is_ok_and(|name| {
+            I::implementation(unsafe { self.context().to_ref() }, name)
+                == Implementation::Implemented
+        })
+    }
+
+    /// Returns `true` if an operation implements an interface of a type ID.
+    fn implements_interface(&self, id: TypeId) -> bool {
+        unsafe { mlirOperationImplementsInterface(self.to_raw(), id.to_raw()) }
+    }
+
     /// Verifies an operation.
     fn verify(&self) -> bool {
         unsafe { mlirOperationVerify(self.to_raw()) }
@@ -247,13 +263,16 @@ pub trait OperationLike<'c: 'a, 'a>: Display + 'a {
     }
 
     /// Prints an operation with flags.
-    fn to_string_with_flags(&self, flags: OperationPrintingFlags) -> Result<String, Error> {
+    fn to_string_with_flags(
+        &self,
+        flags: impl Borrow<OperationPrintingFlags>,
+    ) -> Result<String, Error> {
         let mut data = (String::new(), Ok::<_, Error>(()));
 
         unsafe {
             mlirOperationPrintWithFlags(
                 self.to_raw(),
-                flags.to_raw(),
+                flags.borrow().to_raw(),
                 Some(print_string_callback),
                 &mut data as *mut _ as *mut _,
             );
@@ -48,6 +48,9 @@ impl OperationPrintingFlags {
     }
 }
 
+// Printing flags are plain values without references to contexts.
+unsafe impl Send for OperationPrintingFlags {}
+
 impl Drop for OperationPrintingFlags {
     fn drop(&mut self) {
         unsafe { mlirOpPrintingFlagsDestroy(self.0) }
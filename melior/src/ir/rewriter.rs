@@ -0,0 +1,830 @@
+use super::{
+    operation::{OperationLike, WalkOrder, WalkResult},
+    Block, BlockLike, BlockRef, Location, Operation, OperationRef, RegionLike, Type, Value,
+    ValueLike,
+};
+use crate::{
+    context::{Context, ContextRef},
+    Error,
+};
+use mlir_sys::{
+    mlirIRRewriterCreate, mlirIRRewriterCreateFromOp, mlirIRRewriterDestroy,
+    mlirOpOperandGetNextUse, mlirOpOperandGetOwner, mlirOpOperandIsNull,
+    mlirRewriterBaseCancelOpModification, mlirRewriterBaseClearInsertionPoint,
+    mlirRewriterBaseCreateBlockBefore, mlirRewriterBaseEraseOp,
+    mlirRewriterBaseFinalizeOpModification, mlirRewriterBaseGetContext,
+    mlirRewriterBaseGetInsertionBlock, mlirRewriterBaseInsert, mlirRewriterBaseReplaceAllUsesWith,
+    mlirRewriterBaseReplaceOpWithOperation, mlirRewriterBaseReplaceOpWithValues,
+    mlirRewriterBaseSetInsertionPointAfter, mlirRewriterBaseSetInsertionPointBefore,
+    mlirRewriterBaseSetInsertionPointToEnd, mlirRewriterBaseSetInsertionPointToStart,
+    mlirRewriterBaseStartOpModification, mlirValueGetFirstUse, mlirValueReplaceAllUsesExcept,
+    MlirBlock, MlirOperation, MlirRewriterBase, MlirValue,
+};
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+};
+
+/// A listener notified of IR changes made by rewriters.
+///
+/// Only changes made through methods of rewriters are notified, including
+/// modifications of users of replaced values. The MLIR C API cannot attach
+/// listeners to rewriters, so changes made by MLIR itself, such as those made
+/// by its pattern drivers, or changes made directly on operations and blocks
+/// are not notified.
+pub trait RewriteListener<'c> {
+    /// Notifies that an operation is inserted.
+    fn notify_operation_inserted(&self, _operation: OperationRef<'c, '_>) {}
+
+    /// Notifies that a block is created.
+    fn notify_block_created(&self, _block: BlockRef<'c, '_>) {}
+
+    /// Notifies that an operation is modified in place.
+    fn notify_operation_modified(&self, _operation: OperationRef<'c, '_>) {}
+
+    /// Notifies that an operation is about to be replaced with values.
+    fn notify_operation_replaced(
+        &self,
+        _operation: OperationRef<'c, '_>,
+        _values: &[Value<'c, '_>],
+    ) {
+    }
+
+    /// Notifies that an operation is about to be erased.
+    ///
+    /// This is called for operations nested in erased operations as well.
+    fn notify_operation_erased(&self, _operation: OperationRef<'c, '_>) {}
+}
+
+#[derive(Clone, Copy, Debug)]
+enum InsertionPoint {
+    None,
+    Before(MlirOperation),
+    After(MlirOperation),
+    BlockStart(MlirBlock),
+    BlockEnd(MlirBlock),
+}
+
+impl InsertionPoint {
+    /// Returns `true` if an insertion point refers to an operation or is
+    /// nested in it.
+    fn is_in(self, operation: OperationRef) -> bool {
+        let mut parent = match self {
+            Self::None => None,
+            Self::Before(operation) | Self::After(operation) => Some(operation),
+            Self::BlockStart(block) | Self::BlockEnd(block) => unsafe { BlockRef::from_raw(block) }
+                .parent_operation()
+                .map(|operation| operation.to_raw()),
+        };
+
+        while let Some(current) = parent {
+            if current.ptr == operation.to_raw().ptr {
+                return true;
+            }
+
+            parent = unsafe { OperationRef::from_raw(current) }
+                .parent_operation()
+                .map(|operation| operation.to_raw());
+        }
+
+        false
+    }
+}
+
+/// An IR rewriter.
+///
+/// Rewriters insert, replace, and erase operations at their insertion points.
+/// Pattern drivers require patterns to mutate IR through rewriters so that
+/// they can keep track of erased operations with listeners.
+pub struct Rewriter<'c> {
+    raw: MlirRewriterBase,
+    insertion_point: Cell<InsertionPoint>,
+    location: Cell<Location<'c>>,
+    listener: Option<Box<dyn RewriteListener<'c> + 'c>>,
+    saved_insertion_points: RefCell<Vec<Option<InsertionPoint>>>,
+    _context: PhantomData<&'c Context>,
+}
+
+impl<'c> Rewriter<'c> {
+    /// Creates a rewriter.
+    pub fn new(context: &'c Context) -> Self {
+        Self {
+            raw: unsafe { mlirIRRewriterCreate(context.to_raw()) },
+            insertion_point: Cell::new(InsertionPoint::None),
+            location: Cell::new(Location::unknown(context)),
+            listener: None,
+            saved_insertion_points: Default::default(),
+            _context: Default::default(),
+        }
+    }
+
+    /// Creates a rewriter with an insertion point before an operation.
+    ///
+    /// Its current location is the operation's location.
+    pub fn from_operation(operation: &impl OperationLike<'c, '_>) -> Self {
+        Self {
+            raw: unsafe { mlirIRRewriterCreateFromOp(operation.to_raw()) },
+            insertion_point: Cell::new(InsertionPoint::Before(operation.to_raw())),
+            location: Cell::new(operation.location()),
+            listener: None,
+            saved_insertion_points: Default::default(),
+            _context: Default::default(),
+        }
+    }
+
+    /// Sets a listener.
+    pub fn with_listener(mut self, listener: impl RewriteListener<'c> + 'c) -> Self {
+        self.listener = Some(Box::new(listener));
+        self
+    }
+
+    /// Returns a context.
+    pub fn context(&self) -> ContextRef<'c> {
+        unsafe { ContextRef::from_raw(mlirRewriterBaseGetContext(self.raw)) }
+    }
+
+    /// Returns a current location.
+    pub fn location(&self) -> Location<'c> {
+        self.location.get()
+    }
+
+    /// Sets a current location.
+    pub fn set_location(&self, location: Location<'c>) {
+        self.location.set(location);
+    }
+
+    /// Clears an insertion point.
+    pub fn clear_insertion_point(&self) {
+        self.set_insertion_point(InsertionPoint::None);
+    }
+
+    /// Sets an insertion point before an operation.
+    pub fn set_insertion_point_before(&self, operation: &impl OperationLike<'c, '_>) {
+        self.set_insertion_point(InsertionPoint::Before(operation.to_raw()));
+    }
+
+    /// Sets an insertion point after an operation.
+    pub fn set_insertion_point_after(&self, operation: &impl OperationLike<'c, '_>) {
+        self.set_insertion_point(InsertionPoint::After(operation.to_raw()));
+    }
+
+    /// Sets an insertion point to the start of a block.
+    pub fn set_insertion_point_to_start(&self, block: &impl BlockLike<'c, '_>) {
+        self.set_insertion_point(InsertionPoint::BlockStart(block.to_raw()));
+    }
+
+    /// Sets an insertion point to the end of a block.
+    pub fn set_insertion_point_to_end(&self, block: &impl BlockLike<'c, '_>) {
+        self.set_insertion_point(InsertionPoint::BlockEnd(block.to_raw()));
+    }
+
+    /// Saves a current insertion point and restores it when a returned guard
+    /// is dropped.
+    ///
+    /// If an operation at the saved insertion point is erased by the
+    /// rewriter, the guard restores an insertion point at the position of the
+    /// erased operation instead.
+    pub fn insertion_guard(&self) -> InsertionGuard<'c, '_> {
+        let mut points = self.saved_insertion_points.borrow_mut();
+
+        points.push(Some(self.insertion_point.get()));
+
+        InsertionGuard {
+            rewriter: self,
+            index: points.len() - 1,
+            location: self.location.get(),
+        }
+    }
+
+    /// Returns a block of an insertion point.
+    pub fn insertion_block(&self) -> Option<BlockRef<'c, '_>> {
+        unsafe { BlockRef::from_option_raw(mlirRewriterBaseGetInsertionBlock(self.raw)) }
+    }
+
+    /// Inserts an operation at an insertion point.
+    ///
+    /// It fails and drops the operation if no insertion point is set.
+    pub fn insert(&self, operation: Operation<'c>) -> Result<OperationRef<'c, '_>, Error> {
+        if self.insertion_block().is_none() {
+            return Err(Error::InsertionPointNotSet);
+        }
+
+        let operation = unsafe {
+            OperationRef::from_raw(mlirRewriterBaseInsert(self.raw, operation.into_raw()))
+        };
+
+        if let Some(listener) = &self.listener {
+            listener.notify_operation_inserted(operation);
+        }
+
+        Ok(operation)
+    }
+
+    /// Creates a block at the end of a region and sets an insertion point to
+    /// the end of the block.
+    pub fn create_block<'a>(
+        &self,
+        region: &impl RegionLike<'c, 'a>,
+        arguments: &[(Type<'c>, Location<'c>)],
+    ) -> BlockRef<'c, 'a> {
+        let block = region.append_block(Block::new(arguments));
+
+        self.notify_block_created(block)
+    }
+
+    /// Creates a block before another block and sets an insertion point to the
+    /// end of the created block.
+    pub fn create_block_before<'a>(
+        &self,
+        block: BlockRef<'c, 'a>,
+        arguments: &[(Type<'c>, Location<'c>)],
+    ) -> BlockRef<'c, 'a> {
+        let (types, locations): (Vec<_>, Vec<_>) = arguments
+            .iter()
+            .map(|(r#type, location)| (r#type.to_raw(), location.to_raw()))
+            .unzip();
+
+        let block = unsafe {
+            BlockRef::from_raw(mlirRewriterBaseCreateBlockBefore(
+                self.raw,
+                block.to_raw(),
+                types.len() as isize,
+                types.as_ptr(),
+                locations.as_ptr(),
+            ))
+        };
+
+        self.notify_block_created(block)
+    }
+
+    /// Replaces an operation with values and erases it.
+    pub fn replace_operation_with_values(
+        &self,
+        operation: OperationRef<'c, '_>,
+        values: &[Value<'c, '_>],
+    ) {
+        if let Some(listener) = &self.listener {
+            listener.notify_operation_replaced(operation, values);
+        }
+
+        let users = self.users(operation.results().map(|result| result.to_raw()));
+        self.notify_erased(operation);
+
+        unsafe {
+            mlirRewriterBaseReplaceOpWithValues(
+                self.raw,
+                operation.to_raw(),
+                values.len() as isize,
+                values.as_ptr() as *const _,
+            )
+        }
+
+        self.notify_modified(users);
+    }
+
+    /// Replaces an operation with results of another operation and erases it.
+    pub fn replace_operation(&self, operation: OperationRef<'c, '_>, other: OperationRef<'c, '_>) {
+        if let Some(listener) = &self.listener {
+            let values = other.results().map(Value::from).collect::<Vec<_>>();
+
+            listener.notify_operation_replaced(operation, &values);
+        }
+
+        let users = self.users(operation.results().map(|result| result.to_raw()));
+        self.notify_erased(operation);
+
+        unsafe {
+            mlirRewriterBaseReplaceOpWithOperation(self.raw, operation.to_raw(), other.to_raw())
+        }
+
+        self.notify_modified(users);
+    }
+
+    /// Erases an operation.
+    pub fn erase_operation(&self, operation: OperationRef<'c, '_>) {
+        self.notify_erased(operation);
+
+        unsafe { mlirRewriterBaseEraseOp(self.raw, operation.to_raw()) }
+    }
+
+    /// Modifies an operation in place.
+    ///
+    /// The modification is cancelled if a callback returns `false`.
+    pub fn modify_operation_in_place(
+        &self,
+        operation: OperationRef<'c, '_>,
+        callback: impl FnOnce(OperationRef<'c, '_>) -> bool,
+    ) -> bool {
+        unsafe { mlirRewriterBaseStartOpModification(self.raw, operation.to_raw()) }
+
+        let modified = callback(operation);
+
+        unsafe {
+            if modified {
+                mlirRewriterBaseFinalizeOpModification(self.raw, operation.to_raw())
+            } else {
+                mlirRewriterBaseCancelOpModification(self.raw, operation.to_raw())
+            }
+        }
+
+        if modified {
+            if let Some(listener) = &self.listener {
+                listener.notify_operation_modified(operation);
+            }
+        }
+
+        modified
+    }
+
+    /// Replaces all uses of a value with another.
+    pub fn replace_all_uses_with(&self, from: Value<'c, '_>, to: Value<'c, '_>) {
+        let users = self.users([from.to_raw()]);
+
+        unsafe { mlirRewriterBaseReplaceAllUsesWith(self.raw, from.to_raw(), to.to_raw()) }
+
+        self.notify_modified(users);
+    }
+
+    /// Replaces all uses of a value with another except uses in operations.
+    pub fn replace_all_uses_except(
+        &self,
+        from: Value<'c, '_>,
+        to: Value<'c, '_>,
+        exceptions: &[OperationRef<'c, '_>],
+    ) {
+        let users = self
+            .users([from.to_raw()])
+            .into_iter()
+            .filter(|user| {
+                !exceptions
+                    .iter()
+                    .any(|other| other.to_raw().ptr == user.ptr)
+            })
+            .collect();
+
+        unsafe {
+            mlirValueReplaceAllUsesExcept(
+                from.to_raw(),
+                to.to_raw(),
+                exceptions.len() as isize,
+                exceptions.as_ptr() as *mut _,
+            )
+        }
+
+        self.notify_modified(users);
+    }
+
+    /// Converts a rewriter into a raw object.
+    pub const fn to_raw(&self) -> MlirRewriterBase {
+        self.raw
+    }
+
+    fn set_insertion_point(&self, insertion_point: InsertionPoint) {
+        unsafe {
+            match insertion_point {
+                InsertionPoint::None => mlirRewriterBaseClearInsertionPoint(self.raw),
+                InsertionPoint::Before(operation) => {
+                    mlirRewriterBaseSetInsertionPointBefore(self.raw, operation)
+                }
+                InsertionPoint::After(operation) => {
+                    mlirRewriterBaseSetInsertionPointAfter(self.raw, operation)
+                }
+                InsertionPoint::BlockStart(block) => {
+                    mlirRewriterBaseSetInsertionPointToStart(self.raw, block)
+                }
+                InsertionPoint::BlockEnd(block) => {
+                    mlirRewriterBaseSetInsertionPointToEnd(self.raw, block)
+                }
+            }
+        }
+
+        self.insertion_point.set(insertion_point);
+    }
+
+    fn notify_block_created<'a>(&self, block: BlockRef<'c, 'a>) -> BlockRef<'c, 'a> {
+        self.set_insertion_point_to_end(&block);
+
+        if let Some(listener) = &self.listener {
+            listener.notify_block_created(block);
+        }
+
+        block
+    }
+
+    /// Notifies a listener that an operation and operations nested in it are
+    /// about to be erased, and moves insertion points in them to the position
+    /// of the operation.
+    pub(crate) fn notify_erased(&self, operation: OperationRef) {
+        let position = match (operation.next_in_block(), operation.block()) {
+            (Some(next), _) => InsertionPoint::Before(next.to_raw()),
+            (None, Some(block)) => InsertionPoint::BlockEnd(block.to_raw()),
+            (None, None) => InsertionPoint::None,
+        };
+
+        if self.insertion_point.get().is_in(operation) {
+            self.set_insertion_point(position);
+        }
+
+        for point in self
+            .saved_insertion_points
+            .borrow_mut()
+            .iter_mut()
+            .flatten()
+        {
+            if point.is_in(operation) {
+                *point = position;
+            }
+        }
+
+        let Some(listener) = &self.listener else {
+            return;
+        };
+        let mut operations = vec![];
+
+        operation.walk(WalkOrder::PreOrder, |operation| {
+            operations.push(operation.to_raw());
+            WalkResult::Advance
+        });
+
+        for operation in operations {
+            listener.notify_operation_erased(unsafe { OperationRef::from_raw(operation) });
+        }
+    }
+
+    fn users(&self, values: impl IntoIterator<Item = MlirValue>) -> Vec<MlirOperation> {
+        let mut users = Vec::<MlirOperation>::new();
+
+        if self.listener.is_none() {
+            return users;
+        }
+
+        for value in values {
+            let mut operand = unsafe { mlirValueGetFirstUse(value) };
+
+            while !unsafe { mlirOpOperandIsNull(operand) } {
+                let user = unsafe { mlirOpOperandGetOwner(operand) };
+
+                if !users.iter().any(|other| other.ptr == user.ptr) {
+                    users.push(user);
+                }
+
+                operand = unsafe { mlirOpOperandGetNextUse(operand) };
+            }
+        }
+
+        users
+    }
+
+    fn notify_modified(&self, users: Vec<MlirOperation>) {
+        if let Some(listener) = &self.listener {
+            for user in users {
+                listener.notify_operation_modified(unsafe { OperationRef::from_raw(user) });
+            }
+        }
+    }
+}
+
+impl Drop for Rewriter<'_> {
+    fn drop(&mut self) {
+        unsafe { mlirIRRewriterDestroy(self.raw) }
+    }
+}
+
+/// An insertion guard.
+///
+/// It restores an insertion point and a location of a rewriter when it is
+/// dropped.
+pub struct InsertionGuard<'c, 'a> {
+    rewriter: &'a Rewriter<'c>,
+    index: usize,
+    location: Location<'c>,
+}
+
+impl Drop for InsertionGuard<'_, '_> {
+    fn drop(&mut self) {
+        let point = {
+            let mut points = self.rewriter.saved_insertion_points.borrow_mut();
+            let point = points[self.index].take();
+
+            while points.last().is_some_and(Option::is_none) {
+                points.pop();
+            }
+
+            point
+        };
+
+        if let Some(point) = point {
+            self.rewriter.set_insertion_point(point);
+        }
+
+        self.rewriter.set_location(self.location);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dialect::{arith, func},
+        ir::{attribute::IntegerAttribute, r#type::IntegerType, Block, Location, Type},
+        test::create_test_context,
+    };
+    use pretty_assertions::assert_eq;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn new() {
+        let context = create_test_context();
+
+        Rewriter::new(&context);
+    }
+
+    #[test]
+    fn insert() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let block = Block::new(&[]);
+        let r#return = block.append_operation(func::r#return(&[], location));
+
+        rewriter.set_insertion_point_before(&r#return);
+        rewriter
+            .insert(arith::constant(
+                &context,
+                IntegerAttribute::new(Type::index(&context), 42).into(),
+                location,
+            ))
+            .unwrap();
+
+        assert_eq!(rewriter.insertion_block().as_deref(), Some(&block));
+        assert_eq!(
+            block
+                .first_operation()
+                .unwrap()
+                .name()
+                .as_string_ref()
+                .as_str(),
+            Ok("arith.constant")
+        );
+    }
+
+    #[test]
+    fn insert_without_insertion_point() {
+        let context = create_test_context();
+        let rewriter = Rewriter::new(&context);
+
+        assert_eq!(
+            rewriter.insert(arith::constant(
+                &context,
+                IntegerAttribute::new(Type::index(&context), 42).into(),
+                Location::unknown(&context),
+            )),
+            Err(Error::InsertionPointNotSet)
+        );
+    }
+
+    #[test]
+    fn replace_operation_with_values() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let r#type = IntegerType::new(&context, 64).into();
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+        let lhs = block.argument(0).unwrap().into();
+        let rhs = block.argument(1).unwrap().into();
+
+        let sum = block.append_operation(arith::addi(lhs, rhs, location));
+        let r#return =
+            block.append_operation(func::r#return(&[sum.result(0).unwrap().into()], location));
+
+        rewriter.replace_operation_with_values(sum, &[lhs]);
+
+        assert_eq!(r#return.operand(0).unwrap(), lhs);
+        assert_eq!(block.first_operation(), Some(r#return));
+    }
+
+    #[test]
+    fn replace_all_uses_except() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let r#type = IntegerType::new(&context, 64).into();
+        let block = Block::new(&[(r#type, location), (r#type, location)]);
+        let lhs = block.argument(0).unwrap().into();
+        let rhs = block.argument(1).unwrap().into();
+
+        let sum = block.append_operation(arith::addi(lhs, lhs, location));
+        let r#return = block.append_operation(func::r#return(&[lhs], location));
+
+        rewriter.replace_all_uses_except(lhs, rhs, &[sum]);
+
+        assert_eq!(sum.operand(0).unwrap(), lhs);
+        assert_eq!(r#return.operand(0).unwrap(), rhs);
+    }
+
+    #[test]
+    fn erase_operation() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let block = Block::new(&[]);
+        let constant = block.append_operation(arith::constant(
+            &context,
+            IntegerAttribute::new(Type::index(&context), 42).into(),
+            location,
+        ));
+
+        rewriter.erase_operation(constant);
+
+        assert_eq!(block.first_operation(), None);
+    }
+
+    #[test]
+    fn modify_operation_in_place() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let block = Block::new(&[]);
+        let constant = block.append_operation(arith::constant(
+            &context,
+            IntegerAttribute::new(Type::index(&context), 42).into(),
+            location,
+        ));
+
+        assert!(rewriter.modify_operation_in_place(constant, |_| true));
+        assert!(!rewriter.modify_operation_in_place(constant, |_| false));
+    }
+
+    #[test]
+    fn location() {
+        let context = create_test_context();
+        let rewriter = Rewriter::new(&context);
+        let location = Location::new(&context, "foo", 1, 2);
+
+        assert_eq!(rewriter.location(), Location::unknown(&context));
+
+        rewriter.set_location(location);
+
+        assert_eq!(rewriter.location(), location);
+    }
+
+    #[test]
+    fn insertion_guard() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let block = Block::new(&[]);
+        let other_block = Block::new(&[]);
+
+        rewriter.set_insertion_point_to_end(&block);
+
+        {
+            let _guard = rewriter.insertion_guard();
+
+            rewriter.set_insertion_point_to_end(&other_block);
+            rewriter.set_location(Location::new(&context, "foo", 1, 2));
+
+            assert_eq!(rewriter.insertion_block().as_deref(), Some(&other_block));
+        }
+
+        assert_eq!(rewriter.insertion_block().as_deref(), Some(&block));
+        assert_eq!(rewriter.location(), location);
+    }
+
+    #[test]
+    fn insertion_guard_with_erased_operation() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let block = Block::new(&[]);
+        let constant = block.append_operation(arith::constant(
+            &context,
+            IntegerAttribute::new(Type::index(&context), 42).into(),
+            location,
+        ));
+        let r#return = block.append_operation(func::r#return(&[], location));
+
+        rewriter.set_insertion_point_before(&constant);
+
+        {
+            let _guard = rewriter.insertion_guard();
+
+            rewriter.set_insertion_point_to_end(&block);
+            rewriter.erase_operation(constant);
+        }
+
+        rewriter
+            .insert(arith::constant(
+                &context,
+                IntegerAttribute::new(Type::index(&context), 0).into(),
+                location,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            block.first_operation().unwrap().next_in_block(),
+            Some(r#return)
+        );
+    }
+
+    #[test]
+    fn create_block() {
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let rewriter = Rewriter::new(&context);
+        let region = Region::new();
+        let r#type = Type::index(&context);
+
+        let block = rewriter.create_block(&region, &[(r#type, location)]);
+        let other_block = rewriter.create_block_before(block, &[]);
+
+        assert_eq!(block.argument_count(), 1);
+        assert_eq!(region.first_block(), Some(other_block));
+        assert_eq!(rewriter.insertion_block(), Some(other_block));
+    }
+
+    #[test]
+    fn listener() {
+        #[derive(Default)]
+        struct Listener {
+            events: Rc<RefCell<Vec<String>>>,
+        }
+
+        impl<'c> RewriteListener<'c> for Listener {
+            fn notify_operation_inserted(&self, operation: OperationRef<'c, '_>) {
+                self.events.borrow_mut().push(format!(
+                    "inserted {}",
+                    operation.name().as_string_ref().as_str().unwrap()
+                ));
+            }
+
+            fn notify_operation_modified(&self, operation: OperationRef<'c, '_>) {
+                self.events.borrow_mut().push(format!(
+                    "modified {}",
+                    operation.name().as_string_ref().as_str().unwrap()
+                ));
+            }
+
+            fn notify_operation_erased(&self, operation: OperationRef<'c, '_>) {
+                self.events.borrow_mut().push(format!(
+                    "erased {}",
+                    operation.name().as_string_ref().as_str().unwrap()
+                ));
+            }
+        }
+
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let listener = Listener::default();
+        let events = listener.events.clone();
+        let rewriter = Rewriter::new(&context).with_listener(listener);
+        let block = Block::new(&[]);
+
+        rewriter.set_insertion_point_to_end(&block);
+        let constant = rewriter
+            .insert(arith::constant(
+                &context,
+                IntegerAttribute::new(Type::index(&context), 42).into(),
+                location,
+            ))
+            .unwrap();
+        rewriter.erase_operation(constant);
+
+        assert_eq!(
+            *events.borrow(),
+            vec!["inserted arith.constant", "erased arith.constant"]
+        );
+    }
+
+    #[test]
+    fn listen_modified_users() {
+        #[derive(Default)]
+        struct Listener {
+            modified: Rc<RefCell<Vec<String>>>,
+        }
+
+        impl<'c> RewriteListener<'c> for Listener {
+            fn notify_operation_modified(&self, operation: OperationRef<'c, '_>) {
+                self.modified
+                    .borrow_mut()
+                    .push(operation.name().as_string_ref().as_str().unwrap().into());
+            }
+        }
+
+        let context = create_test_context();
+        let location = Location::unknown(&context);
+        let listener = Listener::default();
+        let modified = listener.modified.clone();
+        let rewriter = Rewriter::new(&context).with_listener(listener);
+        let r#type = IntegerType::new(&context, 64).into();
+        let block = Block::new(&[(r#type, location)]);
+        let argument = block.argument(0).unwrap().into();
+
+        let sum = block.append_operation(arith::addi(argument, argument, location));
+        block.append_operation(func::r#return(&[sum.result(0).unwrap().into()], location));
+
+        rewriter.replace_operation_with_values(sum, &[argument]);
+
+        assert_eq!(*modified.borrow(), vec!["func.return"]);
+    }
+}
@@ -26,6 +26,7 @@ use mlir_sys::{
 use std::{
     ffi::c_void,
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     marker::PhantomData,
 };
 
@@ -145,6 +146,13 @@ impl PartialEq for Type<'_> {
 
 impl Eq for Type<'_> {}
 
+// Types are uniqued in contexts, so their pointers identify them.
+impl Hash for Type<'_> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.raw.ptr.hash(hasher);
+    }
+}
+
 impl Display for Type<'_> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let mut data = (formatter, Ok(()));
@@ -183,6 +191,7 @@ mod tests {
     use crate::test::create_test_context;
 
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn new() {
@@ -269,6 +278,33 @@ mod tests {
         assert_ne!(Type::index(&context), Type::float64(&context));
     }
 
+    #[test]
+    fn hash() {
+        let context = create_test_context();
+
+        let types = HashSet::from([
+            Type::index(&context),
+            Type::parse(&context, "index").unwrap(),
+            Type::float64(&context),
+        ]);
+
+        assert_eq!(types.len(), 2);
+        assert!(types.contains(&Type::float64(&context)));
+    }
+
+    #[test]
+    fn hash_subtype() {
+        let context = create_test_context();
+
+        let types = HashSet::from([
+            IntegerType::new(&context, 64),
+            IntegerType::new(&context, 64),
+            IntegerType::new(&context, 32),
+        ]);
+
+        assert_eq!(types.len(), 2);
+    }
+
     #[test]
     fn display() {
         let context = create_test_context();
@@ -31,5 +31,19 @@ macro_rules! type_traits {
                 std::fmt::Display::fmt(&self.r#type, formatter)
             }
         }
+
+        impl<'c> PartialEq for $name<'c> {
+            fn eq(&self, other: &Self) -> bool {
+                self.r#type == other.r#type
+            }
+        }
+
+        impl<'c> Eq for $name<'c> {}
+
+        impl<'c> std::hash::Hash for $name<'c> {
+            fn hash<H: std::hash::Hasher>(&self, hasher: &mut H) {
+                std::hash::Hash::hash(&self.r#type, hasher);
+            }
+        }
     };
 }
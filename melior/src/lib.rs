@@ -8,12 +8,16 @@ mod context;
 pub mod diagnostic;
 pub mod dialect;
 mod error;
-mod execution_engine;
+pub mod execution_engine;
 #[cfg(feature = "helpers")]
 pub mod helpers;
+pub mod interpreter;
 pub mod ir;
 mod logical_result;
+pub mod parallel;
 pub mod pass;
+pub mod pipeline;
+pub mod reproducer;
 mod string_ref;
 
 #[cfg(test)]
@@ -27,7 +31,7 @@ pub use self::{
     string_ref::StringRef,
 };
 
-pub use melior_macro::dialect;
+pub use melior_macro::{dialect, pass};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,510 @@
+//! Parallel compilation of modules.
+//!
+//! Contexts are not shared between threads. Each worker thread compiles
+//! modules in their textual forms in its own context, which is created with
+//! all dialects and LLVM translations registered and multi-threading
+//! disabled.
+
+use crate::{
+    dialect::DialectRegistry,
+    ir::{
+        attribute::{FlatSymbolRefAttribute, StringAttribute, TypeAttribute},
+        block::BlockLike,
+        operation::{OperationBuilder, OperationLike},
+        Module, OperationRef, Region, RegionLike,
+    },
+    pass::Pipeline,
+    utility::{register_all_dialects, register_all_llvm_translations, register_all_passes},
+    Context, Error, ExecutionEngine,
+};
+use std::{
+    cell::RefCell,
+    fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+const FUNCTION_NAME: &str = "func.func";
+// Attributes of initial values of `memref.global` and `llvm.mlir.global`.
+const INITIALIZER_ATTRIBUTES: &[&str] = &["initial_value", "value"];
+
+/// A compiler of modules on a thread pool.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{parallel::ParallelCompiler, pipeline, ExecutionEngine};
+///
+/// let sources = (0..4)
+///     .map(|index| {
+///         format!(
+///             "func.func @foo() -> i64 attributes {{ llvm.emit_c_interface }} {{
+///                 %0 = arith.constant {index} : i64
+///                 return %0 : i64
+///             }}"
+///         )
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let compilations = ParallelCompiler::new(pipeline::scalar_to_llvm())
+///     .threads(2)
+///     .compile(&sources, |module| {
+///         ExecutionEngine::new(module, 2, &[], false)?.invoke::<(), i64>("foo", ())
+///     });
+///
+/// for (index, compilation) in compilations.into_iter().enumerate() {
+///     assert_eq!(compilation.into_result(), Ok(index as i64));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParallelCompiler {
+    pipeline: Pipeline,
+    threads: usize,
+}
+
+impl ParallelCompiler {
+    /// Creates a compiler with a pass pipeline.
+    ///
+    /// The number of threads defaults to the available parallelism.
+    pub fn new(pipeline: Pipeline) -> Self {
+        Self {
+            pipeline,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        }
+    }
+
+    /// Sets the number of threads.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Compiles modules in textual forms and passes compiled modules to a
+    /// function on worker threads.
+    ///
+    /// Compilations are returned in the order of sources.
+    pub fn compile<T: Send>(
+        &self,
+        sources: &[impl AsRef<str> + Sync],
+        handle: impl Fn(&Module) -> Result<T, Error> + Sync,
+    ) -> Vec<Compilation<T>> {
+        self.compile_with_index(sources, |_, module| handle(module))
+    }
+
+    /// Compiles modules into their textual forms.
+    pub fn compile_to_text(&self, sources: &[impl AsRef<str> + Sync]) -> Vec<Compilation<String>> {
+        self.compile(sources, |module| Ok(module.as_operation().to_string()))
+    }
+
+    /// Compiles modules into object files in an existing directory.
+    ///
+    /// Object files are named after indices of sources, such as `0.o`.
+    /// Existing files are overwritten.
+    pub fn compile_to_object_files(
+        &self,
+        sources: &[impl AsRef<str> + Sync],
+        directory: &Path,
+        optimization_level: usize,
+    ) -> Vec<Compilation<PathBuf>> {
+        self.compile_with_index(sources, |index, module| {
+            let path = directory.join(format!("{index}.o"));
+            let engine = ExecutionEngine::new(module, optimization_level, &[], true)?;
+
+            // The C API does not report failures of dumping object files.
+            match fs::remove_file(&path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    return Err(Error::DumpObjectFile(path.display().to_string()));
+                }
+                _ => {}
+            }
+
+            engine.dump_to_object_file(&path.display().to_string());
+
+            if path.is_file() {
+                Ok(path)
+            } else {
+                Err(Error::DumpObjectFile(path.display().to_string()))
+            }
+        })
+    }
+
+    fn compile_with_index<T: Send>(
+        &self,
+        sources: &[impl AsRef<str> + Sync],
+        handle: impl Fn(usize, &Module) -> Result<T, Error> + Sync,
+    ) -> Vec<Compilation<T>> {
+        register_all_passes();
+
+        let next = AtomicUsize::new(0);
+        let compilations = Mutex::new(
+            (0..sources.len())
+                .map(|_| None)
+                .collect::<Vec<Option<Compilation<T>>>>(),
+        );
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(sources.len()) {
+                scope.spawn(|| {
+                    let context = create_context();
+                    let diagnostics = Rc::new(RefCell::new(vec![]));
+
+                    context.attach_diagnostic_handler({
+                        let diagnostics = diagnostics.clone();
+
+                        move |diagnostic| {
+                            diagnostics.borrow_mut().push(diagnostic.to_string());
+                            true
+                        }
+                    });
+
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+
+                        let Some(source) = sources.get(index) else {
+                            break;
+                        };
+
+                        let result = self.compile_module(&context, source.as_ref(), |module| {
+                            handle(index, module)
+                        });
+
+                        compilations.lock().expect("compilations are not poisoned")[index] =
+                            Some(Compilation {
+                                result,
+                                diagnostics: diagnostics.take(),
+                            });
+                    }
+                });
+            }
+        });
+
+        compilations
+            .into_inner()
+            .expect("compilations are not poisoned")
+            .into_iter()
+            .map(|compilation| compilation.expect("module is compiled"))
+            .collect()
+    }
+
+    fn compile_module<T>(
+        &self,
+        context: &Context,
+        source: &str,
+        handle: impl FnOnce(&Module) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut module = Module::parse(context, source).ok_or(Error::ModuleParse)?;
+
+        self.pipeline
+            .create_pass_manager(context)?
+            .run(&mut module)?;
+
+        handle(&module)
+    }
+}
+
+/// A result of a compilation of a module.
+#[derive(Debug)]
+pub struct Compilation<T> {
+    result: Result<T, Error>,
+    diagnostics: Vec<String>,
+}
+
+impl<T> Compilation<T> {
+    /// Returns a result.
+    pub const fn result(&self) -> &Result<T, Error> {
+        &self.result
+    }
+
+    /// Converts a compilation into a result.
+    pub fn into_result(self) -> Result<T, Error> {
+        self.result
+    }
+
+    /// Returns diagnostics emitted during a compilation.
+    pub fn diagnostics(&self) -> &[String] {
+        &self.diagnostics
+    }
+}
+
+/// Splits a module into modules of functions in their textual forms.
+///
+/// Each module contains a function with a body and declarations of the
+/// other functions. Operations other than functions, such as globals, are
+/// defined in the first module and symbols defined by them are declared in
+/// the other modules. Symbols are declared by dropping bodies and
+/// initializers of their definitions, so definitions referenced across modules
+/// must be public.
+pub fn split_functions(module: &Module) -> Vec<String> {
+    let context = module.context();
+    let mut functions = vec![];
+    let mut function_declarations = vec![];
+    let mut others = vec![];
+    let mut other_declarations = vec![];
+    let mut current = module.body().first_operation();
+
+    while let Some(operation) = current {
+        let declaration = if operation.name().as_string_ref().as_str() == Ok(FUNCTION_NAME)
+            && !is_declaration(operation)
+        {
+            declare_function(&context, operation)
+        } else {
+            None
+        };
+
+        if let Some(declaration) = declaration {
+            functions.push(operation.to_string());
+            function_declarations.push(declaration);
+        } else {
+            others.push(operation.to_string());
+            other_declarations.extend(declare_symbol(operation));
+        }
+
+        current = operation.next_in_block();
+    }
+
+    if functions.is_empty() && !others.is_empty() {
+        functions.push(Default::default());
+    }
+
+    functions
+        .iter()
+        .enumerate()
+        .map(|(index, function)| {
+            let mut source = String::new();
+
+            let others = if index == 0 {
+                &others
+            } else {
+                &other_declarations
+            };
+
+            for other in others {
+                source += other;
+                source += "\n";
+            }
+
+            for (other, declaration) in function_declarations.iter().enumerate() {
+                if other != index {
+                    source += declaration;
+                    source += "\n";
+                }
+            }
+
+            source + function
+        })
+        .collect()
+}
+
+fn is_declaration(operation: OperationRef) -> bool {
+    operation
+        .region(0)
+        .map_or(true, |region| region.first_block().is_none())
+}
+
+fn declare_function(context: &Context, operation: OperationRef) -> Option<String> {
+    let name = StringAttribute::try_from(operation.attribute("sym_name").ok()?).ok()?;
+    let r#type = TypeAttribute::try_from(operation.attribute("function_type").ok()?).ok()?;
+
+    Some(format!(
+        "{FUNCTION_NAME} private {}{}",
+        FlatSymbolRefAttribute::new(context, name.value()),
+        r#type.value()
+    ))
+}
+
+// Declares a symbol, such as a global, by copying its definition without
+// regions and initial values.
+fn declare_symbol(operation: OperationRef) -> Option<String> {
+    operation.attribute("sym_name").ok()?;
+
+    Some(
+        OperationBuilder::new(
+            operation.name().as_string_ref().as_str().ok()?,
+            operation.location(),
+        )
+        .add_attributes(
+            &operation
+                .attributes()
+                .filter(|(name, _)| {
+                    !name
+                        .as_string_ref()
+                        .as_str()
+                        .is_ok_and(|name| INITIALIZER_ATTRIBUTES.contains(&name))
+                })
+                .collect::<Vec<_>>(),
+        )
+        .add_regions_vec(
+            (0..operation.region_count())
+                .map(|_| Region::new())
+                .collect(),
+        )
+        .build()
+        .ok()?
+        .to_string(),
+    )
+}
+
+fn create_context() -> Context {
+    let registry = DialectRegistry::new();
+    register_all_dialects(&registry);
+
+    let context = Context::new();
+
+    context.append_dialect_registry(&registry);
+    context.load_all_available_dialects();
+    context.enable_multi_threading(false);
+    register_all_llvm_translations(&context);
+
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pipeline,
+        test::{create_temp_path, create_test_context},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn create_source(value: i64) -> String {
+        format!(
+            "func.func @foo() -> i64 attributes {{ llvm.emit_c_interface }} {{
+                %0 = arith.constant {value} : i64
+                return %0 : i64
+            }}"
+        )
+    }
+
+    fn invoke(module: &Module, name: &str) -> Result<i64, Error> {
+        ExecutionEngine::new(module, 2, &[], false)?.invoke::<(), i64>(name, ())
+    }
+
+    #[test]
+    fn compile_modules() {
+        let sources = (0..16).map(create_source).collect::<Vec<_>>();
+        let compilations = ParallelCompiler::new(pipeline::scalar_to_llvm())
+            .threads(4)
+            .compile(&sources, |module| invoke(module, "foo"));
+
+        assert_eq!(
+            compilations
+                .into_iter()
+                .map(Compilation::into_result)
+                .collect::<Vec<_>>(),
+            (0..16).map(Ok).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn compile_modules_to_text() {
+        let compilations =
+            ParallelCompiler::new(pipeline::scalar_to_llvm()).compile_to_text(&[create_source(42)]);
+
+        assert!(compilations[0]
+            .result()
+            .as_ref()
+            .unwrap()
+            .contains("llvm.func @foo"));
+    }
+
+    #[test]
+    fn collect_diagnostics() {
+        let compilations = ParallelCompiler::new(pipeline::scalar_to_llvm())
+            .threads(2)
+            .compile_to_text(&[create_source(42), "func.func @foo(".into()]);
+
+        assert!(compilations[0].result().is_ok());
+        assert!(compilations[0].diagnostics().is_empty());
+        assert_eq!(compilations[1].result(), &Err(Error::ModuleParse));
+        assert!(!compilations[1].diagnostics().is_empty());
+    }
+
+    #[test]
+    fn compile_split_functions() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            "
+            memref.global @qux : memref<i64> = dense<42>
+
+            func.func @foo() -> i64 attributes { llvm.emit_c_interface } {
+                %0 = call @bar() : () -> i64
+                %1 = arith.addi %0, %0 : i64
+                return %1 : i64
+            }
+
+            func.func @bar() -> i64 attributes { llvm.emit_c_interface } {
+                %0 = arith.constant 21 : i64
+                return %0 : i64
+            }
+
+            func.func private @baz() -> i64
+            ",
+        )
+        .unwrap();
+
+        let sources = split_functions(&module);
+
+        assert_eq!(sources.len(), 2);
+        assert!(sources[0].contains("memref.global @qux : memref<i64> = dense<42>"));
+        assert!(sources[0].contains("func.func private @bar() -> i64"));
+        assert!(sources[0].contains("func.func private @baz() -> i64"));
+        assert!(sources[1].contains("memref.global @qux : memref<i64>\n"));
+        assert!(!sources[1].contains("dense<42>"));
+        assert!(sources[1].contains("func.func private @baz() -> i64"));
+        assert!(sources[1].contains("func.func private @foo() -> i64"));
+
+        let compilations = ParallelCompiler::new(pipeline::memref_to_llvm())
+            .compile(&sources[1..], |module| invoke(module, "bar"));
+
+        assert_eq!(compilations[0].result(), &Ok(21));
+    }
+
+    #[test]
+    fn split_functions_without_definitions() {
+        let context = create_test_context();
+        let module =
+            Module::parse(&context, "memref.global @foo : memref<i64> = dense<42>").unwrap();
+
+        assert_eq!(
+            split_functions(&module),
+            ["memref.global @foo : memref<i64> = dense<42>\n"]
+        );
+    }
+
+    #[test]
+    fn compile_modules_to_object_files() {
+        let directory = create_temp_path("object_files");
+
+        fs::create_dir(&directory).unwrap();
+
+        let compilations = ParallelCompiler::new(pipeline::scalar_to_llvm())
+            .compile_to_object_files(&[create_source(42)], &directory, 2);
+
+        assert_eq!(compilations[0].result(), &Ok(directory.join("0.o")));
+        assert!(directory.join("0.o").is_file());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn fail_to_compile_modules_to_object_files() {
+        let directory = create_temp_path("missing");
+        let compilations = ParallelCompiler::new(pipeline::scalar_to_llvm())
+            .compile_to_object_files(&[create_source(42)], &directory, 2);
+
+        assert_eq!(
+            compilations[0].result(),
+            &Err(Error::DumpObjectFile(
+                directory.join("0.o").display().to_string()
+            ))
+        );
+    }
+}
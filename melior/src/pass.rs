@@ -1,25 +1,51 @@
 //! Passes and pass managers.
 
+mod analysis;
 pub mod r#async;
 pub mod conversion;
+mod definition;
+pub mod dialect_conversion;
 pub mod external;
 pub mod gpu;
+mod instrumentation;
+mod ir_printer;
 pub mod linalg;
 mod manager;
 mod operation_manager;
+mod options;
+pub mod pattern;
+mod pipeline;
+mod registry;
 pub mod sparse_tensor;
+mod statistic;
+mod timing;
 pub mod transform;
 
 pub use self::{
+    analysis::{Analysis, AnalysisManager, CallGraph, DominanceInfo},
+    definition::PassDefinition,
     external::{create_external, ExternalPass, RunExternalPass},
+    instrumentation::PassInstrumentation,
+    ir_printer::PassIrPrinter,
     manager::PassManager,
     operation_manager::OperationPassManager,
+    options::{PassOptionValue, PassOptions, RegionSimplificationLevel},
+    pipeline::{Pipeline, PipelineElement},
+    registry::{register_external_pass, register_pass_definition},
+    statistic::PassStatistic,
+    timing::PassTiming,
+};
+pub(crate) use self::{
+    pipeline::{split_nested_pipeline, split_pipeline},
+    registry::contains_registered_pass,
 };
 use mlir_sys::MlirPass;
 
 /// A pass.
 pub struct Pass {
     raw: MlirPass,
+    statistics: Vec<PassStatistic>,
+    options: String,
 }
 
 impl Pass {
@@ -31,6 +57,8 @@ impl Pass {
     pub unsafe fn from_raw_fn(create_raw: unsafe extern "C" fn() -> MlirPass) -> Self {
         Self {
             raw: unsafe { create_raw() },
+            statistics: Vec::new(),
+            options: String::new(),
         }
     }
 
@@ -40,7 +68,42 @@ impl Pass {
     ///
     /// A raw object must be valid.
     pub const unsafe fn from_raw(raw: MlirPass) -> Self {
-        Self { raw }
+        Self {
+            raw,
+            statistics: Vec::new(),
+            options: String::new(),
+        }
+    }
+
+    /// Attaches statistics to a pass.
+    ///
+    /// Pass managers collect statistics of passes added to them. Statistics
+    /// of MLIR's built-in passes are not available as the C API does not
+    /// expose them.
+    pub fn with_statistics(mut self, statistics: Vec<PassStatistic>) -> Self {
+        self.statistics.extend(statistics);
+        self
+    }
+
+    /// Returns statistics attached to a pass.
+    pub fn statistics(&self) -> &[PassStatistic] {
+        &self.statistics
+    }
+
+    /// Attaches textual options to a pass, such as `threshold=4`.
+    ///
+    /// Pass managers show options of passes added to them in printed pass
+    /// pipelines, such as `my-pass{threshold=4}`, while pass arguments are
+    /// kept without them. Options of MLIR's built-in passes are shown
+    /// natively.
+    pub fn with_options(mut self, options: impl Into<String>) -> Self {
+        self.options = options.into();
+        self
+    }
+
+    /// Returns textual options attached to a pass.
+    pub fn options(&self) -> &str {
+        &self.options
     }
 
     /// Converts a pass into a raw object.
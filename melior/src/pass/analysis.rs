@@ -0,0 +1,735 @@
+mod call_graph;
+mod dominance;
+
+pub use self::{call_graph::CallGraph, dominance::DominanceInfo};
+use super::{instrumentation::Instrumentation, PassInstrumentation};
+use crate::ir::{block::BlockLike, operation::OperationLike, OperationRef};
+use std::{
+    any::{self, Any},
+    collections::{HashMap, HashSet},
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    mem::transmute,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+/// An analysis of an operation.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{
+///     ir::{operation::OperationLike, OperationRef},
+///     pass::Analysis,
+/// };
+///
+/// struct RegionCount(usize);
+///
+/// impl Analysis for RegionCount {
+///     fn analyze(operation: OperationRef) -> Self {
+///         Self(operation.region_count())
+///     }
+/// }
+/// ```
+pub trait Analysis: Send + Sync + 'static {
+    /// Computes an analysis of an operation.
+    fn analyze(operation: OperationRef) -> Self;
+
+    /// Returns a name of an analysis for pass instrumentations.
+    fn name() -> &'static str {
+        any::type_name::<Self>()
+    }
+}
+
+/// An analysis manager.
+///
+/// An analysis manager caches analyses of operations for Rust passes. Once the
+/// manager is added to a pass manager with
+/// [`PassManager::add_analysis_manager`](super::PassManager::add_analysis_manager),
+/// passes get it with [`ExternalPass::analysis_manager`](super::ExternalPass::analysis_manager)
+/// and declare analyses they preserve while they run. Analyses not preserved
+/// by a pass are invalidated after it runs. Analyses of the operation the pass
+/// ran on, its ancestors and its descendants are invalidated.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{
+///     ir::{operation::OperationLike, OperationRef},
+///     pass::{Analysis, ExternalPass, RunExternalPass},
+///     ContextRef,
+/// };
+///
+/// struct RegionCount(usize);
+///
+/// impl Analysis for RegionCount {
+///     fn analyze(operation: OperationRef) -> Self {
+///         Self(operation.region_count())
+///     }
+/// }
+///
+/// #[derive(Clone)]
+/// struct ExamplePass;
+///
+/// impl<'c> RunExternalPass<'c> for ExamplePass {
+///     fn initialize(&mut self, _context: ContextRef<'c>) {}
+///
+///     fn run(&mut self, operation: OperationRef<'c, '_>, pass: ExternalPass<'_>) {
+///         let Some(analyses) = pass.analysis_manager(operation) else {
+///             return;
+///         };
+///         let count = analyses.get_analysis::<RegionCount>(operation);
+///
+///         println!("{} regions", count.0);
+///
+///         // The pass does not change the number of regions.
+///         analyses.preserve::<RegionCount>(operation);
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct AnalysisManager<'c> {
+    state: Arc<Mutex<AnalysisState>>,
+    _context: PhantomData<&'c ()>,
+}
+
+#[derive(Default)]
+struct AnalysisState {
+    operations: HashMap<usize, OperationAnalyses>,
+    preserved: HashMap<usize, PreservedAnalyses>,
+}
+
+struct OperationAnalyses {
+    name: usize,
+    ancestors: Vec<usize>,
+    analyses: HashMap<any::TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+enum PreservedAnalyses {
+    All,
+    Some(HashSet<any::TypeId>),
+}
+
+impl<'c> AnalysisManager<'c> {
+    /// Creates an analysis manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an analysis of an operation, computing it if it is not cached.
+    ///
+    /// Instrumentations of a pass manager running on the operation are
+    /// notified when the analysis is computed.
+    pub fn get_analysis<T: Analysis>(&self, operation: OperationRef<'c, '_>) -> Arc<T> {
+        if let Some(analysis) = self.get_cached_analysis(operation) {
+            return analysis;
+        }
+
+        let instrumentation = self.instrumentation(operation);
+
+        if let Some(instrumentation) = &instrumentation {
+            instrumentation.run_before_analysis(T::name(), operation);
+        }
+
+        let analysis = Arc::new(T::analyze(operation));
+
+        if let Some(instrumentation) = &instrumentation {
+            instrumentation.run_after_analysis(T::name(), operation);
+        }
+
+        self.lock()
+            .operations
+            .entry(key(operation))
+            .or_insert_with(|| OperationAnalyses::new(operation))
+            .analyses
+            .insert(any::TypeId::of::<T>(), analysis.clone());
+
+        analysis
+    }
+
+    /// Returns a cached analysis of an operation.
+    pub fn get_cached_analysis<T: Analysis>(
+        &self,
+        operation: OperationRef<'c, '_>,
+    ) -> Option<Arc<T>> {
+        self.lock()
+            .get(operation)?
+            .analyses
+            .get(&any::TypeId::of::<T>())?
+            .clone()
+            .downcast()
+            .ok()
+    }
+
+    /// Declares that a pass running on an operation preserves an analysis.
+    pub fn preserve<T: Analysis>(&self, operation: OperationRef<'c, '_>) {
+        let mut state = self.lock();
+        let preserved = state
+            .preserved
+            .entry(key(operation))
+            .or_insert_with(|| PreservedAnalyses::Some(Default::default()));
+
+        if let PreservedAnalyses::Some(analyses) = preserved {
+            analyses.insert(any::TypeId::of::<T>());
+        }
+    }
+
+    /// Declares that a pass running on an operation preserves all analyses.
+    pub fn preserve_all(&self, operation: OperationRef<'c, '_>) {
+        self.lock()
+            .preserved
+            .insert(key(operation), PreservedAnalyses::All);
+    }
+
+    /// Invalidates analyses of an operation, its ancestors and its
+    /// descendants except ones declared as preserved.
+    pub fn invalidate(&self, operation: OperationRef<'c, '_>) {
+        self.lock().invalidate(operation);
+    }
+
+    /// Clears all cached analyses.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns an analysis manager of a pass manager running on an operation
+    /// or its ancestors.
+    pub(crate) fn running(operation: OperationRef<'c, '_>) -> Option<Self> {
+        find_run(operation, |_| true).map(|run| Self {
+            state: run.state.clone(),
+            _context: Default::default(),
+        })
+    }
+
+    /// Starts a run of a pass manager on an operation.
+    ///
+    /// The analysis manager is reachable from passes and notifies
+    /// instrumentations of the pass manager until the returned guard is
+    /// dropped. Cached analyses are cleared as operations of previous runs
+    /// might have been freed.
+    pub(crate) fn start_run(
+        &self,
+        operation: OperationRef<'c, '_>,
+        instrumentation: &Instrumentation<'c>,
+    ) -> RunGuard {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        self.clear();
+
+        lock_runs().push(Run {
+            id,
+            operation: key(operation),
+            state: self.state.clone(),
+            // The run is removed by the guard before the pass manager and its
+            // instrumentations are dropped.
+            instrumentation: unsafe {
+                transmute::<Instrumentation<'c>, Instrumentation<'static>>(instrumentation.clone())
+            },
+        });
+
+        RunGuard { id }
+    }
+
+    /// Creates an instrumentation that invalidates analyses after passes.
+    pub(crate) fn invalidator(&self) -> Invalidator {
+        Invalidator {
+            state: self.state.clone(),
+        }
+    }
+
+    fn instrumentation(&self, operation: OperationRef<'c, '_>) -> Option<Instrumentation<'c>> {
+        find_run(operation, |run| Arc::ptr_eq(&run.state, &self.state)).map(|run| unsafe {
+            transmute::<Instrumentation<'static>, Instrumentation<'c>>(run.instrumentation)
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, AnalysisState> {
+        self.state
+            .lock()
+            .expect("analysis manager state is not poisoned")
+    }
+}
+
+impl Debug for AnalysisManager<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("AnalysisManager")
+            .finish_non_exhaustive()
+    }
+}
+
+impl AnalysisState {
+    // Operations are keyed by addresses which can be reused by new operations.
+    // Cached analyses of operations with different names or ancestors are
+    // stale.
+    fn get(&mut self, operation: OperationRef) -> Option<&OperationAnalyses> {
+        let key = key(operation);
+
+        if self
+            .operations
+            .get(&key)
+            .is_some_and(|analyses| !analyses.is_of(operation))
+        {
+            self.operations.remove(&key);
+        }
+
+        self.operations.get(&key)
+    }
+
+    fn invalidate(&mut self, operation: OperationRef) {
+        let operation_key = key(operation);
+        let preserved = self.preserved.remove(&operation_key);
+
+        if matches!(preserved, Some(PreservedAnalyses::All)) {
+            return;
+        }
+
+        let ancestors = ancestors(operation);
+
+        self.operations.retain(|key, analyses| {
+            if *key != operation_key
+                && !ancestors.contains(key)
+                && !analyses.ancestors.contains(&operation_key)
+            {
+                return true;
+            }
+
+            if let Some(PreservedAnalyses::Some(preserved)) = &preserved {
+                analyses
+                    .analyses
+                    .retain(|type_id, _| preserved.contains(type_id));
+            } else {
+                analyses.analyses.clear();
+            }
+
+            !analyses.analyses.is_empty()
+        });
+    }
+
+    fn clear(&mut self) {
+        self.operations.clear();
+        self.preserved.clear();
+    }
+}
+
+impl OperationAnalyses {
+    fn new(operation: OperationRef) -> Self {
+        Self {
+            name: name(operation),
+            ancestors: ancestors(operation),
+            analyses: Default::default(),
+        }
+    }
+
+    fn is_of(&self, operation: OperationRef) -> bool {
+        self.name == name(operation) && self.ancestors == ancestors(operation)
+    }
+}
+
+/// A guard of a run of a pass manager.
+pub(crate) struct RunGuard {
+    id: usize,
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        lock_runs().retain(|run| run.id != self.id);
+    }
+}
+
+#[derive(Clone)]
+struct Run {
+    id: usize,
+    operation: usize,
+    state: Arc<Mutex<AnalysisState>>,
+    instrumentation: Instrumentation<'static>,
+}
+
+// Passes run on multiple threads and instrumentations are already called from
+// them.
+unsafe impl Send for Run {}
+
+static RUNS: Mutex<Vec<Run>> = Mutex::new(Vec::new());
+
+fn lock_runs() -> MutexGuard<'static, Vec<Run>> {
+    RUNS.lock().expect("analysis runs are not poisoned")
+}
+
+// Finds the innermost run on an operation or its ancestors.
+fn find_run(operation: OperationRef, filter: impl Fn(&Run) -> bool) -> Option<Run> {
+    let runs = lock_runs();
+
+    [key(operation)]
+        .into_iter()
+        .chain(ancestors(operation))
+        .find_map(|key| {
+            runs.iter()
+                .rev()
+                .find(|run| run.operation == key && filter(run))
+        })
+        .cloned()
+}
+
+/// An instrumentation that invalidates analyses after passes.
+pub(crate) struct Invalidator {
+    state: Arc<Mutex<AnalysisState>>,
+}
+
+impl Invalidator {
+    fn lock(&self) -> MutexGuard<'_, AnalysisState> {
+        self.state
+            .lock()
+            .expect("analysis manager state is not poisoned")
+    }
+}
+
+impl<'c> PassInstrumentation<'c> for Invalidator {
+    fn run_after_pass(
+        &mut self,
+        _pass: &str,
+        operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        self.lock().invalidate(operation);
+
+        Ok(())
+    }
+
+    fn run_after_pass_failed(&mut self, _pass: &str, _operation: OperationRef<'c, '_>) {
+        self.lock().clear();
+    }
+}
+
+fn key(operation: OperationRef) -> usize {
+    operation.to_raw().ptr as usize
+}
+
+fn name(operation: OperationRef) -> usize {
+    operation.name().to_raw().ptr as usize
+}
+
+fn ancestors(operation: OperationRef) -> Vec<usize> {
+    let mut ancestors = vec![];
+    let mut current = operation.block().and_then(|block| block.parent_operation());
+
+    while let Some(operation) = current {
+        ancestors.push(key(operation));
+        current = operation.block().and_then(|block| block.parent_operation());
+    }
+
+    ancestors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{r#type::TypeId, Module},
+        pass::{self, create_external, ExternalPass, Pass, PassManager, RunExternalPass},
+        test::create_test_context,
+        ContextRef, Error,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    struct RegionCount(usize);
+
+    impl Analysis for RegionCount {
+        fn analyze(operation: OperationRef) -> Self {
+            Self(operation.region_count())
+        }
+
+        fn name() -> &'static str {
+            "region-count"
+        }
+    }
+
+    #[repr(align(8))]
+    struct PassId;
+
+    static ANALYZE_PASS: PassId = PassId;
+
+    #[derive(Clone)]
+    struct AnalyzePass {
+        preserve: bool,
+        cached: Arc<Mutex<Vec<bool>>>,
+    }
+
+    impl<'c> RunExternalPass<'c> for AnalyzePass {
+        fn initialize(&mut self, _context: ContextRef<'c>) {}
+
+        fn run(&mut self, operation: OperationRef<'c, '_>, pass: ExternalPass<'_>) {
+            let analyses = pass.analysis_manager(operation).unwrap();
+
+            self.cached.lock().unwrap().push(
+                analyses
+                    .get_cached_analysis::<RegionCount>(operation)
+                    .is_some(),
+            );
+
+            assert_eq!(analyses.get_analysis::<RegionCount>(operation).0, 1);
+
+            if self.preserve {
+                analyses.preserve::<RegionCount>(operation);
+            }
+        }
+    }
+
+    fn create_analyze_pass(preserve: bool, cached: &Arc<Mutex<Vec<bool>>>) -> Pass {
+        create_external(
+            AnalyzePass {
+                preserve,
+                cached: cached.clone(),
+            },
+            TypeId::create(&ANALYZE_PASS),
+            "AnalyzePass",
+            "analyze",
+            "Analyzes operations.",
+            "",
+            &[],
+        )
+    }
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl<'c> PassInstrumentation<'c> for Recorder {
+        fn run_before_analysis(&mut self, analysis: &str, _operation: OperationRef<'c, '_>) {
+            self.0.lock().unwrap().push(format!("before {analysis}"));
+        }
+
+        fn run_after_analysis(&mut self, analysis: &str, _operation: OperationRef<'c, '_>) {
+            self.0.lock().unwrap().push(format!("after {analysis}"));
+        }
+    }
+
+    fn parse_module(context: &crate::Context) -> Module<'_> {
+        Module::parse(
+            context,
+            indoc!(
+                "
+                func.func @foo() {
+                    return
+                }
+                "
+            ),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn cache_analysis() {
+        let context = create_test_context();
+        let module = parse_module(&context);
+        let operation = module.as_operation();
+        let analyses = AnalysisManager::new();
+
+        assert!(analyses
+            .get_cached_analysis::<RegionCount>(operation)
+            .is_none());
+
+        let analysis = analyses.get_analysis::<RegionCount>(operation);
+
+        assert_eq!(analysis.0, 1);
+        assert!(Arc::ptr_eq(
+            &analysis,
+            &analyses.get_analysis::<RegionCount>(operation)
+        ));
+
+        analyses.preserve::<RegionCount>(operation);
+        analyses.invalidate(operation);
+
+        assert!(analyses
+            .get_cached_analysis::<RegionCount>(operation)
+            .is_some());
+
+        analyses.invalidate(operation);
+
+        assert!(analyses
+            .get_cached_analysis::<RegionCount>(operation)
+            .is_none());
+    }
+
+    #[test]
+    fn invalidate_analyses_of_descendants() {
+        let context = create_test_context();
+        let module = parse_module(&context);
+        let function = module.body().first_operation().unwrap();
+        let analyses = AnalysisManager::new();
+
+        analyses.get_analysis::<RegionCount>(function);
+        analyses.invalidate(module.as_operation());
+
+        assert!(analyses
+            .get_cached_analysis::<RegionCount>(function)
+            .is_none());
+    }
+
+    #[test]
+    fn invalidate_analyses_in_pipeline() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let analyses = AnalysisManager::new();
+        let cached = Arc::new(Mutex::new(vec![]));
+
+        manager.add_analysis_manager(&analyses).unwrap();
+
+        for preserve in [true, false, false] {
+            manager
+                .nested_under("func.func")
+                .add_pass(create_analyze_pass(preserve, &cached));
+        }
+
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(*cached.lock().unwrap(), [false, true, false]);
+    }
+
+    #[test]
+    fn invalidate_analyses_in_textual_pipeline() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let analyses = AnalysisManager::new();
+        let cached = Arc::new(Mutex::new(vec![]));
+
+        pass::transform::register_cse();
+        manager.add_analysis_manager(&analyses).unwrap();
+        manager
+            .nested_under("func.func")
+            .add_pass(create_analyze_pass(true, &cached));
+        manager.add_pipeline("func.func(cse)").unwrap();
+        manager
+            .nested_under("func.func")
+            .add_pass(create_analyze_pass(true, &cached));
+
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(*cached.lock().unwrap(), [false, false]);
+    }
+
+    #[test]
+    fn instrument_analyses() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let analyses = AnalysisManager::new();
+        let events = Arc::new(Mutex::new(vec![]));
+
+        manager.add_instrumentation(Recorder(events.clone()));
+        manager.add_analysis_manager(&analyses).unwrap();
+        manager
+            .nested_under("func.func")
+            .add_pass(create_analyze_pass(true, &Default::default()));
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            ["before region-count", "after region-count"]
+        );
+    }
+
+    #[test]
+    fn share_analysis_manager() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let analyses = AnalysisManager::new();
+        let cached = Arc::new(Mutex::new(vec![]));
+        let events = [0, 1].map(|_| Arc::new(Mutex::new(vec![])));
+
+        for events in &events {
+            let manager = PassManager::new(&context);
+
+            manager.add_instrumentation(Recorder(events.clone()));
+            manager.add_analysis_manager(&analyses).unwrap();
+            manager
+                .nested_under("func.func")
+                .add_pass(create_analyze_pass(true, &cached));
+            manager.run(&mut module).unwrap();
+        }
+
+        assert_eq!(*cached.lock().unwrap(), [false, false]);
+
+        for events in &events {
+            assert_eq!(
+                *events.lock().unwrap(),
+                ["before region-count", "after region-count"]
+            );
+        }
+    }
+
+    #[test]
+    fn clear_analyses_before_run() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let analyses = AnalysisManager::new();
+        let cached = Arc::new(Mutex::new(vec![]));
+
+        analyses.get_analysis::<RegionCount>(module.body().first_operation().unwrap());
+        manager.add_analysis_manager(&analyses).unwrap();
+        manager
+            .nested_under("func.func")
+            .add_pass(create_analyze_pass(true, &cached));
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(*cached.lock().unwrap(), [false]);
+    }
+
+    #[test]
+    fn get_no_analysis_manager() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let found = Arc::new(Mutex::new(None));
+
+        manager.nested_under("func.func").add_pass(create_external(
+            {
+                let found = found.clone();
+
+                move |operation: OperationRef, pass: ExternalPass<'_>| {
+                    *found.lock().unwrap() = Some(pass.analysis_manager(operation).is_some());
+                }
+            },
+            TypeId::create(&ANALYZE_PASS),
+            "AnalyzePass",
+            "analyze",
+            "Analyzes operations.",
+            "",
+            &[],
+        ));
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(*found.lock().unwrap(), Some(false));
+    }
+
+    #[test]
+    fn fail_to_add_analysis_manager() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+        let analyses = AnalysisManager::new();
+
+        manager.nested_under("func.func");
+        manager.add_analysis_manager(&analyses).unwrap();
+
+        assert_eq!(
+            manager.add_analysis_manager(&analyses),
+            Err(Error::AddAnalysisManager("analysis manager already added"))
+        );
+
+        let manager = PassManager::new(&context);
+
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+
+        assert_eq!(
+            manager.add_analysis_manager(&analyses),
+            Err(Error::AddAnalysisManager("passes already added"))
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use super::Analysis;
+use crate::ir::{
+    attribute::{FlatSymbolRefAttribute, StringAttribute},
+    block::BlockLike,
+    operation::{OperationLike, WalkOrder, WalkResult},
+    OperationRef,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A call graph of symbols in an operation.
+///
+/// Nodes are operations with `sym_name` attributes, such as functions. Edges
+/// are operations with `callee` attributes, such as `func.call`, in the nodes.
+/// Symbols are identified by their names and ones in nested symbol tables are
+/// not distinguished.
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    callees: BTreeMap<String, BTreeSet<String>>,
+    callers: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CallGraph {
+    /// Returns names of nodes.
+    pub fn nodes(&self) -> impl Iterator<Item = &str> {
+        self.callees.keys().map(String::as_str)
+    }
+
+    /// Returns names of symbols called by a symbol.
+    pub fn callees(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.callees
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Returns names of symbols calling a symbol.
+    pub fn callers(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.callers
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+}
+
+impl Analysis for CallGraph {
+    fn analyze(operation: OperationRef) -> Self {
+        let mut graph = Self::default();
+
+        operation.walk(WalkOrder::PreOrder, |operation| {
+            if let Some(name) = symbol_name(operation) {
+                graph.callees.entry(name.into()).or_default();
+            }
+
+            if let (Some(caller), Some(callee)) = (caller(operation), callee(operation)) {
+                graph
+                    .callees
+                    .entry(caller.clone())
+                    .or_default()
+                    .insert(callee.clone());
+                graph.callers.entry(callee).or_default().insert(caller);
+            }
+
+            WalkResult::Advance
+        });
+
+        graph
+    }
+
+    fn name() -> &'static str {
+        "call-graph"
+    }
+}
+
+fn symbol_name(operation: OperationRef) -> Option<String> {
+    Some(
+        StringAttribute::try_from(operation.attribute("sym_name").ok()?)
+            .ok()?
+            .value()
+            .into(),
+    )
+}
+
+fn callee(operation: OperationRef) -> Option<String> {
+    Some(
+        FlatSymbolRefAttribute::try_from(operation.attribute("callee").ok()?)
+            .ok()?
+            .value()
+            .into(),
+    )
+}
+
+// Finds the nearest enclosing symbol of an operation.
+fn caller(operation: OperationRef) -> Option<String> {
+    let mut current = operation.block().and_then(|block| block.parent_operation());
+
+    while let Some(operation) = current {
+        if let Some(name) = symbol_name(operation) {
+            return Some(name);
+        }
+
+        current = operation.block().and_then(|block| block.parent_operation());
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir::Module, test::create_test_context};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn analyze_calls() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func private @baz()
+
+                func.func @foo() {
+                    func.call @bar() : () -> ()
+                    func.call @baz() : () -> ()
+                    return
+                }
+
+                func.func @bar() {
+                    func.call @baz() : () -> ()
+                    return
+                }
+                "
+            ),
+        )
+        .unwrap();
+        let graph = CallGraph::analyze(module.as_operation());
+
+        assert_eq!(graph.nodes().collect::<Vec<_>>(), ["bar", "baz", "foo"]);
+        assert_eq!(graph.callees("foo").collect::<Vec<_>>(), ["bar", "baz"]);
+        assert_eq!(graph.callees("baz").count(), 0);
+        assert_eq!(graph.callers("baz").collect::<Vec<_>>(), ["bar", "foo"]);
+        assert_eq!(graph.callers("foo").count(), 0);
+    }
+}
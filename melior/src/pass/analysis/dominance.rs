@@ -0,0 +1,300 @@
+use super::Analysis;
+use crate::ir::{block::BlockLike, operation::OperationLike, BlockRef, OperationRef, RegionLike};
+use std::collections::{HashMap, HashSet};
+
+/// Dominance information of blocks in regions of an operation and its
+/// descendants.
+///
+/// Blocks are compared by their addresses and the information is valid only
+/// while the blocks are not modified. Blocks are never accessed through the
+/// addresses and results are resolved against live regions of given blocks.
+#[derive(Debug, Default)]
+pub struct DominanceInfo {
+    // Immediate dominators of blocks reachable from entry blocks of regions.
+    // Entry blocks are their own immediate dominators.
+    dominators: HashMap<BlockKey, BlockKey>,
+}
+
+// An opaque key of a block which is only compared and never dereferenced.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct BlockKey(usize);
+
+impl DominanceInfo {
+    /// Returns `true` if a block dominates another block.
+    ///
+    /// A block also dominates blocks nested in operations in the blocks it
+    /// dominates. Unreachable blocks are not dominated by any other blocks.
+    pub fn dominates(&self, one: BlockRef, other: BlockRef) -> bool {
+        let Some(other) = lift(one, other) else {
+            return false;
+        };
+        let mut other = key(other);
+
+        loop {
+            if other == key(one) {
+                return true;
+            }
+
+            match self.dominators.get(&other) {
+                Some(&dominator) if dominator != other => other = dominator,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Returns `true` if a block dominates another different block.
+    pub fn properly_dominates(&self, one: BlockRef, other: BlockRef) -> bool {
+        key(one) != key(other) && self.dominates(one, other)
+    }
+
+    /// Returns an immediate dominator of a block.
+    ///
+    /// It is `None` for entry blocks, unreachable blocks and blocks whose
+    /// dominators are not in their regions anymore.
+    pub fn immediate_dominator<'c, 'a>(&self, block: BlockRef<'c, 'a>) -> Option<BlockRef<'c, 'a>> {
+        let dominator = *self
+            .dominators
+            .get(&key(block))
+            .filter(|&&dominator| dominator != key(block))?;
+        let mut current = block.parent_region()?.first_block();
+
+        while let Some(block) = current {
+            if key(block) == dominator {
+                return Some(block);
+            }
+
+            current = block.next_in_region();
+        }
+
+        None
+    }
+
+    fn analyze_operation(&mut self, operation: OperationRef) {
+        for region in operation.regions() {
+            let Some(entry) = region.first_block() else {
+                continue;
+            };
+
+            self.analyze_region(entry);
+
+            let mut block = Some(entry);
+
+            while let Some(current) = block {
+                let mut operation = current.first_operation();
+
+                while let Some(current) = operation {
+                    self.analyze_operation(current);
+                    operation = current.next_in_block();
+                }
+
+                block = current.next_in_region();
+            }
+        }
+    }
+
+    // Computes immediate dominators with the algorithm in "A Simple, Fast
+    // Dominance Algorithm" by Cooper, Harvey and Kennedy.
+    fn analyze_region(&mut self, entry: BlockRef) {
+        let order = postorder(entry);
+        let indices = order
+            .iter()
+            .enumerate()
+            .map(|(index, block)| (key(*block), index))
+            .collect::<HashMap<_, _>>();
+        let mut predecessors = vec![vec![]; order.len()];
+
+        for (index, block) in order.iter().enumerate() {
+            for successor in successors(*block) {
+                predecessors[indices[&key(successor)]].push(index);
+            }
+        }
+
+        let entry_index = order.len() - 1;
+        let mut dominators = vec![None; order.len()];
+        dominators[entry_index] = Some(entry_index);
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for index in (0..entry_index).rev() {
+                let mut dominator = None;
+
+                for &predecessor in &predecessors[index] {
+                    if dominators[predecessor].is_some() {
+                        dominator = Some(dominator.map_or(predecessor, |dominator| {
+                            intersect(&dominators, predecessor, dominator)
+                        }));
+                    }
+                }
+
+                if dominator.is_some() && dominators[index] != dominator {
+                    dominators[index] = dominator;
+                    changed = true;
+                }
+            }
+        }
+
+        for (index, dominator) in dominators.into_iter().enumerate() {
+            if let Some(dominator) = dominator {
+                self.dominators
+                    .insert(key(order[index]), key(order[dominator]));
+            }
+        }
+    }
+}
+
+impl Analysis for DominanceInfo {
+    fn analyze(operation: OperationRef) -> Self {
+        let mut info = Self::default();
+
+        info.analyze_operation(operation);
+
+        info
+    }
+
+    fn name() -> &'static str {
+        "dominance"
+    }
+}
+
+fn postorder(entry: BlockRef) -> Vec<BlockRef> {
+    let mut order = vec![];
+    let mut visited = HashSet::from([key(entry)]);
+    let mut stack = vec![(entry, 0)];
+
+    while let Some((block, index)) = stack.pop() {
+        if let Some(successor) = block
+            .terminator()
+            .and_then(|terminator| terminator.successor(index).ok())
+        {
+            stack.push((block, index + 1));
+
+            if visited.insert(key(successor)) {
+                stack.push((successor, 0));
+            }
+        } else {
+            order.push(block);
+        }
+    }
+
+    order
+}
+
+fn successors<'c, 'a>(block: BlockRef<'c, 'a>) -> Vec<BlockRef<'c, 'a>> {
+    block
+        .terminator()
+        .map(|terminator| terminator.successors().collect())
+        .unwrap_or_default()
+}
+
+fn intersect(dominators: &[Option<usize>], mut one: usize, mut other: usize) -> usize {
+    while one != other {
+        while one < other {
+            one = dominators[one].expect("dominator is computed");
+        }
+
+        while other < one {
+            other = dominators[other].expect("dominator is computed");
+        }
+    }
+
+    one
+}
+
+// Lifts a block to an ancestor block in the same region as another block.
+fn lift<'c, 'a>(one: BlockRef, mut other: BlockRef<'c, 'a>) -> Option<BlockRef<'c, 'a>> {
+    let region = one.parent_region()?;
+
+    while other.parent_region()? != region {
+        other = other.parent_operation()?.block()?;
+    }
+
+    Some(other)
+}
+
+fn key(block: BlockRef) -> BlockKey {
+    BlockKey(block.to_raw().ptr as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir::Module, test::create_test_context};
+    use indoc::indoc;
+
+    #[test]
+    fn dominate_blocks() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo(%x: i1) {
+                    cf.cond_br %x, ^bb1, ^bb2
+                ^bb1:
+                    cf.br ^bb3
+                ^bb2:
+                    cf.br ^bb3
+                ^bb3:
+                    return
+                ^bb4:
+                    return
+                }
+                "
+            ),
+        )
+        .unwrap();
+        let function = module.body().first_operation().unwrap();
+        let info = DominanceInfo::analyze(module.as_operation());
+        let entry = function.region(0).unwrap().first_block().unwrap();
+        let left = entry.next_in_region().unwrap();
+        let right = left.next_in_region().unwrap();
+        let exit = right.next_in_region().unwrap();
+        let unreachable = exit.next_in_region().unwrap();
+
+        assert!(info.dominates(entry, entry));
+        assert!(!info.properly_dominates(entry, entry));
+        assert!(info.dominates(entry, left));
+        assert!(info.dominates(entry, exit));
+        assert!(!info.dominates(left, exit));
+        assert!(!info.dominates(right, exit));
+        assert!(!info.dominates(entry, unreachable));
+        assert_eq!(info.immediate_dominator(entry), None);
+        assert_eq!(info.immediate_dominator(left), Some(entry));
+        assert_eq!(info.immediate_dominator(exit), Some(entry));
+        assert_eq!(info.immediate_dominator(unreachable), None);
+        assert!(info.dominates(module.body(), entry));
+        assert!(!info.dominates(entry, module.body()));
+    }
+
+    #[test]
+    fn resolve_immediate_dominator_in_live_region() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo() {
+                    cf.br ^bb1
+                ^bb1:
+                    return
+                }
+                "
+            ),
+        )
+        .unwrap();
+        let function = module.body().first_operation().unwrap();
+        let info = DominanceInfo::analyze(module.as_operation());
+        let entry = function.region(0).unwrap().first_block().unwrap();
+        let exit = entry.next_in_region().unwrap();
+
+        assert_eq!(info.immediate_dominator(exit), Some(entry));
+
+        let entry = unsafe { entry.detach() }.unwrap();
+
+        assert_eq!(info.immediate_dominator(exit), None);
+
+        drop(entry);
+    }
+}
@@ -1,13 +1,6 @@
 //! Async passes.
 
-melior_macro::passes!(
-    "Async",
-    [
-        mlirCreateAsyncAsyncFuncToAsyncRuntimePass,
-        mlirCreateAsyncAsyncParallelForPass,
-        mlirCreateAsyncAsyncRuntimePolicyBasedRefCountingPass,
-        mlirCreateAsyncAsyncRuntimeRefCountingPass,
-        mlirCreateAsyncAsyncRuntimeRefCountingOptPass,
-        mlirCreateAsyncAsyncToAsyncRuntimePass,
-    ]
-);
+melior_macro::passes_from_tablegen! {
+    prefix: "Async",
+    files: ["mlir/Dialect/Async/Passes.td"],
+}
@@ -1,79 +1,8 @@
 //! Conversion passes.
 
-melior_macro::conversion_passes!(
-    mlirCreateConversionArithToAMDGPUConversionPass,
-    mlirCreateConversionArithToArmSMEConversionPass,
-    mlirCreateConversionArithToLLVMConversionPass,
-    mlirCreateConversionConvertAffineForToGPUPass,
-    mlirCreateConversionConvertAMDGPUToROCDLPass,
-    mlirCreateConversionConvertArithToEmitC,
-    mlirCreateConversionConvertArithToSPIRVPass,
-    mlirCreateConversionConvertArmNeon2dToIntrPass,
-    mlirCreateConversionConvertArmSMEToLLVM,
-    mlirCreateConversionConvertArmSMEToSCFPass,
-    mlirCreateConversionConvertAsyncToLLVMPass,
-    mlirCreateConversionConvertBufferizationToMemRefPass,
-    mlirCreateConversionConvertComplexToLibm,
-    mlirCreateConversionConvertComplexToLLVMPass,
-    mlirCreateConversionConvertComplexToSPIRVPass,
-    mlirCreateConversionConvertComplexToStandardPass,
-    mlirCreateConversionConvertControlFlowToLLVMPass,
-    mlirCreateConversionConvertControlFlowToSPIRVPass,
-    mlirCreateConversionConvertFuncToEmitC,
-    mlirCreateConversionConvertFuncToLLVMPass,
-    mlirCreateConversionConvertFuncToSPIRVPass,
-    mlirCreateConversionConvertGpuOpsToLLVMSPVOps,
-    mlirCreateConversionConvertGpuOpsToNVVMOps,
-    mlirCreateConversionConvertGpuOpsToROCDLOps,
-    mlirCreateConversionConvertGPUToSPIRV,
-    mlirCreateConversionConvertIndexToLLVMPass,
-    mlirCreateConversionConvertIndexToSPIRVPass,
-    mlirCreateConversionConvertLinalgToStandardPass,
-    mlirCreateConversionConvertMathToEmitC,
-    mlirCreateConversionConvertMathToFuncs,
-    mlirCreateConversionConvertMathToLibmPass,
-    mlirCreateConversionConvertMathToLLVMPass,
-    mlirCreateConversionConvertMathToROCDL,
-    mlirCreateConversionConvertMathToSPIRVPass,
-    mlirCreateConversionConvertMemRefToEmitC,
-    mlirCreateConversionConvertMemRefToSPIRVPass,
-    mlirCreateConversionConvertMeshToMPIPass,
-    mlirCreateConversionConvertNVGPUToNVVMPass,
-    mlirCreateConversionConvertNVVMToLLVMPass,
-    mlirCreateConversionConvertOpenACCToSCFPass,
-    mlirCreateConversionConvertOpenMPToLLVMPass,
-    mlirCreateConversionConvertParallelLoopToGpuPass,
-    mlirCreateConversionConvertPDLToPDLInterpPass,
-    mlirCreateConversionConvertSCFToOpenMPPass,
-    mlirCreateConversionConvertShapeConstraintsPass,
-    mlirCreateConversionConvertShapeToStandardPass,
-    mlirCreateConversionConvertSPIRVToLLVMPass,
-    mlirCreateConversionConvertTensorToLinalgPass,
-    mlirCreateConversionConvertTensorToSPIRVPass,
-    mlirCreateConversionConvertToLLVMPass,
-    mlirCreateConversionConvertVectorToArmSMEPass,
-    mlirCreateConversionConvertVectorToGPU,
-    mlirCreateConversionConvertVectorToLLVMPass,
-    mlirCreateConversionConvertVectorToSCF,
-    mlirCreateConversionConvertVectorToSPIRVPass,
-    mlirCreateConversionConvertVectorToXeGPU,
-    mlirCreateConversionFinalizeMemRefToLLVMConversionPass,
-    mlirCreateConversionGpuToLLVMConversionPass,
-    mlirCreateConversionLiftControlFlowToSCFPass,
-    mlirCreateConversionLowerAffinePass,
-    mlirCreateConversionLowerHostCodeToLLVMPass,
-    mlirCreateConversionMapMemRefStorageClass,
-    mlirCreateConversionReconcileUnrealizedCastsPass,
-    mlirCreateConversionSCFToControlFlowPass,
-    mlirCreateConversionSCFToEmitC,
-    mlirCreateConversionSCFToSPIRV,
-    mlirCreateConversionSetLLVMModuleDataLayoutPass,
-    mlirCreateConversionTosaToArithPass,
-    mlirCreateConversionTosaToLinalg,
-    mlirCreateConversionTosaToLinalgNamed,
-    mlirCreateConversionTosaToMLProgram,
-    mlirCreateConversionTosaToSCFPass,
-    mlirCreateConversionTosaToTensorPass,
-    mlirCreateConversionUBToLLVMConversionPass,
-    mlirCreateConversionUBToSPIRVConversionPass,
-);
+melior_macro::passes_from_tablegen! {
+    prefix: "Conversion",
+    files: ["mlir/Conversion/Passes.td"],
+    strip_prefixes: ["Convert"],
+    strip_suffixes: ["ConversionPass", "Pass"],
+}
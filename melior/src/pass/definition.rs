@@ -0,0 +1,321 @@
+use super::{create_external, options::parse_pass_options, Pass, PassStatistic, RunExternalPass};
+use crate::{dialect::DialectHandle, ir::r#type::TypeId, Error};
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+};
+
+/// A definition of a pass written in Rust.
+///
+/// This trait is usually implemented with the [`pass`](macro@crate::pass)
+/// attribute macro, which generates a type ID, option parsing and statistics
+/// of a pass from its struct definition.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{
+///     ir::OperationRef,
+///     pass::{ExternalPass, PassDefinition, PassStatistic, RunExternalPass},
+///     ContextRef,
+/// };
+///
+/// #[melior::pass(
+///     name = "MyPass",
+///     argument = "my-pass",
+///     description = "Counts operations.",
+///     anchor = "func.func",
+///     dependent_dialects = [func],
+/// )]
+/// #[derive(Clone, Debug)]
+/// struct MyPass {
+///     #[option(description = "A threshold.", default = 2)]
+///     threshold: u32,
+///     #[statistic(description = "Number of runs.")]
+///     runs: PassStatistic,
+/// }
+///
+/// impl<'c> RunExternalPass<'c> for MyPass {
+///     fn initialize(&mut self, _context: ContextRef<'c>) {}
+///
+///     fn run(&mut self, _operation: OperationRef<'c, '_>, _pass: ExternalPass<'_>) {
+///         self.runs.increment();
+///     }
+/// }
+///
+/// let mut pass = MyPass::default();
+///
+/// pass.parse_options("threshold=4").unwrap();
+///
+/// assert_eq!(pass.threshold, 4);
+/// assert_eq!(pass.statistics()[0].name(), "runs");
+/// ```
+pub trait PassDefinition<'c>: RunExternalPass<'c> {
+    /// A pass name.
+    const NAME: &'static str;
+    /// A pass argument in textual pass pipelines.
+    const ARGUMENT: &'static str;
+    /// A pass description.
+    const DESCRIPTION: &'static str;
+    /// An operation name which a pass is anchored on, or an empty string for
+    /// any operations.
+    const ANCHOR: &'static str;
+
+    /// Returns a type ID of a pass.
+    fn type_id() -> TypeId<'static>;
+
+    /// Returns handles of dialects loaded before a pass runs.
+    fn dependent_dialects() -> Vec<DialectHandle> {
+        vec![]
+    }
+
+    /// Sets an option by its key and textual value.
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), Error>;
+
+    /// Formats options in the textual pass option syntax, such as
+    /// `threshold=4 verbose=true`.
+    fn format_options(&self, _formatter: &mut Formatter) -> fmt::Result {
+        Ok(())
+    }
+
+    /// Returns statistics of a pass.
+    ///
+    /// Statistics are attached to passes created by
+    /// [`into_pass`](Self::into_pass) and [`from_options`](Self::from_options),
+    /// and collected by pass managers they are added to.
+    fn statistics(&self) -> Vec<PassStatistic> {
+        vec![]
+    }
+
+    /// Parses textual options, such as `threshold=4 verbose`, into a pass.
+    fn parse_options(&mut self, options: &str) -> Result<(), Error> {
+        for (key, value) in parse_pass_options(options)? {
+            self.set_option(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a pass from textual options with default values of the other
+    /// fields.
+    ///
+    /// Options are attached to a pass with [`Pass::with_options`], so that
+    /// printed pass pipelines include them, such as `my-pass{threshold=4}`.
+    fn from_options(options: &str) -> Result<Pass, Error>
+    where
+        Self: Default,
+    {
+        let mut definition = Self::default();
+
+        definition.parse_options(options)?;
+
+        Ok(create_pass(definition, options.trim().into()))
+    }
+
+    /// Converts a definition into a pass.
+    ///
+    /// All options are attached to a pass with [`Pass::with_options`], so
+    /// that printed pass pipelines can be parsed back into the same pass.
+    fn into_pass(self) -> Pass {
+        let options = FormattedOptions(&self, PhantomData).to_string();
+
+        create_pass(self, options)
+    }
+}
+
+fn create_pass<'c, T: PassDefinition<'c>>(definition: T, options: String) -> Pass {
+    let statistics = definition.statistics();
+
+    create_external(
+        definition,
+        T::type_id(),
+        T::NAME,
+        T::ARGUMENT,
+        T::DESCRIPTION,
+        T::ANCHOR,
+        &T::dependent_dialects(),
+    )
+    .with_statistics(statistics)
+    .with_options(options)
+}
+
+struct FormattedOptions<'a, 'c, T>(&'a T, PhantomData<&'c ()>);
+
+impl<'c, T: PassDefinition<'c>> Display for FormattedOptions<'_, 'c, T> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.0.format_options(formatter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{operation::OperationLike, Module, OperationRef},
+        pass::{ExternalPass, PassInstrumentation, PassManager},
+        test::create_test_context,
+        ContextRef,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use std::sync::{Arc, Mutex};
+
+    #[melior::pass(
+        name = "CountPass",
+        argument = "count",
+        description = "Counts functions.",
+        anchor = "func.func",
+        dependent_dialects = [func]
+    )]
+    #[derive(Clone, Debug)]
+    struct CountPass {
+        #[option(description = "An increment.", default = 1)]
+        increment: u64,
+        #[option(argument = "names", description = "Function names.")]
+        function_names: Vec<String>,
+        #[statistic(name = "num-functions", description = "Number of functions.")]
+        functions: PassStatistic,
+    }
+
+    impl<'c> RunExternalPass<'c> for CountPass {
+        fn initialize(&mut self, _context: ContextRef<'c>) {}
+
+        fn run(&mut self, operation: OperationRef<'c, '_>, _pass: ExternalPass<'_>) {
+            assert_eq!(operation.name().as_string_ref().as_str(), Ok("func.func"));
+
+            self.functions.add(self.increment);
+        }
+    }
+
+    #[test]
+    fn default_options() {
+        let pass = CountPass::default();
+
+        assert_eq!(pass.increment, 1);
+        assert_eq!(pass.function_names, Vec::<String>::new());
+        assert_eq!(pass.functions.pass(), "count");
+        assert_eq!(pass.functions.name(), "num-functions");
+        assert_eq!(pass.functions.description(), "Number of functions.");
+    }
+
+    #[test]
+    fn parse_options() {
+        let mut pass = CountPass::default();
+
+        pass.parse_options("increment=3 names=foo,{bar baz}")
+            .unwrap();
+
+        assert_eq!(pass.increment, 3);
+        assert_eq!(pass.function_names, ["foo", "bar baz"]);
+    }
+
+    #[test]
+    fn fail_to_parse_options() {
+        let mut pass = CountPass::default();
+
+        assert_eq!(
+            pass.parse_options("increment=foo"),
+            Err(Error::ParsePassOption(
+                "invalid value `foo` of option `increment`".into()
+            ))
+        );
+        assert_eq!(
+            pass.parse_options("foo=1"),
+            Err(Error::ParsePassOption(
+                "unknown option `foo` of pass `count`".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn round_trip_options() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+        let mut pass = CountPass::default();
+
+        pass.parse_options("increment=3 names=foo,{bar baz}")
+            .unwrap();
+        manager.nested_under("func.func").add_pass(pass.into_pass());
+
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "any(func.func(count{increment=3 names=foo,{bar baz}}))"
+        );
+
+        let pass = CountPass::from_options("increment=3 names=foo,{bar baz}").unwrap();
+        let manager = PassManager::new(&context);
+
+        manager.nested_under("func.func").add_pass(pass);
+
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "any(func.func(count{increment=3 names=foo,{bar baz}}))"
+        );
+    }
+
+    #[test]
+    fn keep_pass_argument_without_options() {
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl<'c> PassInstrumentation<'c> for Recorder {
+            fn run_before_pass(
+                &mut self,
+                pass: &str,
+                _operation: OperationRef<'c, '_>,
+            ) -> Result<(), String> {
+                self.0.lock().unwrap().push(pass.into());
+                Ok(())
+            }
+        }
+
+        let context = create_test_context();
+        let mut module = Module::parse(&context, "func.func @foo() { return }").unwrap();
+        let manager = PassManager::new(&context);
+        let passes = Arc::new(Mutex::new(vec![]));
+
+        manager.add_instrumentation(Recorder(passes.clone()));
+        manager
+            .nested_under("func.func")
+            .add_pass(CountPass::from_options("increment=3").unwrap());
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(*passes.lock().unwrap(), ["count"]);
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "any(func.func(count{increment=3}))"
+        );
+    }
+
+    #[test]
+    fn run_pass() {
+        let context = create_test_context();
+        let mut module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo() {
+                    return
+                }
+
+                func.func @bar() {
+                    return
+                }
+                "
+            ),
+        )
+        .unwrap();
+        let manager = PassManager::new(&context);
+        let mut pass = CountPass::default();
+
+        pass.parse_options("increment=2").unwrap();
+
+        manager.nested_under("func.func").add_pass(pass.into_pass());
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(manager.statistics()[0].value(), 4);
+
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(manager.statistics()[0].value(), 4);
+    }
+}
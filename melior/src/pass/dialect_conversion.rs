@@ -0,0 +1,1140 @@
+//! Dialect conversion.
+//!
+//! The MLIR C API does not expose the dialect conversion framework. This
+//! module implements a subset of it on top of rewriters. Values whose types
+//! are converted are bridged with `builtin.unrealized_conversion_cast`
+//! operations by default, so conversions compose with upstream conversion
+//! passes followed by the `reconcile-unrealized-casts` pass.
+//!
+//! Conversions are applied to a clone of an operation, which replaces the
+//! regions of the original operation only on success. Therefore, the original
+//! operation is left unchanged on failure.
+
+use super::pattern::Worklist;
+use crate::{
+    context::Context,
+    dialect::llvm,
+    ir::{
+        attribute::{IntegerAttribute, TypeAttribute},
+        operation::{
+            OperationBuilder, OperationLike, OperationMutLike, OperationRefMut, WalkOrder,
+            WalkResult,
+        },
+        r#type::{FunctionType, IntegerType, MemRefType},
+        BlockLike, Location, Operation, OperationRef, RegionLike, RegionRef, Rewriter,
+        ShapedTypeLike, Type, TypeLike, Value, ValueLike,
+    },
+    Error,
+};
+use mlir_sys::{mlirOperationClone, mlirRegionTakeBody, MlirOperation};
+use std::{collections::HashSet, ops::Deref};
+
+const UNREALIZED_CONVERSION_CAST: &str = "builtin.unrealized_conversion_cast";
+
+type TypeConversion<'c> = Box<dyn Fn(Type<'c>) -> Option<Type<'c>> + 'c>;
+type Materialization<'c> =
+    Box<dyn for<'a> Fn(Type<'c>, Value<'c, 'a>, Location<'c>) -> Option<Operation<'c>> + 'c>;
+type LegalityCallback<'c> = Box<dyn for<'a> Fn(OperationRef<'c, 'a>) -> bool + 'c>;
+
+/// A conversion target.
+///
+/// It decides which operations are legal after conversion. Legality of
+/// operations takes precedence over legality of their dialects.
+#[derive(Default)]
+pub struct ConversionTarget<'c> {
+    legal_dialects: HashSet<String>,
+    illegal_dialects: HashSet<String>,
+    legal_operations: HashSet<String>,
+    illegal_operations: HashSet<String>,
+    dynamically_legal_operations: Vec<(String, LegalityCallback<'c>)>,
+}
+
+impl<'c> ConversionTarget<'c> {
+    /// Creates a conversion target.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks a dialect legal.
+    pub fn add_legal_dialect(&mut self, name: &str) {
+        self.illegal_dialects.remove(name);
+        self.legal_dialects.insert(name.into());
+    }
+
+    /// Marks a dialect illegal.
+    pub fn add_illegal_dialect(&mut self, name: &str) {
+        self.legal_dialects.remove(name);
+        self.illegal_dialects.insert(name.into());
+    }
+
+    /// Marks an operation legal.
+    pub fn add_legal_operation(&mut self, name: &str) {
+        self.illegal_operations.remove(name);
+        self.legal_operations.insert(name.into());
+    }
+
+    /// Marks an operation illegal.
+    pub fn add_illegal_operation(&mut self, name: &str) {
+        self.legal_operations.remove(name);
+        self.illegal_operations.insert(name.into());
+    }
+
+    /// Marks an operation legal if a callback returns `true`.
+    pub fn add_dynamically_legal_operation(
+        &mut self,
+        name: &str,
+        callback: impl Fn(OperationRef<'c, '_>) -> bool + 'c,
+    ) {
+        self.dynamically_legal_operations
+            .push((name.into(), Box::new(callback)));
+    }
+
+    /// Returns legality of an operation, or `None` if it is unknown.
+    pub fn is_legal(&self, operation: OperationRef<'c, '_>) -> Option<bool> {
+        let name = operation.name();
+        let name = name.as_string_ref().as_str().ok()?;
+
+        if name == UNREALIZED_CONVERSION_CAST {
+            return Some(true);
+        }
+
+        if let Some((_, callback)) = self
+            .dynamically_legal_operations
+            .iter()
+            .rev()
+            .find(|(other, _)| other == name)
+        {
+            return Some(callback(operation));
+        }
+
+        if self.legal_operations.contains(name) {
+            return Some(true);
+        } else if self.illegal_operations.contains(name) {
+            return Some(false);
+        }
+
+        let (dialect, _) = name.split_once('.')?;
+
+        if self.legal_dialects.contains(dialect) {
+            Some(true)
+        } else if self.illegal_dialects.contains(dialect) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// A type converter.
+///
+/// Type conversions are tried from the last added one. Materializations
+/// build operations with single results that convert values between source
+/// and target types. `builtin.unrealized_conversion_cast` operations are used
+/// when no materialization applies.
+#[derive(Default)]
+pub struct TypeConverter<'c> {
+    conversions: Vec<TypeConversion<'c>>,
+    source_materializations: Vec<Materialization<'c>>,
+    target_materializations: Vec<Materialization<'c>>,
+}
+
+impl<'c> TypeConverter<'c> {
+    /// Creates a type converter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a type converter into types of the `llvm` dialect.
+    ///
+    /// It covers only a subset of `LLVMTypeConverter` in MLIR and converts
+    /// the following types:
+    ///
+    /// - `index` types into integer types of a bit width.
+    /// - Integer, floating-point, and vector types and types of the `llvm`
+    ///   dialect into themselves.
+    /// - Ranked memref types with integer memory spaces and convertible element
+    ///   types into memref descriptors of
+    ///   `!llvm.struct<(ptr, ptr, iN, array<R x iN>, array<R x iN>)>`, where
+    ///   the arrays are omitted for rank 0.
+    /// - Unranked memref types into `!llvm.struct<(iN, ptr)>`.
+    ///
+    /// Other types, such as complex, function, and tuple types, are not
+    /// converted. Element types of vector types are not converted and
+    /// multi-dimensional vector types are not converted into arrays either.
+    /// Memref descriptors match ones of `LLVMTypeConverter` with its default
+    /// options and the same bit width of `index` types. The `llvm` dialect
+    /// must be loaded.
+    pub fn llvm(context: &'c Context, index_bit_width: u32) -> Self {
+        let mut converter = Self::new();
+
+        converter.add_conversion(move |r#type| llvm_type(context, index_bit_width, r#type));
+
+        converter
+    }
+
+    /// Adds a type conversion.
+    ///
+    /// A conversion returns `None` to let other conversions convert a type.
+    pub fn add_conversion(&mut self, conversion: impl Fn(Type<'c>) -> Option<Type<'c>> + 'c) {
+        self.conversions.push(Box::new(conversion));
+    }
+
+    /// Adds a source materialization that converts a value of a target type
+    /// back into a source type.
+    pub fn add_source_materialization(
+        &mut self,
+        materialization: impl Fn(Type<'c>, Value<'c, '_>, Location<'c>) -> Option<Operation<'c>> + 'c,
+    ) {
+        self.source_materializations.push(Box::new(materialization));
+    }
+
+    /// Adds a target materialization that converts a value of a source type
+    /// into a target type.
+    pub fn add_target_materialization(
+        &mut self,
+        materialization: impl Fn(Type<'c>, Value<'c, '_>, Location<'c>) -> Option<Operation<'c>> + 'c,
+    ) {
+        self.target_materializations.push(Box::new(materialization));
+    }
+
+    /// Converts a type.
+    pub fn convert_type(&self, r#type: Type<'c>) -> Option<Type<'c>> {
+        self.conversions
+            .iter()
+            .rev()
+            .find_map(|conversion| conversion(r#type))
+    }
+
+    /// Converts a function type.
+    ///
+    /// It returns `None` if any of input or result types cannot be converted.
+    pub fn convert_function_type(&self, r#type: FunctionType<'c>) -> Option<FunctionType<'c>> {
+        let convert = |types: &mut dyn Iterator<Item = Result<Type<'c>, Error>>| {
+            types
+                .map(|r#type| self.convert_type(r#type.ok()?))
+                .collect::<Option<Vec<_>>>()
+        };
+
+        Some(FunctionType::new(
+            unsafe { r#type.context().to_ref() },
+            &convert(&mut (0..r#type.input_count()).map(|index| r#type.input(index)))?,
+            &convert(&mut (0..r#type.result_count()).map(|index| r#type.result(index)))?,
+        ))
+    }
+
+    /// Returns `true` if a type is legal.
+    pub fn is_legal_type(&self, r#type: Type<'c>) -> bool {
+        self.convert_type(r#type) == Some(r#type)
+    }
+
+    /// Returns `true` if all operand and result types of an operation are
+    /// legal.
+    pub fn is_legal_operation(&self, operation: &impl OperationLike<'c, '_>) -> bool {
+        operation
+            .operands()
+            .all(|operand| self.is_legal_type(operand.r#type()))
+            && operation
+                .results()
+                .all(|result| self.is_legal_type(result.r#type()))
+    }
+
+    fn materialize<'a>(
+        materializations: &[Materialization<'c>],
+        rewriter: &'a Rewriter<'c>,
+        r#type: Type<'c>,
+        value: Value<'c, '_>,
+        location: Location<'c>,
+    ) -> Result<OperationRef<'c, 'a>, Error> {
+        let operation = match materializations
+            .iter()
+            .rev()
+            .find_map(|materialization| materialization(r#type, value, location))
+        {
+            Some(operation) => operation,
+            None => OperationBuilder::new(UNREALIZED_CONVERSION_CAST, location)
+                .add_operands(&[value])
+                .add_results(&[r#type])
+                .build()?,
+        };
+        let operation = rewriter.insert(operation)?;
+
+        // Check if a materialization has a result.
+        operation.result(0)?;
+
+        Ok(operation)
+    }
+}
+
+fn llvm_type<'c>(context: &'c Context, index_bit_width: u32, r#type: Type<'c>) -> Option<Type<'c>> {
+    let index = IntegerType::new(context, index_bit_width).into();
+
+    if r#type.is_index() {
+        Some(index)
+    } else if r#type.is_integer()
+        || r#type.is_float()
+        || r#type.is_vector()
+        || r#type.dialect().namespace() == Ok("llvm")
+    {
+        Some(r#type)
+    } else if let Ok(memref) = MemRefType::try_from(r#type) {
+        llvm_type(context, index_bit_width, memref.element())?;
+
+        let pointer = llvm::r#type::pointer(
+            context,
+            match memref.memory_space() {
+                Some(space) => IntegerAttribute::try_from(space).ok()?.value() as u32,
+                None => 0,
+            },
+        );
+        let mut fields = vec![pointer, pointer, index];
+
+        if memref.rank() > 0 {
+            let array = llvm::r#type::array(index, memref.rank() as u32);
+
+            fields.extend([array, array]);
+        }
+
+        Some(llvm::r#type::r#struct(context, &fields, false))
+    } else if r#type.is_unranked_mem_ref() {
+        Some(llvm::r#type::r#struct(
+            context,
+            &[index, llvm::r#type::pointer(context, 0)],
+            false,
+        ))
+    } else {
+        None
+    }
+}
+
+/// A rewriter given to conversion patterns.
+///
+/// It dereferences to an underlying rewriter.
+pub struct ConversionRewriter<'c, 'a> {
+    rewriter: &'a Rewriter<'c>,
+    type_converter: &'a TypeConverter<'c>,
+}
+
+impl<'c> ConversionRewriter<'c, '_> {
+    /// Returns a type converter.
+    pub fn type_converter(&self) -> &TypeConverter<'c> {
+        self.type_converter
+    }
+
+    /// Replaces an operation with values and erases it.
+    ///
+    /// Values of types different from result types are converted back into
+    /// the result types with source materializations. It fails without
+    /// mutating IR if the number of values is different from the number of
+    /// results.
+    pub fn replace_operation_with_values(
+        &self,
+        operation: OperationRef<'c, '_>,
+        values: &[Value<'c, '_>],
+    ) -> Result<(), Error> {
+        if operation.result_count() != values.len() {
+            return Err(Error::ResultCount {
+                operation: operation.to_string(),
+                expected: operation.result_count(),
+                actual: values.len(),
+            });
+        }
+
+        let _guard = self.rewriter.insertion_guard();
+        self.rewriter.set_insertion_point_before(&operation);
+
+        let values = operation
+            .results()
+            .zip(values)
+            .map(|(result, &value)| {
+                Ok(if result.r#type() == value.r#type() {
+                    value
+                } else {
+                    TypeConverter::materialize(
+                        &self.type_converter.source_materializations,
+                        self.rewriter,
+                        result.r#type(),
+                        value,
+                        operation.location(),
+                    )?
+                    .result(0)?
+                    .into()
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.rewriter
+            .replace_operation_with_values(operation, &values);
+
+        Ok(())
+    }
+
+    /// Converts types of block arguments in a region.
+    ///
+    /// Uses of converted block arguments are replaced with values converted
+    /// back into original types with source materializations.
+    pub fn convert_region_types(&self, region: RegionRef<'c, '_>) -> Result<(), Error> {
+        let _guard = self.rewriter.insertion_guard();
+        let mut block = region.first_block();
+
+        while let Some(current) = block {
+            self.rewriter.set_insertion_point_to_start(&current);
+
+            for index in 0..current.argument_count() {
+                let argument = current.argument(index)?;
+                let r#type = argument.r#type();
+
+                let Some(converted) = self.type_converter.convert_type(r#type) else {
+                    continue;
+                };
+
+                if converted == r#type {
+                    continue;
+                }
+
+                argument.set_type(converted);
+
+                let materialization = TypeConverter::materialize(
+                    &self.type_converter.source_materializations,
+                    self.rewriter,
+                    r#type,
+                    argument.into(),
+                    argument.owner().parent_operation().map_or_else(
+                        || Location::unknown(unsafe { r#type.context().to_ref() }),
+                        |operation| operation.location(),
+                    ),
+                )?;
+
+                self.rewriter.replace_all_uses_except(
+                    argument.into(),
+                    materialization.result(0)?.into(),
+                    &[materialization],
+                );
+            }
+
+            block = current.next_in_region();
+        }
+
+        Ok(())
+    }
+}
+
+impl<'c> Deref for ConversionRewriter<'c, '_> {
+    type Target = Rewriter<'c>;
+
+    fn deref(&self) -> &Self::Target {
+        self.rewriter
+    }
+}
+
+/// A conversion pattern.
+pub trait ConversionPattern<'c> {
+    /// Returns a name of root operations, or `None` to match any operations.
+    fn root(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns a benefit.
+    ///
+    /// Patterns with larger benefits are applied first.
+    fn benefit(&self) -> usize {
+        1
+    }
+
+    /// Matches and rewrites an operation.
+    ///
+    /// Operands are already converted by a type converter. Returns `true` if
+    /// the operation is rewritten. An error fails the whole conversion.
+    fn match_and_rewrite(
+        &self,
+        operation: OperationRef<'c, '_>,
+        operands: &[Value<'c, '_>],
+        rewriter: &ConversionRewriter<'c, '_>,
+    ) -> Result<bool, Error>;
+}
+
+/// A conversion pattern that converts signatures of function-like operations.
+///
+/// It converts `function_type` attributes of builtin function types and
+/// block argument types of the first regions of operations, such as
+/// `func.func`.
+pub struct FunctionSignatureConversion {
+    name: String,
+}
+
+impl FunctionSignatureConversion {
+    /// Creates a conversion pattern for operations of a name.
+    pub fn new(name: &str) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl<'c> ConversionPattern<'c> for FunctionSignatureConversion {
+    fn root(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn match_and_rewrite(
+        &self,
+        operation: OperationRef<'c, '_>,
+        _operands: &[Value<'c, '_>],
+        rewriter: &ConversionRewriter<'c, '_>,
+    ) -> Result<bool, Error> {
+        let r#type = FunctionType::try_from(
+            TypeAttribute::try_from(operation.attribute("function_type")?)?.value(),
+        )?;
+        let Some(converted) = rewriter.type_converter().convert_function_type(r#type) else {
+            return Ok(false);
+        };
+
+        if converted == r#type {
+            return Ok(false);
+        }
+
+        rewriter.modify_operation_in_place(operation, |operation| {
+            unsafe { OperationRefMut::from_raw(operation.to_raw()) }
+                .set_attribute("function_type", TypeAttribute::new(converted.into()).into());
+
+            true
+        });
+
+        if let Ok(region) = operation.region(0) {
+            rewriter.convert_region_types(region)?;
+        }
+
+        Ok(true)
+    }
+}
+
+/// A set of conversion patterns.
+#[derive(Default)]
+pub struct ConversionPatternSet<'c> {
+    patterns: Vec<Box<dyn ConversionPattern<'c> + 'c>>,
+}
+
+impl<'c> ConversionPatternSet<'c> {
+    /// Creates a conversion pattern set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pattern.
+    pub fn add(&mut self, pattern: impl ConversionPattern<'c> + 'c) {
+        self.patterns.push(Box::new(pattern));
+        // The sort is stable, so patterns with the same benefit keep their order.
+        self.patterns
+            .sort_by_key(|pattern| std::cmp::Reverse(pattern.benefit()));
+    }
+}
+
+/// A configuration of dialect conversion.
+#[derive(Clone, Copy, Debug)]
+pub struct ConversionConfig {
+    max_iterations: usize,
+}
+
+impl ConversionConfig {
+    /// Creates a configuration.
+    pub const fn new() -> Self {
+        Self { max_iterations: 32 }
+    }
+
+    /// Sets a maximum number of iterations over IR.
+    pub const fn max_iterations(mut self, count: usize) -> Self {
+        self.max_iterations = count;
+        self
+    }
+}
+
+impl Default for ConversionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Applies a partial conversion to operations nested in an operation.
+///
+/// Operations of unknown legality are allowed to remain.
+pub fn apply_partial_conversion<'c>(
+    operation: &impl OperationLike<'c, '_>,
+    target: &ConversionTarget<'c>,
+    patterns: &ConversionPatternSet<'c>,
+    type_converter: &TypeConverter<'c>,
+    config: &ConversionConfig,
+) -> Result<(), Error> {
+    apply_conversion(
+        operation,
+        target,
+        patterns,
+        type_converter,
+        config,
+        |legal| legal != Some(false),
+    )
+}
+
+/// Applies a full conversion to operations nested in an operation.
+///
+/// All operations must be legal after conversion.
+pub fn apply_full_conversion<'c>(
+    operation: &impl OperationLike<'c, '_>,
+    target: &ConversionTarget<'c>,
+    patterns: &ConversionPatternSet<'c>,
+    type_converter: &TypeConverter<'c>,
+    config: &ConversionConfig,
+) -> Result<(), Error> {
+    apply_conversion(
+        operation,
+        target,
+        patterns,
+        type_converter,
+        config,
+        |legal| legal == Some(true),
+    )
+}
+
+fn apply_conversion<'c>(
+    operation: &impl OperationLike<'c, '_>,
+    target: &ConversionTarget<'c>,
+    patterns: &ConversionPatternSet<'c>,
+    type_converter: &TypeConverter<'c>,
+    config: &ConversionConfig,
+    accept: impl Fn(Option<bool>) -> bool,
+) -> Result<(), Error> {
+    let clone = unsafe { Operation::from_raw(mlirOperationClone(operation.to_raw())) };
+
+    convert_operations(&clone, target, patterns, type_converter, config)?;
+
+    for nested in collect_operations(&clone) {
+        let nested = unsafe { OperationRef::from_raw(nested) };
+
+        if !accept(target.is_legal(nested)) {
+            return Err(Error::ApplyConversion(
+                nested
+                    .name()
+                    .as_string_ref()
+                    .as_str()
+                    .unwrap_or_default()
+                    .into(),
+            ));
+        }
+    }
+
+    for (region, converted) in operation.regions().zip(clone.regions()) {
+        unsafe { mlirRegionTakeBody(region.to_raw(), converted.to_raw()) }
+    }
+
+    Ok(())
+}
+
+fn convert_operations<'c>(
+    operation: &Operation<'c>,
+    target: &ConversionTarget<'c>,
+    patterns: &ConversionPatternSet<'c>,
+    type_converter: &TypeConverter<'c>,
+    config: &ConversionConfig,
+) -> Result<(), Error> {
+    let worklist = Worklist::default();
+    let rewriter = Rewriter::from_operation(operation).with_listener(worklist.clone());
+    let rewriter = ConversionRewriter {
+        rewriter: &rewriter,
+        type_converter,
+    };
+
+    for _ in 0..config.max_iterations {
+        for operation in collect_operations(operation) {
+            worklist.push(operation);
+        }
+
+        let mut changed = false;
+
+        while let Some(operation) = worklist.pop() {
+            let operation = unsafe { OperationRef::from_raw(operation) };
+
+            if target.is_legal(operation) == Some(true) {
+                continue;
+            }
+
+            changed |= convert_operation(operation, patterns, &rewriter)?;
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn collect_operations<'c>(operation: &impl OperationLike<'c, '_>) -> Vec<MlirOperation> {
+    let root = operation.to_raw().ptr;
+    let mut operations = vec![];
+
+    operation.walk(WalkOrder::PreOrder, |nested| {
+        if nested.to_raw().ptr != root {
+            operations.push(nested.to_raw());
+        }
+
+        WalkResult::Advance
+    });
+
+    operations
+}
+
+fn convert_operation<'c>(
+    operation: OperationRef<'c, '_>,
+    patterns: &ConversionPatternSet<'c>,
+    rewriter: &ConversionRewriter<'c, '_>,
+) -> Result<bool, Error> {
+    let name = operation.name();
+    let name = name.as_string_ref().as_str().ok();
+
+    for pattern in patterns
+        .patterns
+        .iter()
+        .filter(|pattern| pattern.root().is_none() || pattern.root() == name)
+    {
+        rewriter.set_insertion_point_before(&operation);
+
+        let mut materializations = vec![];
+        let mut operands = Vec::<(Value, Value)>::new();
+
+        for operand in operation.operands() {
+            if let Some((_, converted)) = operands.iter().find(|(other, _)| *other == operand) {
+                operands.push((operand, *converted));
+                continue;
+            }
+
+            let converted = match rewriter.type_converter.convert_type(operand.r#type()) {
+                Some(r#type) if r#type != operand.r#type() => {
+                    let materialization = TypeConverter::materialize(
+                        &rewriter.type_converter.target_materializations,
+                        rewriter.rewriter,
+                        r#type,
+                        operand,
+                        operation.location(),
+                    )?;
+
+                    materializations.push(materialization);
+                    materialization.result(0)?.into()
+                }
+                _ => operand,
+            };
+
+            operands.push((operand, converted));
+        }
+
+        let operands = operands
+            .into_iter()
+            .map(|(_, converted)| converted)
+            .collect::<Vec<_>>();
+
+        if pattern.match_and_rewrite(operation, &operands, rewriter)? {
+            return Ok(true);
+        }
+
+        for materialization in materializations.into_iter().rev() {
+            rewriter.erase_operation(materialization);
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        dialect::{arith, func},
+        ir::{operation::CallableOpInterface, Module},
+        test::{create_test_context, find_operation},
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    // Converts `index` additions into 64-bit integer additions.
+    struct AddIndex;
+
+    impl<'c> ConversionPattern<'c> for AddIndex {
+        fn root(&self) -> Option<&str> {
+            Some("arith.addi")
+        }
+
+        fn match_and_rewrite(
+            &self,
+            operation: OperationRef<'c, '_>,
+            operands: &[Value<'c, '_>],
+            rewriter: &ConversionRewriter<'c, '_>,
+        ) -> Result<bool, Error> {
+            let addition =
+                rewriter.insert(arith::addi(operands[0], operands[1], operation.location()))?;
+
+            rewriter.replace_operation_with_values(operation, &[addition.result(0)?.into()])?;
+
+            Ok(true)
+        }
+    }
+
+    // Converts returns of `index` values.
+    struct Return;
+
+    impl<'c> ConversionPattern<'c> for Return {
+        fn root(&self) -> Option<&str> {
+            Some("func.return")
+        }
+
+        fn match_and_rewrite(
+            &self,
+            operation: OperationRef<'c, '_>,
+            operands: &[Value<'c, '_>],
+            rewriter: &ConversionRewriter<'c, '_>,
+        ) -> Result<bool, Error> {
+            rewriter.insert(func::r#return(operands, operation.location()))?;
+            rewriter.erase_operation(operation);
+
+            Ok(true)
+        }
+    }
+
+    // Fails on any operation.
+    struct Fail;
+
+    impl<'c> ConversionPattern<'c> for Fail {
+        fn match_and_rewrite(
+            &self,
+            _operation: OperationRef<'c, '_>,
+            _operands: &[Value<'c, '_>],
+            _rewriter: &ConversionRewriter<'c, '_>,
+        ) -> Result<bool, Error> {
+            Err(Error::OperationBuild)
+        }
+    }
+
+    // Replaces operations with no values.
+    struct ReplaceWithNothing;
+
+    impl<'c> ConversionPattern<'c> for ReplaceWithNothing {
+        fn match_and_rewrite(
+            &self,
+            operation: OperationRef<'c, '_>,
+            _operands: &[Value<'c, '_>],
+            rewriter: &ConversionRewriter<'c, '_>,
+        ) -> Result<bool, Error> {
+            rewriter.replace_operation_with_values(operation, &[])?;
+
+            Ok(true)
+        }
+    }
+
+    fn type_converter(context: &Context) -> TypeConverter<'_> {
+        let mut converter = TypeConverter::new();
+
+        converter.add_conversion(Some);
+        converter.add_conversion(|r#type| {
+            r#type
+                .is_index()
+                .then(|| IntegerType::new(context, 64).into())
+        });
+
+        converter
+    }
+
+    fn has_index_operand_or_result(operation: OperationRef) -> bool {
+        operation
+            .operands()
+            .any(|operand| operand.r#type().is_index())
+            || operation.results().any(|result| result.r#type().is_index())
+    }
+
+    const SOURCE: &str = indoc!(
+        "
+        func.func @foo(%arg0 : index) -> index {
+            %0 = arith.addi %arg0, %arg0 : index
+            return %0 : index
+        }
+        "
+    );
+
+    #[test]
+    fn convert_type() {
+        let context = create_test_context();
+        let converter = type_converter(&context);
+
+        assert_eq!(
+            converter.convert_type(Type::index(&context)),
+            Some(IntegerType::new(&context, 64).into())
+        );
+        assert!(converter.is_legal_type(IntegerType::new(&context, 32).into()));
+        assert!(!converter.is_legal_type(Type::index(&context)));
+        assert!(!converter.is_legal_operation(
+            &Module::parse(&context, SOURCE)
+                .unwrap()
+                .body()
+                .first_operation()
+                .unwrap()
+        ));
+    }
+
+    #[test]
+    fn apply_partial_conversion() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let converter = type_converter(&context);
+        let mut target = ConversionTarget::new();
+        let mut patterns = ConversionPatternSet::new();
+
+        target.add_dynamically_legal_operation("arith.addi", |operation| {
+            !operation.results().any(|result| result.r#type().is_index())
+        });
+        patterns.add(AddIndex);
+
+        super::apply_partial_conversion(
+            &module.as_operation(),
+            &target,
+            &patterns,
+            &converter,
+            &ConversionConfig::new(),
+        )
+        .unwrap();
+
+        assert!(module.as_operation().verify());
+        insta::assert_snapshot!(module.as_operation());
+    }
+
+    #[test]
+    fn apply_full_conversion() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let converter = type_converter(&context);
+        let mut target = ConversionTarget::new();
+        let mut patterns = ConversionPatternSet::new();
+
+        target.add_dynamically_legal_operation("func.func", |operation| {
+            let callable = CallableOpInterface::new(operation).unwrap();
+
+            !callable
+                .argument_types()
+                .into_iter()
+                .chain(callable.result_types())
+                .any(|r#type| r#type.is_index())
+        });
+        target.add_dynamically_legal_operation("func.return", |operation| {
+            !has_index_operand_or_result(operation)
+        });
+        target.add_dynamically_legal_operation("arith.addi", |operation| {
+            !has_index_operand_or_result(operation)
+        });
+        patterns.add(FunctionSignatureConversion::new("func.func"));
+        patterns.add(Return);
+        patterns.add(AddIndex);
+
+        super::apply_full_conversion(
+            &module.as_operation(),
+            &target,
+            &patterns,
+            &converter,
+            &ConversionConfig::new(),
+        )
+        .unwrap();
+
+        let function = find_operation(&module, "func.func");
+        let r#type = IntegerType::new(&context, 64).into();
+
+        assert!(module.as_operation().verify());
+        assert_eq!(
+            TypeAttribute::try_from(function.attribute("function_type").unwrap())
+                .unwrap()
+                .value(),
+            FunctionType::new(&context, &[r#type], &[r#type]).into()
+        );
+        assert_eq!(
+            function
+                .region(0)
+                .unwrap()
+                .first_block()
+                .unwrap()
+                .argument(0)
+                .unwrap()
+                .r#type(),
+            r#type
+        );
+    }
+
+    #[test]
+    fn roll_back_failed_conversion() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let converter = type_converter(&context);
+        let mut target = ConversionTarget::new();
+        let mut patterns = ConversionPatternSet::new();
+
+        target.add_dynamically_legal_operation("arith.addi", |operation| {
+            !has_index_operand_or_result(operation)
+        });
+        patterns.add(FunctionSignatureConversion::new("func.func"));
+        patterns.add(AddIndex);
+
+        let source = module.as_operation().to_string();
+
+        assert_eq!(
+            super::apply_full_conversion(
+                &module.as_operation(),
+                &target,
+                &patterns,
+                &converter,
+                &ConversionConfig::new(),
+            ),
+            Err(Error::ApplyConversion("func.func".into()))
+        );
+        assert_eq!(module.as_operation().to_string(), source);
+    }
+
+    #[test]
+    fn fail_pattern() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let mut target = ConversionTarget::new();
+        let mut patterns = ConversionPatternSet::new();
+
+        target.add_illegal_dialect("arith");
+        patterns.add(Fail);
+
+        let source = module.as_operation().to_string();
+
+        assert_eq!(
+            super::apply_partial_conversion(
+                &module.as_operation(),
+                &target,
+                &patterns,
+                &type_converter(&context),
+                &ConversionConfig::new(),
+            ),
+            Err(Error::OperationBuild)
+        );
+        assert_eq!(module.as_operation().to_string(), source);
+    }
+
+    #[test]
+    fn fail_to_replace_operation_with_wrong_number_of_values() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let mut target = ConversionTarget::new();
+        let mut patterns = ConversionPatternSet::new();
+
+        target.add_illegal_dialect("arith");
+        patterns.add(ReplaceWithNothing);
+
+        let source = module.as_operation().to_string();
+
+        assert!(matches!(
+            super::apply_partial_conversion(
+                &module.as_operation(),
+                &target,
+                &patterns,
+                &type_converter(&context),
+                &ConversionConfig::new(),
+            ),
+            Err(Error::ResultCount {
+                expected: 1,
+                actual: 0,
+                ..
+            })
+        ));
+        assert_eq!(module.as_operation().to_string(), source);
+    }
+
+    #[test]
+    fn apply_no_iteration() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let mut target = ConversionTarget::new();
+        let mut patterns = ConversionPatternSet::new();
+
+        target.add_illegal_dialect("arith");
+        patterns.add(AddIndex);
+
+        assert_eq!(
+            super::apply_partial_conversion(
+                &module.as_operation(),
+                &target,
+                &patterns,
+                &type_converter(&context),
+                &ConversionConfig::new().max_iterations(0),
+            ),
+            Err(Error::ApplyConversion("arith.addi".into()))
+        );
+    }
+
+    #[test]
+    fn llvm_type_converter() {
+        let context = create_test_context();
+        let converter = TypeConverter::llvm(&context, 32);
+        let parse = |string| Type::parse(&context, string).unwrap();
+
+        assert_eq!(
+            converter.convert_type(Type::index(&context)),
+            Some(parse("i32"))
+        );
+        assert_eq!(converter.convert_type(parse("f64")), Some(parse("f64")));
+        assert_eq!(
+            converter.convert_type(parse("!llvm.ptr")),
+            Some(parse("!llvm.ptr"))
+        );
+        assert_eq!(
+            converter.convert_type(parse("memref<?x4xf32>")),
+            Some(parse(
+                "!llvm.struct<(ptr, ptr, i32, array<2 x i32>, array<2 x i32>)>"
+            ))
+        );
+        assert_eq!(
+            converter.convert_type(parse("memref<f32>")),
+            Some(parse("!llvm.struct<(ptr, ptr, i32)>"))
+        );
+        assert_eq!(
+            converter.convert_type(parse("memref<*xf32>")),
+            Some(parse("!llvm.struct<(i32, ptr)>"))
+        );
+        assert_eq!(converter.convert_type(parse("tensor<4xf32>")), None);
+    }
+
+    #[test]
+    fn fail_to_apply_full_conversion() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let converter = type_converter(&context);
+        let mut target = ConversionTarget::new();
+
+        target.add_legal_dialect("arith");
+
+        assert_eq!(
+            super::apply_full_conversion(
+                &module.as_operation(),
+                &target,
+                &ConversionPatternSet::new(),
+                &converter,
+                &ConversionConfig::new(),
+            ),
+            Err(Error::ApplyConversion("func.func".into()))
+        );
+    }
+
+    #[test]
+    fn fail_to_apply_partial_conversion() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let converter = type_converter(&context);
+        let mut target = ConversionTarget::new();
+
+        target.add_illegal_dialect("arith");
+
+        assert_eq!(
+            super::apply_partial_conversion(
+                &module.as_operation(),
+                &target,
+                &ConversionPatternSet::new(),
+                &converter,
+                &ConversionConfig::new(),
+            ),
+            Err(Error::ApplyConversion("arith.addi".into()))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! External passes
 
-use super::Pass;
+use super::{AnalysisManager, Pass};
 use crate::{
     dialect::DialectHandle,
     ir::{r#type::TypeId, OperationRef},
@@ -24,6 +24,17 @@ impl ExternalPass<'_> {
         unsafe { mlirExternalPassSignalFailure(self.raw) }
     }
 
+    /// Returns an analysis manager added to a pass manager running a pass on an
+    /// operation.
+    ///
+    /// See [`PassManager::add_analysis_manager`](super::PassManager::add_analysis_manager).
+    pub fn analysis_manager<'c>(
+        self,
+        operation: OperationRef<'c, '_>,
+    ) -> Option<AnalysisManager<'c>> {
+        AnalysisManager::running(operation)
+    }
+
     /// Converts an external pass to a raw object.
     pub const fn to_raw(self) -> MlirExternalPass {
         self.raw
@@ -1,18 +1,6 @@
 //! GPU passes.
 
-melior_macro::passes!(
-    "GPU",
-    [
-        // spell-checker: disable-next-line
-        mlirCreateGPUGpuAsyncRegionPass,
-        mlirCreateGPUGpuDecomposeMemrefsPass,
-        mlirCreateGPUGpuEliminateBarriers,
-        mlirCreateGPUGpuKernelOutliningPass,
-        mlirCreateGPUGpuLaunchSinkIndexComputationsPass,
-        mlirCreateGPUGpuMapParallelLoopsPass,
-        mlirCreateGPUGpuModuleToBinaryPass,
-        mlirCreateGPUGpuNVVMAttachTarget,
-        mlirCreateGPUGpuROCDLAttachTarget,
-        mlirCreateGPUGpuSPIRVAttachTarget,
-    ]
-);
+melior_macro::passes_from_tablegen! {
+    prefix: "GPU",
+    files: ["mlir/Dialect/GPU/Transforms/Passes.td"],
+}
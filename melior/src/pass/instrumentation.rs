@@ -0,0 +1,693 @@
+//! Pass instrumentation.
+
+use super::{
+    create_external,
+    pipeline::{pass_argument, split_nested_pipeline, split_pipeline},
+    ExternalPass, OperationPassManager, Pass, PassStatistic,
+};
+use crate::{
+    ir::{
+        operation::{OperationLike, OperationPrintingFlags},
+        r#type::TypeId,
+        OperationRef,
+    },
+    logical_result::LogicalResult,
+    Error,
+};
+use mlir_sys::{mlirOpPassManagerAddOwnedPass, MlirOperation};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt::{self, Debug, Formatter},
+    mem,
+    sync::{Arc, Mutex, MutexGuard, OnceLock},
+};
+
+type SharedInstrumentation<'c> = Arc<Mutex<Box<dyn PassInstrumentation<'c> + 'c>>>;
+
+const BEFORE_PASS_ARGUMENT: &str = "instrumentation-before";
+const AFTER_PASS_ARGUMENT: &str = "instrumentation-after";
+const OPTIONS_PASS_ARGUMENT: &str = "instrumentation-options";
+
+#[repr(align(8))]
+struct PassId;
+
+static BEFORE_PASS_ID: PassId = PassId;
+static AFTER_PASS_ID: PassId = PassId;
+static OPTIONS_PASS_ID: PassId = PassId;
+
+/// A pass instrumentation.
+///
+/// Instrumentations observe passes and analyses in pass managers. Each hook
+/// receives a pass argument, such as `canonicalize`, and an operation a pass
+/// runs on. Hooks before and after passes can abort pipelines by returning
+/// errors.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{
+///     ir::{operation::OperationLike, OperationRef},
+///     pass::PassInstrumentation,
+/// };
+///
+/// struct Logger;
+///
+/// impl<'c> PassInstrumentation<'c> for Logger {
+///     fn run_before_pass(
+///         &mut self,
+///         pass: &str,
+///         operation: OperationRef<'c, '_>,
+///     ) -> Result<(), String> {
+///         println!(
+///             "running {pass} on {}",
+///             operation.name().as_string_ref().as_str().unwrap()
+///         );
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait PassInstrumentation<'c>: Send {
+    /// Runs before a pass.
+    fn run_before_pass(
+        &mut self,
+        _pass: &str,
+        _operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Runs after a pass succeeds.
+    fn run_after_pass(
+        &mut self,
+        _pass: &str,
+        _operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Runs after a pass fails.
+    fn run_after_pass_failed(&mut self, _pass: &str, _operation: OperationRef<'c, '_>) {}
+
+    /// Runs before an analysis is computed.
+    fn run_before_analysis(&mut self, _analysis: &str, _operation: OperationRef<'c, '_>) {}
+
+    /// Runs after an analysis is computed.
+    fn run_after_analysis(&mut self, _analysis: &str, _operation: OperationRef<'c, '_>) {}
+}
+
+/// Instrumentations registered on a pass manager.
+///
+/// As the C API does not expose pass instrumentations, passes are surrounded
+/// by external marker passes that call instrumentations when they are added to
+/// operation pass managers. Marker passes do not change IR and are hidden
+/// from textual pipelines of pass managers. However, the C API cannot mark
+/// external passes as preserving analyses, so MLIR invalidates its own
+/// analyses after marker passes. Analyses of analysis managers in Rust are
+/// invalidated only after instrumented passes.
+///
+/// Instrumentations are called without a lock of the shared state held, so
+/// they can be called concurrently for different instrumentations.
+#[derive(Clone, Default)]
+pub(crate) struct Instrumentation<'c> {
+    state: Arc<Mutex<State<'c>>>,
+}
+
+#[derive(Default)]
+struct State<'c> {
+    instrumentations: Vec<SharedInstrumentation<'c>>,
+    running: Vec<RunningPass>,
+    error: Option<String>,
+    snapshot: bool,
+    failed_pass: Option<FailedPass>,
+    statistics: Vec<PassStatistic>,
+}
+
+#[derive(Debug)]
+struct PassName {
+    argument: String,
+    pipeline: String,
+}
+
+struct RunningPass {
+    name: Arc<PassName>,
+    operation: MlirOperation,
+    snapshot: Option<String>,
+}
+
+/// A pass failed in a run of a pass manager.
+#[derive(Debug)]
+pub(crate) struct FailedPass {
+    /// A pipeline of the pass anchored on a top-level operation.
+    pub pipeline: String,
+    /// An operation the pass ran on, printed before the pass.
+    pub snapshot: Option<String>,
+}
+
+impl<'c> Instrumentation<'c> {
+    pub fn add(&self, instrumentation: impl PassInstrumentation<'c> + 'c) {
+        self.lock()
+            .instrumentations
+            .push(Arc::new(Mutex::new(Box::new(instrumentation))));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        let state = self.lock();
+
+        !state.instrumentations.is_empty() || state.snapshot
+    }
+
+    /// Enables snapshots of operations before passes for local crash
+    /// reproducers.
+    pub fn enable_snapshot(&self) {
+        self.lock().snapshot = true;
+    }
+
+    /// Adds statistics of a pass added to a pass manager.
+    pub fn add_statistics(&self, statistics: &[PassStatistic]) {
+        self.lock().statistics.extend_from_slice(statistics);
+    }
+
+    /// Returns statistics of passes added to a pass manager.
+    pub fn statistics(&self) -> Vec<PassStatistic> {
+        self.lock().statistics.clone()
+    }
+
+    /// Takes a pass failed in the last run.
+    pub fn take_failed_pass(&self) -> Option<FailedPass> {
+        self.lock().failed_pass.take()
+    }
+
+    /// Adds passes surrounded by instrumentation passes.
+    pub fn instrument<T, E>(
+        &self,
+        manager: OperationPassManager,
+        add: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let name = Arc::new(OnceLock::new());
+
+        self.add_marker(manager, Marker::Before, name.clone());
+        let value = add()?;
+        let pipeline = last_pass(&manager.to_string()).to_owned();
+        let _ = name.set(Arc::new(PassName {
+            argument: pass_argument(&pipeline).into(),
+            pipeline,
+        }));
+        self.add_marker(manager, Marker::After, name);
+
+        Ok(value)
+    }
+
+    /// Notifies instrumentations before an analysis is computed.
+    ///
+    /// Instrumentations are not notified of analyses computed in their own
+    /// hooks.
+    pub fn run_before_analysis(&self, analysis: &str, operation: OperationRef<'c, '_>) {
+        for instrumentation in self.instrumentations() {
+            call(&instrumentation, |instrumentation| {
+                instrumentation.run_before_analysis(analysis, operation)
+            });
+        }
+    }
+
+    /// Notifies instrumentations after an analysis is computed.
+    pub fn run_after_analysis(&self, analysis: &str, operation: OperationRef<'c, '_>) {
+        for instrumentation in self.instrumentations() {
+            call(&instrumentation, |instrumentation| {
+                instrumentation.run_after_analysis(analysis, operation)
+            });
+        }
+    }
+
+    /// Resets a state and statistics before a pass manager runs.
+    pub fn reset(&self) {
+        let mut state = self.lock();
+
+        state.running.clear();
+        state.error = None;
+        state.failed_pass = None;
+
+        for statistic in &state.statistics {
+            statistic.reset();
+        }
+    }
+
+    /// Finishes a run of a pass manager and notifies failed passes.
+    pub fn finish(&self, result: LogicalResult) -> Result<(), Error> {
+        let running = mem::take(&mut self.lock().running);
+
+        if result.is_success() {
+            return Ok(());
+        }
+
+        let instrumentations = self.instrumentations();
+
+        for pass in running.iter().rev() {
+            for instrumentation in &instrumentations {
+                call(instrumentation, |instrumentation| {
+                    instrumentation.run_after_pass_failed(&pass.name.argument, unsafe {
+                        OperationRef::from_raw(pass.operation)
+                    })
+                });
+            }
+        }
+
+        let mut state = self.lock();
+
+        state.failed_pass = running.into_iter().next_back().map(|pass| {
+            let operation = unsafe { OperationRef::from_raw(pass.operation) };
+            let name = operation
+                .name()
+                .as_string_ref()
+                .as_str()
+                .unwrap_or_default()
+                .to_owned();
+
+            FailedPass {
+                pipeline: if name == "builtin.module" {
+                    format!("builtin.module({})", pass.name.pipeline)
+                } else {
+                    format!("builtin.module({name}({}))", pass.name.pipeline)
+                },
+                snapshot: pass.snapshot,
+            }
+        });
+
+        Err(state
+            .error
+            .take()
+            .map(Error::PassInstrumentation)
+            .unwrap_or(Error::RunPass))
+    }
+
+    fn add_marker(
+        &self,
+        manager: OperationPassManager,
+        marker: Marker,
+        name: Arc<OnceLock<Arc<PassName>>>,
+    ) {
+        let instrumentation = self.clone();
+        let pass = create_external(
+            move |operation: OperationRef<'c, '_>, pass: ExternalPass<'_>| {
+                let Some(name) = name.get() else {
+                    return;
+                };
+
+                if let Err(error) = instrumentation.run(marker, name, operation) {
+                    instrumentation.lock().error.get_or_insert(error);
+                    pass.signal_failure();
+                }
+            },
+            TypeId::create(match marker {
+                Marker::Before => &BEFORE_PASS_ID,
+                Marker::After => &AFTER_PASS_ID,
+            }),
+            "instrumentation",
+            match marker {
+                Marker::Before => BEFORE_PASS_ARGUMENT,
+                Marker::After => AFTER_PASS_ARGUMENT,
+            },
+            "Calls pass instrumentations.",
+            "",
+            &[],
+        );
+
+        add_pass(manager, pass);
+    }
+
+    fn run(
+        &self,
+        marker: Marker,
+        name: &Arc<PassName>,
+        operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        let pass = &name.argument;
+
+        match marker {
+            Marker::Before => {
+                for instrumentation in self.instrumentations() {
+                    call(&instrumentation, |instrumentation| {
+                        instrumentation.run_before_pass(pass, operation)
+                    })
+                    .unwrap_or(Ok(()))?;
+                }
+
+                let snapshot = if self.lock().snapshot {
+                    operation
+                        .to_string_with_flags(
+                            OperationPrintingFlags::new().enable_debug_info(true, false),
+                        )
+                        .ok()
+                } else {
+                    None
+                };
+
+                self.lock().running.push(RunningPass {
+                    name: name.clone(),
+                    operation: operation.to_raw(),
+                    snapshot,
+                });
+            }
+            Marker::After => {
+                {
+                    let mut state = self.lock();
+
+                    if let Some(index) = state.running.iter().rposition(|running| {
+                        Arc::ptr_eq(&running.name, name)
+                            && running.operation.ptr == operation.to_raw().ptr
+                    }) {
+                        state.running.remove(index);
+                    }
+                }
+
+                for instrumentation in self.instrumentations() {
+                    call(&instrumentation, |instrumentation| {
+                        instrumentation.run_after_pass(pass, operation)
+                    })
+                    .unwrap_or(Ok(()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn instrumentations(&self) -> Vec<SharedInstrumentation<'c>> {
+        self.lock().instrumentations.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<'c>> {
+        self.state
+            .lock()
+            .expect("instrumentation state is not poisoned")
+    }
+}
+
+// Calls an instrumentation unless it is already being called on the current
+// thread, such as when it computes an analysis in its own hook.
+fn call<'c, T>(
+    instrumentation: &SharedInstrumentation<'c>,
+    hook: impl FnOnce(&mut (dyn PassInstrumentation<'c> + 'c)) -> T,
+) -> Option<T> {
+    thread_local! {
+        static CALLING: RefCell<HashSet<usize>> = Default::default();
+    }
+
+    let key = Arc::as_ptr(instrumentation) as usize;
+
+    if !CALLING.with(|calling| calling.borrow_mut().insert(key)) {
+        return None;
+    }
+
+    let value = hook(
+        &mut **instrumentation
+            .lock()
+            .expect("instrumentation is not poisoned"),
+    );
+
+    CALLING.with(|calling| calling.borrow_mut().remove(&key));
+
+    Some(value)
+}
+
+impl Debug for Instrumentation<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Instrumentation")
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Marker {
+    Before,
+    After,
+}
+
+fn add_pass(manager: OperationPassManager, pass: Pass) {
+    unsafe { mlirOpPassManagerAddOwnedPass(manager.to_raw(), pass.to_raw()) }
+}
+
+/// Adds a marker pass of options of a pass added last.
+///
+/// The C API cannot attach options to external passes, so a marker pass keeps
+/// them in its argument, such as `instrumentation-options{threshold=4}`, and
+/// they are moved into the preceding pass in textual pipelines of pass
+/// managers.
+pub(crate) fn add_options_marker(manager: OperationPassManager, options: &str) {
+    add_pass(
+        manager,
+        create_external(
+            |_: OperationRef, _: ExternalPass| {},
+            TypeId::create(&OPTIONS_PASS_ID),
+            "instrumentation",
+            &format!("{OPTIONS_PASS_ARGUMENT}{{{options}}}"),
+            "Keeps options of a pass.",
+            "",
+            &[],
+        ),
+    );
+}
+
+/// Removes instrumentation passes from a textual pass pipeline and moves
+/// options in option marker passes into their preceding passes.
+pub(crate) fn strip_instrumentation(pipeline: &str) -> String {
+    let Some((name, inner)) = split_nested_pipeline(pipeline) else {
+        return pipeline.into();
+    };
+    let mut elements = Vec::<String>::new();
+
+    for element in split_pipeline(inner) {
+        match pass_argument(element) {
+            BEFORE_PASS_ARGUMENT | AFTER_PASS_ARGUMENT => {}
+            OPTIONS_PASS_ARGUMENT => {
+                if let Some(last) = elements.last_mut() {
+                    last.push_str(element[OPTIONS_PASS_ARGUMENT.len()..].trim());
+                }
+            }
+            _ => elements.push(strip_instrumentation(element)),
+        }
+    }
+
+    format!("{name}({})", elements.join(","))
+}
+
+fn last_pass(pipeline: &str) -> &str {
+    let pipeline = split_nested_pipeline(pipeline).map_or(pipeline, |(_, inner)| inner);
+
+    split_pipeline(pipeline).pop().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{operation::OperationLike, Module},
+        pass::{self, Analysis, AnalysisManager, PassManager},
+        test::create_test_context,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    struct Recorder {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl<'c> PassInstrumentation<'c> for Recorder {
+        fn run_before_pass(
+            &mut self,
+            pass: &str,
+            operation: OperationRef<'c, '_>,
+        ) -> Result<(), String> {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("before {pass} on {}", name(operation)));
+            Ok(())
+        }
+
+        fn run_after_pass(
+            &mut self,
+            pass: &str,
+            operation: OperationRef<'c, '_>,
+        ) -> Result<(), String> {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("after {pass} on {}", name(operation)));
+            Ok(())
+        }
+    }
+
+    fn name(operation: OperationRef) -> String {
+        operation.name().as_string_ref().as_str().unwrap().into()
+    }
+
+    fn parse_module(context: &crate::Context) -> Module<'_> {
+        Module::parse(
+            context,
+            indoc!(
+                "
+                func.func @foo(%arg0 : i32) -> i32 {
+                    %res = arith.addi %arg0, %arg0 : i32
+                    return %res : i32
+                }
+                "
+            ),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn instrument_passes() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let events = Arc::new(Mutex::new(vec![]));
+
+        manager.add_instrumentation(Recorder {
+            events: events.clone(),
+        });
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "before symbol-dce on builtin.module",
+                "after symbol-dce on builtin.module",
+                "before cse on func.func",
+                "after cse on func.func",
+            ]
+        );
+    }
+
+    #[test]
+    fn instrument_pipeline() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let events = Arc::new(Mutex::new(vec![]));
+
+        pass::transform::register_cse();
+        pass::transform::register_canonicalizer();
+
+        manager.add_instrumentation(Recorder {
+            events: events.clone(),
+        });
+        manager
+            .add_pipeline("func.func(cse,canonicalize{max-iterations=3})")
+            .unwrap();
+
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "before cse on func.func",
+                "after cse on func.func",
+                "before canonicalize on func.func",
+                "after canonicalize on func.func",
+            ]
+        );
+    }
+
+    #[test]
+    fn hide_instrumentation_passes() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        manager.add_instrumentation(Recorder {
+            events: Default::default(),
+        });
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "any(symbol-dce,func.func(cse))"
+        );
+    }
+
+    #[test]
+    fn compute_analysis_in_instrumentation() {
+        struct RegionCount;
+
+        impl Analysis for RegionCount {
+            fn analyze(_operation: OperationRef) -> Self {
+                Self
+            }
+        }
+
+        struct Analyzer<'c>(AnalysisManager<'c>);
+
+        impl<'c> PassInstrumentation<'c> for Analyzer<'c> {
+            fn run_after_pass(
+                &mut self,
+                _pass: &str,
+                operation: OperationRef<'c, '_>,
+            ) -> Result<(), String> {
+                self.0.get_analysis::<RegionCount>(operation);
+                Ok(())
+            }
+        }
+
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let analyses = AnalysisManager::new();
+        let events = Arc::new(Mutex::new(vec![]));
+
+        manager.add_analysis_manager(&analyses).unwrap();
+        manager.add_instrumentation(Analyzer(analyses.clone()));
+        manager.add_instrumentation(Recorder {
+            events: events.clone(),
+        });
+        manager.add_pass(pass::transform::create_symbol_dce());
+
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "before symbol-dce on builtin.module",
+                "after symbol-dce on builtin.module",
+            ]
+        );
+    }
+
+    #[test]
+    fn abort_pipeline() {
+        struct Invariant;
+
+        impl<'c> PassInstrumentation<'c> for Invariant {
+            fn run_after_pass(
+                &mut self,
+                pass: &str,
+                _operation: OperationRef<'c, '_>,
+            ) -> Result<(), String> {
+                Err(format!("invariant broken by {pass}"))
+            }
+        }
+
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+
+        manager.add_instrumentation(Invariant);
+        manager.add_pass(pass::transform::create_symbol_dce());
+
+        assert_eq!(
+            manager.run(&mut module),
+            Err(Error::PassInstrumentation(
+                "invariant broken by symbol-dce".into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,523 @@
+use super::PassInstrumentation;
+use crate::ir::{
+    block::BlockLike,
+    operation::{OperationLike, OperationPrintingFlags},
+    OperationRef,
+};
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, File},
+    io::Write,
+    path::PathBuf,
+};
+
+/// An IR printer of passes.
+///
+/// An IR printer dumps operations before and after passes into a writer or a
+/// directory tree with one file per pass execution.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{
+///     ir::operation::OperationPrintingFlags,
+///     pass::{PassIrPrinter, PassManager},
+///     Context,
+/// };
+///
+/// let context = Context::new();
+/// let manager = PassManager::new(&context);
+///
+/// manager.enable_ir_printer(
+///     PassIrPrinter::new(std::io::sink())
+///         .after(true)
+///         .only_on_change(true)
+///         .filter(|pass| pass == "canonicalize")
+///         .flags(OperationPrintingFlags::new().elide_large_elements_attributes(16)),
+/// );
+/// ```
+pub struct PassIrPrinter {
+    sink: Sink,
+    before: bool,
+    after: bool,
+    only_on_change: bool,
+    only_after_failure: bool,
+    module_scope: bool,
+    filter: Option<Box<dyn Fn(&str) -> bool + Send>>,
+    flags: OperationPrintingFlags,
+    snapshots: HashMap<(String, usize), String>,
+    count: usize,
+}
+
+enum Sink {
+    Writer(Box<dyn Write + Send>),
+    Directory(PathBuf),
+}
+
+impl PassIrPrinter {
+    /// Creates an IR printer into a writer.
+    ///
+    /// By default, operations are printed after every pass.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self::with_sink(Sink::Writer(Box::new(writer)))
+    }
+
+    /// Creates an IR printer into a directory.
+    ///
+    /// Each dump is written into a file named like `0_canonicalize.after.mlir`
+    /// in a directory nested by names of operations a pass runs on.
+    pub fn directory(path: impl Into<PathBuf>) -> Self {
+        Self::with_sink(Sink::Directory(path.into()))
+    }
+
+    fn with_sink(sink: Sink) -> Self {
+        Self {
+            sink,
+            before: false,
+            after: true,
+            only_on_change: false,
+            only_after_failure: false,
+            module_scope: false,
+            filter: None,
+            flags: OperationPrintingFlags::new(),
+            snapshots: Default::default(),
+            count: 0,
+        }
+    }
+
+    /// Sets if operations are printed before passes.
+    pub fn before(mut self, enabled: bool) -> Self {
+        self.before = enabled;
+        self
+    }
+
+    /// Sets if operations are printed after passes.
+    pub fn after(mut self, enabled: bool) -> Self {
+        self.after = enabled;
+        self
+    }
+
+    /// Sets if operations are printed after passes only when they are changed.
+    pub fn only_on_change(mut self, enabled: bool) -> Self {
+        self.only_on_change = enabled;
+        self
+    }
+
+    /// Sets if operations are printed after passes only when they fail.
+    pub fn only_after_failure(mut self, enabled: bool) -> Self {
+        self.only_after_failure = enabled;
+        self
+    }
+
+    /// Sets if top-level operations are printed instead of operations passes
+    /// run on.
+    ///
+    /// Passes on other operations might modify top-level operations while
+    /// they are printed. Therefore, printing fails unless multi-threading is
+    /// disabled in a context.
+    pub fn module_scope(mut self, enabled: bool) -> Self {
+        self.module_scope = enabled;
+        self
+    }
+
+    /// Sets a filter of pass arguments.
+    pub fn filter(mut self, filter: impl Fn(&str) -> bool + Send + 'static) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Sets operation printing flags.
+    pub fn flags(mut self, flags: OperationPrintingFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    fn is_enabled(&self, pass: &str) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter(pass))
+    }
+
+    fn print(&self, operation: OperationRef) -> Result<String, String> {
+        let operation = if !self.module_scope {
+            operation
+        } else if unsafe { operation.context().to_ref() }.is_multi_threading_enabled() {
+            return Err("module scope requires multi-threading to be disabled".into());
+        } else {
+            top_level_operation(operation)
+        };
+
+        operation
+            .to_string_with_flags(&self.flags)
+            .map_err(|error| error.to_string())
+    }
+
+    fn dump(&mut self, stage: &str, pass: &str, operation: OperationRef) -> Result<(), String> {
+        let ir = self.print(operation)?;
+
+        self.write(stage, pass, operation, &ir)
+    }
+
+    fn write(
+        &mut self,
+        stage: &str,
+        pass: &str,
+        operation: OperationRef,
+        ir: &str,
+    ) -> Result<(), String> {
+        let name = operation_name(operation);
+
+        match &mut self.sink {
+            Sink::Writer(writer) => writeln!(
+                writer,
+                "// -----// IR Dump {} ({name}) //----- //\n{ir}",
+                match stage {
+                    "before" => format!("Before {pass}"),
+                    "after" => format!("After {pass}"),
+                    _ => format!("After {pass} Failed"),
+                },
+            ),
+            Sink::Directory(path) => {
+                let mut path = path.clone();
+
+                for operation in operation_path(operation) {
+                    path.push(operation);
+                }
+
+                create_dir_all(&path)
+                    .and_then(|_| {
+                        File::create(path.join(format!("{}_{pass}.{stage}.mlir", self.count)))
+                    })
+                    .and_then(|mut file| writeln!(file, "{ir}"))
+            }
+        }
+        .map_err(|error| error.to_string())?;
+
+        self.count += 1;
+
+        Ok(())
+    }
+}
+
+impl<'c> PassInstrumentation<'c> for PassIrPrinter {
+    fn run_before_pass(
+        &mut self,
+        pass: &str,
+        operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        if !self.is_enabled(pass) {
+            return Ok(());
+        }
+
+        if self.only_on_change {
+            let ir = self.print(operation)?;
+
+            self.snapshots
+                .insert((pass.into(), operation.to_raw().ptr as usize), ir);
+        }
+
+        if self.before {
+            self.dump("before", pass, operation)?;
+        }
+
+        Ok(())
+    }
+
+    fn run_after_pass(
+        &mut self,
+        pass: &str,
+        operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        let snapshot = self
+            .snapshots
+            .remove(&(pass.to_owned(), operation.to_raw().ptr as usize));
+
+        if !self.after || self.only_after_failure || !self.is_enabled(pass) {
+            return Ok(());
+        }
+
+        let ir = self.print(operation)?;
+
+        if self.only_on_change && snapshot.as_ref() == Some(&ir) {
+            return Ok(());
+        }
+
+        self.write("after", pass, operation, &ir)
+    }
+
+    fn run_after_pass_failed(&mut self, pass: &str, operation: OperationRef<'c, '_>) {
+        self.snapshots
+            .remove(&(pass.to_owned(), operation.to_raw().ptr as usize));
+
+        if (self.after || self.only_after_failure) && self.is_enabled(pass) {
+            // A pipeline has already failed.
+            let _ = self.dump("failed", pass, operation);
+        }
+    }
+}
+
+fn operation_name(operation: OperationRef) -> String {
+    operation
+        .name()
+        .as_string_ref()
+        .as_str()
+        .unwrap_or_default()
+        .into()
+}
+
+fn top_level_operation<'c, 'a>(operation: OperationRef<'c, 'a>) -> OperationRef<'c, 'a> {
+    let mut operation = operation;
+
+    while let Some(parent) = operation.block().and_then(|block| block.parent_operation()) {
+        operation = parent;
+    }
+
+    operation
+}
+
+fn operation_path(operation: OperationRef) -> Vec<String> {
+    let mut path = vec![];
+    let mut current = Some(operation);
+
+    while let Some(operation) = current {
+        let symbol = operation
+            .attribute("sym_name")
+            .ok()
+            .map(|attribute| attribute.to_string());
+
+        path.push(match symbol {
+            Some(symbol) => format!("{}_{}", operation_name(operation), symbol.trim_matches('"')),
+            None => operation_name(operation),
+        });
+
+        current = operation.block().and_then(|block| block.parent_operation());
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{r#type::TypeId, Module},
+        pass::{self, create_external, ExternalPass, PassManager},
+        test::{create_temp_path, create_test_context},
+        Error,
+    };
+    use indoc::indoc;
+    use std::{
+        fs::{read_dir, remove_dir_all},
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    #[repr(align(8))]
+    struct PassId;
+
+    static FAILING_PASS: PassId = PassId;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for Buffer {
+        fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buffer)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn parse_module(context: &crate::Context) -> Module<'_> {
+        Module::parse(
+            context,
+            indoc!(
+                "
+                func.func @foo(%arg0 : i32) -> i32 {
+                    %0 = arith.addi %arg0, %arg0 : i32
+                    %1 = arith.addi %arg0, %arg0 : i32
+                    return %1 : i32
+                }
+                "
+            ),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn print_after_passes() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let buffer = Buffer::default();
+
+        manager.enable_ir_printer(PassIrPrinter::new(buffer.clone()).before(true));
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+        manager.run(&mut module).unwrap();
+
+        let contents = buffer.contents();
+
+        assert!(contents.contains("// -----// IR Dump Before cse (func.func) //----- //"));
+        assert!(contents.contains("// -----// IR Dump After cse (func.func) //----- //"));
+        assert!(contents.contains("func.func @foo"));
+    }
+
+    #[test]
+    fn print_only_on_change() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let buffer = Buffer::default();
+
+        manager.enable_ir_printer(PassIrPrinter::new(buffer.clone()).only_on_change(true));
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+        manager.run(&mut module).unwrap();
+
+        let contents = buffer.contents();
+
+        assert!(!contents.contains("symbol-dce"));
+        assert!(contents.contains("IR Dump After cse"));
+    }
+
+    #[test]
+    fn filter_passes() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let buffer = Buffer::default();
+
+        manager.enable_ir_printer(
+            PassIrPrinter::new(buffer.clone()).filter(|pass| pass == "symbol-dce"),
+        );
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+        manager.run(&mut module).unwrap();
+
+        let contents = buffer.contents();
+
+        assert!(contents.contains("IR Dump After symbol-dce (builtin.module)"));
+        assert!(!contents.contains("cse"));
+    }
+
+    #[test]
+    fn print_only_after_failure() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let buffer = Buffer::default();
+
+        manager.enable_ir_printer(PassIrPrinter::new(buffer.clone()).only_after_failure(true));
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(buffer.contents(), "");
+    }
+
+    #[test]
+    fn print_after_failed_pass() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let buffer = Buffer::default();
+
+        manager.enable_ir_printer(PassIrPrinter::new(buffer.clone()).only_after_failure(true));
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager.nested_under("func.func").add_pass(create_external(
+            |_: OperationRef, pass: ExternalPass| pass.signal_failure(),
+            TypeId::create(&FAILING_PASS),
+            "fail",
+            "fail",
+            "Fails always.",
+            "",
+            &[],
+        ));
+
+        assert_eq!(manager.run(&mut module), Err(Error::RunPass));
+
+        let contents = buffer.contents();
+
+        assert!(!contents.contains("symbol-dce"));
+        assert!(contents.contains("// -----// IR Dump After fail Failed (func.func) //----- //"));
+        assert!(contents.contains("func.func @foo"));
+    }
+
+    #[test]
+    fn print_module_scope() {
+        let context = create_test_context();
+        context.enable_multi_threading(false);
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let buffer = Buffer::default();
+
+        manager.enable_ir_printer(PassIrPrinter::new(buffer.clone()).module_scope(true));
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+        manager.run(&mut module).unwrap();
+
+        let contents = buffer.contents();
+
+        assert!(contents.contains("// -----// IR Dump After cse (func.func) //----- //"));
+        assert!(contents.contains("module {"));
+    }
+
+    #[test]
+    fn fail_to_print_module_scope_with_multi_threading() {
+        let context = create_test_context();
+        context.enable_multi_threading(true);
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+
+        manager.enable_ir_printer(PassIrPrinter::new(io::sink()).module_scope(true));
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+
+        // Contexts have single threads on machines with single cores.
+        if context.is_multi_threading_enabled() {
+            assert_eq!(
+                manager.run(&mut module),
+                Err(Error::PassInstrumentation(
+                    "module scope requires multi-threading to be disabled".into()
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn print_into_directory() {
+        let context = create_test_context();
+        let mut module = parse_module(&context);
+        let manager = PassManager::new(&context);
+        let directory = create_temp_path("print_into_directory");
+
+        manager.enable_ir_printer(PassIrPrinter::directory(&directory));
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+        manager.run(&mut module).unwrap();
+
+        let files = read_dir(directory.join("builtin.module").join("func.func_foo"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(files, ["0_cse.after.mlir"]);
+
+        remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,20 +1,6 @@
 //! Linalg passes.
 
-melior_macro::passes!(
-    "Linalg",
-    [
-        // spell-checker: disable-next-line
-        mlirCreateLinalgConvertElementwiseToLinalgPass,
-        mlirCreateLinalgConvertLinalgToAffineLoopsPass,
-        mlirCreateLinalgConvertLinalgToLoopsPass,
-        mlirCreateLinalgConvertLinalgToParallelLoopsPass,
-        mlirCreateLinalgLinalgBlockPackMatmul,
-        mlirCreateLinalgLinalgDetensorizePass,
-        mlirCreateLinalgLinalgElementwiseOpFusionPass,
-        mlirCreateLinalgLinalgFoldUnitExtentDimsPass,
-        mlirCreateLinalgLinalgGeneralizeNamedOpsPass,
-        mlirCreateLinalgLinalgInlineScalarOperandsPass,
-        mlirCreateLinalgLinalgNamedOpConversionPass,
-        mlirCreateLinalgLinalgSpecializeGenericOpsPass,
-    ]
-);
+melior_macro::passes_from_tablegen! {
+    prefix: "Linalg",
+    files: ["mlir/Dialect/Linalg/Passes.td"],
+}
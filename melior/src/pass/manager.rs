@@ -1,48 +1,186 @@
-use super::OperationPassManager;
+use super::{
+    instrumentation::Instrumentation, split_nested_pipeline, split_pipeline, timing::Timer,
+    AnalysisManager, OperationPassManager, PassInstrumentation, PassIrPrinter, PassOptions,
+    PassStatistic, PassTiming,
+};
 use crate::{
     context::Context,
-    ir::{operation::OperationPrintingFlags, Module},
+    ir::{
+        operation::{OperationLike, OperationMutLike, OperationPrintingFlags},
+        Module, OperationRef,
+    },
     logical_result::LogicalResult,
     pass::Pass,
+    reproducer,
     string_ref::StringRef,
     Error,
 };
 use mlir_sys::{
-    mlirPassManagerAddOwnedPass, mlirPassManagerCreate, mlirPassManagerDestroy,
+    mlirEmitError, mlirPassManagerCreateOnOperation, mlirPassManagerDestroy,
     mlirPassManagerEnableIRPrinting, mlirPassManagerEnableVerifier,
     mlirPassManagerGetAsOpPassManager, mlirPassManagerGetNestedUnder, mlirPassManagerRunOnOp,
     MlirPassManager,
 };
-use std::{marker::PhantomData, mem::forget, path::PathBuf};
+use std::{
+    cell::{OnceCell, RefCell},
+    ffi::CString,
+    marker::PhantomData,
+    mem::forget,
+    path::{Path, PathBuf},
+};
+
+const ANY_ANCHOR: &str = "any";
+const MODULE_ANCHOR: &str = "builtin.module";
 
 /// A pass manager.
 pub struct PassManager<'c> {
     raw: MlirPassManager,
+    instrumentation: Instrumentation<'c>,
+    analysis_manager: OnceCell<AnalysisManager<'c>>,
+    timer: OnceCell<Timer>,
+    reproducer: RefCell<Option<Reproducer>>,
     _context: PhantomData<&'c Context>,
 }
 
 impl<'c> PassManager<'c> {
     /// Creates a pass manager.
+    ///
+    /// A pass manager is not anchored on any specific operation.
     pub fn new(context: &Context) -> Self {
+        Self::new_on(context, ANY_ANCHOR)
+    }
+
+    /// Creates a pass manager anchored on operations of a given name, such as
+    /// `func.func`.
+    pub fn new_on(context: &Context, name: &str) -> Self {
         Self {
-            raw: unsafe { mlirPassManagerCreate(context.to_raw()) },
+            raw: unsafe {
+                mlirPassManagerCreateOnOperation(context.to_raw(), StringRef::new(name).to_raw())
+            },
+            instrumentation: Default::default(),
+            analysis_manager: Default::default(),
+            timer: Default::default(),
+            reproducer: Default::default(),
             _context: Default::default(),
         }
     }
 
     /// Returns an operation pass manager for nested operations corresponding to
     /// a given name.
-    pub fn nested_under(&self, name: &str) -> OperationPassManager<'_, '_> {
+    pub fn nested_under(&self, name: &str) -> OperationPassManager<'c, '_> {
         let name = StringRef::new(name);
 
         unsafe {
             OperationPassManager::from_raw(mlirPassManagerGetNestedUnder(self.raw, name.to_raw()))
         }
+        .with_instrumentation(&self.instrumentation)
     }
 
     /// Adds a pass.
     pub fn add_pass(&self, pass: Pass) {
-        unsafe { mlirPassManagerAddOwnedPass(self.raw, pass.to_raw()) }
+        self.as_operation_pass_manager().add_pass(pass)
+    }
+
+    /// Adds passes in a textual pass pipeline.
+    ///
+    /// See [`OperationPassManager::add_pipeline`].
+    pub fn add_pipeline(&self, pipeline: &str) -> Result<(), Error> {
+        self.as_operation_pass_manager().add_pipeline(pipeline)
+    }
+
+    /// Adds a pass with a pass argument and an options string.
+    ///
+    /// See [`OperationPassManager::add_pass_with_options`].
+    pub fn add_pass_with_options(&self, argument: &str, options: &str) -> Result<(), Error> {
+        self.as_operation_pass_manager()
+            .add_pass_with_options(argument, options)
+    }
+
+    /// Adds a pass with typed options.
+    pub fn add_pass_with_typed_options<T: PassOptions>(&self, options: &T) -> Result<(), Error> {
+        self.as_operation_pass_manager()
+            .add_pass_with_typed_options(options)
+    }
+
+    /// Adds a pass instrumentation.
+    ///
+    /// Instrumentations observe passes added after they are registered.
+    pub fn add_instrumentation(&self, instrumentation: impl PassInstrumentation<'c> + 'c) {
+        self.instrumentation.add(instrumentation)
+    }
+
+    /// Adds an analysis manager.
+    ///
+    /// The analysis manager must be added before any passes. Passes get it with
+    /// [`ExternalPass::analysis_manager`](super::ExternalPass::analysis_manager)
+    /// while the pass manager runs and analyses not preserved by them are
+    /// invalidated after they run. Instrumentations are notified when analyses
+    /// are computed. An analysis manager can be shared by pass managers.
+    ///
+    /// Passes added later with textual pass pipelines, such as
+    /// [`add_pipeline`](Self::add_pipeline) and
+    /// [`parse_pass_pipeline`](crate::utility::parse_pass_pipeline), also
+    /// invalidate analyses as they are instrumented.
+    pub fn add_analysis_manager(&self, manager: &AnalysisManager<'c>) -> Result<(), Error> {
+        if self.analysis_manager.get().is_some() {
+            return Err(Error::AddAnalysisManager("analysis manager already added"));
+        } else if split_nested_pipeline(&self.as_operation_pass_manager().to_string())
+            .is_some_and(|(_, pipeline)| has_passes(pipeline))
+        {
+            return Err(Error::AddAnalysisManager("passes already added"));
+        }
+
+        self.add_instrumentation(manager.invalidator());
+        let _ = self.analysis_manager.set(manager.clone());
+
+        Ok(())
+    }
+
+    /// Enables pass timing.
+    ///
+    /// Only passes added after this call are timed.
+    pub fn enable_timing(&self) {
+        self.timer.get_or_init(|| {
+            let timer = Timer::default();
+            self.add_instrumentation(timer.clone());
+            timer
+        });
+    }
+
+    /// Returns a pass timing report of the last run if timing is enabled.
+    pub fn timing_report(&self) -> Option<PassTiming> {
+        self.timer.get().map(Timer::report)
+    }
+
+    /// Returns statistics of passes added to a pass manager.
+    ///
+    /// Statistics are attached to passes with [`Pass::with_statistics`] and
+    /// reset before every run. Statistics of MLIR's built-in passes are not
+    /// available as the C API does not expose them.
+    pub fn statistics(&self) -> Vec<PassStatistic> {
+        self.instrumentation.statistics()
+    }
+
+    /// Enables generation of crash reproducers.
+    ///
+    /// When a run fails, a module and a pass pipeline are written into a file
+    /// at a given path, which [`reproducer::run`] can rerun. If `local_scope`
+    /// is `true`, a reproducer contains only a failed pass and an operation it
+    /// ran on, which requires the pass to be added after this call.
+    ///
+    /// Only failures of pass pipelines are captured. Crashes, such as
+    /// assertion failures in MLIR, abort a process before a reproducer is
+    /// written. If a reproducer cannot be written, an error diagnostic is
+    /// emitted on the operation and a run still returns its original error.
+    pub fn enable_crash_reproducer(&self, path: impl AsRef<Path>, local_scope: bool) {
+        if local_scope {
+            self.instrumentation.enable_snapshot();
+        }
+
+        *self.reproducer.borrow_mut() = Some(Reproducer {
+            path: path.as_ref().into(),
+            local_scope,
+        });
     }
 
     /// Enables a verifier.
@@ -69,22 +207,123 @@ impl<'c> PassManager<'c> {
         }
     }
 
+    /// Enables IR printing into a Rust sink.
+    ///
+    /// Only passes added after this call are printed.
+    pub fn enable_ir_printer(&self, printer: PassIrPrinter) {
+        self.add_instrumentation(printer)
+    }
+
+    /// Returns a name of operations a pass manager is anchored on.
+    ///
+    /// It is `any` for pass managers not anchored on any specific operation.
+    pub fn anchor(&self) -> String {
+        split_nested_pipeline(&self.as_operation_pass_manager().to_string())
+            .map_or_else(|| ANY_ANCHOR.into(), |(name, _)| name.into())
+    }
+
     /// Runs passes added to a pass manager against a module.
     pub fn run(&self, module: &mut Module) -> Result<(), Error> {
+        self.run_on(&mut module.as_operation_mut())
+    }
+
+    /// Runs passes added to a pass manager against an operation, such as a
+    /// function.
+    ///
+    /// The operation must match an anchor of the pass manager.
+    pub fn run_on<'a>(&self, operation: &mut impl OperationMutLike<'c, 'a>) -> Result<(), Error>
+    where
+        'c: 'a,
+    {
+        let name = operation.name();
+        let name = name.as_string_ref().as_str()?;
+        let anchor = self.anchor();
+
+        if anchor != ANY_ANCHOR && anchor != name {
+            return Err(Error::PassAnchorMismatch {
+                expected: anchor,
+                actual: name.into(),
+            });
+        }
+
+        self.run_operation(unsafe { OperationRef::from_raw(operation.to_raw()) })
+    }
+
+    fn run_operation(&self, operation: OperationRef<'c, '_>) -> Result<(), Error> {
+        self.instrumentation.reset();
+
+        let _run = self
+            .analysis_manager
+            .get()
+            .map(|manager| manager.start_run(operation, &self.instrumentation));
+
+        let snapshot = if self.reproducer.borrow().is_some() {
+            Some(operation.to_string_with_flags(
+                OperationPrintingFlags::new().enable_debug_info(true, false),
+            )?)
+        } else {
+            None
+        };
+
+        if let Some(timer) = self.timer.get() {
+            timer.start();
+        }
+
         let result = LogicalResult::from_raw(unsafe {
-            mlirPassManagerRunOnOp(self.raw, module.as_operation().to_raw())
+            mlirPassManagerRunOnOp(self.raw, operation.to_raw())
         });
 
-        if result.is_success() {
-            Ok(())
-        } else {
-            Err(Error::RunPass)
+        if let Some(timer) = self.timer.get() {
+            timer.stop();
         }
+
+        let result = self.instrumentation.finish(result);
+        let failed_pass = self.instrumentation.take_failed_pass();
+
+        if let (Err(_), Some(reproducer), Some(snapshot)) =
+            (&result, &*self.reproducer.borrow(), snapshot)
+        {
+            let written = match failed_pass {
+                Some(pass) if reproducer.local_scope && pass.snapshot.is_some() => {
+                    reproducer::write(
+                        &reproducer.path,
+                        pass.snapshot.as_deref().unwrap_or_default(),
+                        &pass.pipeline,
+                    )
+                }
+                _ => {
+                    let pipeline = self.as_operation_pass_manager().to_string();
+
+                    reproducer::write(
+                        &reproducer.path,
+                        &snapshot,
+                        &if operation.name().as_string_ref().as_str() == Ok(MODULE_ANCHOR) {
+                            pipeline
+                        } else {
+                            // Parsed operations are wrapped by modules.
+                            format!("{MODULE_ANCHOR}({pipeline})")
+                        },
+                    )
+                }
+            };
+
+            // An error of a pipeline takes precedence and a failure of writing
+            // a reproducer is reported as a diagnostic.
+            if let Err(error) = written {
+                let message = CString::new(format!("failed to write crash reproducer: {error}"))
+                    .unwrap_or_default();
+
+                unsafe { mlirEmitError(operation.location().to_raw(), message.as_ptr()) }
+            }
+        }
+
+        result
     }
 
     /// Converts a pass manager to an operation pass manager.
-    pub fn as_operation_pass_manager(&self) -> OperationPassManager<'_, '_> {
+    pub fn as_operation_pass_manager(&self) -> OperationPassManager<'c, '_> {
         unsafe { OperationPassManager::from_raw(mlirPassManagerGetAsOpPassManager(self.raw)) }
+            .with_instrumentation(&self.instrumentation)
     }
 
     /// Creates a PassManager from the given raw pointer.
@@ -94,6 +333,10 @@ impl<'c> PassManager<'c> {
     pub unsafe fn from_raw(raw: MlirPassManager) -> Self {
         Self {
             raw,
+            instrumentation: Default::default(),
+            analysis_manager: Default::default(),
+            timer: Default::default(),
+            reproducer: Default::default(),
             _context: Default::default(),
         }
     }
@@ -117,6 +360,18 @@ impl Drop for PassManager<'_> {
     }
 }
 
+fn has_passes(pipeline: &str) -> bool {
+    split_pipeline(pipeline).into_iter().any(|element| {
+        split_nested_pipeline(element).is_none_or(|(_, pipeline)| has_passes(pipeline))
+    })
+}
+
+#[derive(Debug)]
+struct Reproducer {
+    path: PathBuf,
+    local_scope: bool,
+}
+
 #[derive(Debug)]
 pub struct PassIrPrintingOptions {
     pub before_all: bool,
@@ -132,13 +387,14 @@ pub struct PassIrPrintingOptions {
 mod tests {
     use super::*;
     use crate::{
-        ir::{Location, Module},
-        pass::{self, transform::register_print_op_stats},
+        ir::{block::BlockLike, r#type::TypeId, Location, Module, OperationRef},
+        pass::{self, transform::register_print_op_stats, ExternalPass},
         test::create_test_context,
-        utility::parse_pass_pipeline,
+        utility::{parse_pass_pipeline, register_all_passes},
     };
     use indoc::indoc;
     use pretty_assertions::assert_eq;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn new() {
@@ -290,4 +546,265 @@ mod tests {
             func.func(print-op-stats{json=false}))"
         );
     }
+
+    #[test]
+    fn parse_instrumented_pass_pipeline() {
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl<'c> PassInstrumentation<'c> for Recorder {
+            fn run_before_pass(
+                &mut self,
+                pass: &str,
+                _operation: OperationRef<'c, '_>,
+            ) -> Result<(), String> {
+                self.0.lock().unwrap().push(pass.into());
+                Ok(())
+            }
+        }
+
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+        let passes = Arc::new(Mutex::new(vec![]));
+
+        register_all_passes();
+        manager.add_instrumentation(Recorder(passes.clone()));
+
+        parse_pass_pipeline(
+            manager.as_operation_pass_manager(),
+            "builtin.module(cse,func.func(canonicalize))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "builtin.module(cse,func.func(canonicalize))"
+        );
+
+        manager
+            .run(&mut Module::parse(&context, "func.func @foo() { return }").unwrap())
+            .unwrap();
+
+        assert_eq!(*passes.lock().unwrap(), ["cse", "canonicalize"]);
+    }
+
+    #[test]
+    fn fail_to_parse_pass_pipeline_atomically() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_all_passes();
+        manager.enable_timing();
+        manager.add_pipeline("cse").unwrap();
+
+        assert!(matches!(
+            parse_pass_pipeline(
+                manager.as_operation_pass_manager(),
+                "builtin.module(cse,canonicalize{foo=42})"
+            ),
+            Err(Error::ParsePassPipeline(_))
+        ));
+        assert_eq!(manager.as_operation_pass_manager().to_string(), "any(cse)");
+
+        assert!(matches!(
+            parse_pass_pipeline(manager.as_operation_pass_manager(), "canonicalize{foo=42}"),
+            Err(Error::ParsePassPipeline(_))
+        ));
+        assert_eq!(manager.as_operation_pass_manager().to_string(), "any(cse)");
+    }
+
+    #[test]
+    fn add_pass_with_options() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        pass::transform::register_canonicalizer();
+
+        manager
+            .add_pass_with_options("canonicalize", "max-iterations=3 top-down=false")
+            .unwrap();
+
+        let pipeline = manager.as_operation_pass_manager().to_string();
+
+        assert!(pipeline.contains("max-iterations=3"));
+        assert!(pipeline.contains("top-down=false"));
+    }
+
+    #[test]
+    fn add_pass_with_typed_options() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        pass::transform::register_canonicalizer();
+
+        manager
+            .add_pass_with_typed_options(
+                &pass::transform::CanonicalizerOptions::new().max_iterations(7),
+            )
+            .unwrap();
+
+        assert!(manager
+            .as_operation_pass_manager()
+            .to_string()
+            .contains("max-iterations=7"));
+    }
+
+    #[test]
+    fn fail_to_add_pass_with_invalid_options() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        pass::transform::register_canonicalizer();
+
+        assert!(matches!(
+            manager.add_pass_with_options("canonicalize", "max-iterations=foo"),
+            Err(Error::ParsePassPipeline(_))
+        ));
+        assert!(matches!(
+            manager.add_pass_with_options("canonicalize", "foo=42"),
+            Err(Error::ParsePassPipeline(_))
+        ));
+    }
+
+    #[test]
+    fn fail_to_add_invalid_pipeline_atomically() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_all_passes();
+
+        assert!(matches!(
+            manager.add_pipeline("cse,func.func(cse),canonicalize{foo=42}"),
+            Err(Error::ParsePassPipeline(_))
+        ));
+        assert_eq!(manager.as_operation_pass_manager().to_string(), "any()");
+
+        manager.enable_timing();
+
+        assert!(matches!(
+            manager.add_pipeline("cse,func.func(cse),canonicalize{foo=42}"),
+            Err(Error::ParsePassPipeline(_))
+        ));
+        assert_eq!(manager.as_operation_pass_manager().to_string(), "any()");
+    }
+
+    #[test]
+    fn statistics() {
+        #[repr(align(8))]
+        struct PassId;
+
+        static PASS_ID: PassId = PassId;
+
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+        let statistic = PassStatistic::new("count", "num-operations", "Number of operations");
+
+        manager.add_pass(
+            pass::create_external(
+                {
+                    let statistic = statistic.clone();
+                    move |_: OperationRef, _: ExternalPass| statistic.increment()
+                },
+                TypeId::create(&PASS_ID),
+                "count",
+                "count",
+                "Counts operations.",
+                "",
+                &[],
+            )
+            .with_statistics(vec![statistic]),
+        );
+
+        let mut module = Module::new(Location::unknown(&context));
+
+        manager.run(&mut module).unwrap();
+        manager.run(&mut module).unwrap();
+
+        let statistics = manager.statistics();
+
+        assert_eq!(statistics.len(), 1);
+        assert_eq!(statistics[0].pass(), "count");
+        assert_eq!(statistics[0].name(), "num-operations");
+        assert_eq!(statistics[0].value(), 1);
+    }
+
+    #[test]
+    fn reset_timing_report() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+        let mut module = Module::new(Location::unknown(&context));
+
+        manager.enable_timing();
+        manager.add_pass(pass::transform::create_cse());
+        manager.run(&mut module).unwrap();
+        manager.run(&mut module).unwrap();
+
+        let report = manager.timing_report().unwrap();
+
+        assert_eq!(report.children().len(), 1);
+        assert_eq!(report.children()[0].name(), "cse");
+        assert!(report.user_time() <= report.wall_time());
+    }
+
+    #[test]
+    fn run_on_function_operation() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo(%arg0 : i32) -> i32 {
+                    %0 = arith.addi %arg0, %arg0 : i32
+                    %1 = arith.addi %arg0, %arg0 : i32
+                    return %1 : i32
+                }
+                "
+            ),
+        )
+        .unwrap();
+        let manager = PassManager::new_on(&context, "func.func");
+
+        manager.add_pass(pass::transform::create_cse());
+
+        assert_eq!(manager.anchor(), "func.func");
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "func.func(cse)"
+        );
+
+        let mut function = module.body().first_operation_mut().unwrap();
+
+        assert_eq!(manager.run_on(&mut function), Ok(()));
+        assert!(!module
+            .as_operation()
+            .to_string()
+            .contains("%1 = arith.addi"));
+    }
+
+    #[test]
+    fn fail_to_run_on_mismatched_operation() {
+        let context = create_test_context();
+        let mut module = Module::new(Location::unknown(&context));
+        let manager = PassManager::new_on(&context, "func.func");
+
+        assert_eq!(
+            manager.run_on(&mut module.as_operation_mut()),
+            Err(Error::PassAnchorMismatch {
+                expected: "func.func".into(),
+                actual: "builtin.module".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn anchor_from_raw() {
+        let context = create_test_context();
+        let manager = PassManager::new_on(&context, "func.func");
+
+        assert_eq!(manager.anchor(), "func.func");
+
+        let manager = unsafe { PassManager::from_raw(manager.into_raw()) };
+
+        assert_eq!(manager.anchor(), "func.func");
+        assert_eq!(PassManager::new(&context).anchor(), "any");
+    }
 }
@@ -1,10 +1,19 @@
-use super::PassManager;
-use crate::{pass::Pass, string_ref::StringRef};
+use super::{
+    instrumentation::{add_options_marker, strip_instrumentation, Instrumentation},
+    pipeline::{split_nested_pipeline, split_pipeline},
+    registry::{contains_registered_pass, create_registered_pass},
+    PassManager, PassOptions,
+};
+use crate::{
+    context::Context, logical_result::LogicalResult, pass::Pass, string_ref::StringRef,
+    utility::handle_parse_error, Error,
+};
 use mlir_sys::{
-    mlirOpPassManagerAddOwnedPass, mlirOpPassManagerGetNestedUnder, mlirPrintPassPipeline,
-    MlirOpPassManager, MlirStringRef,
+    mlirOpPassManagerAddOwnedPass, mlirOpPassManagerAddPipeline, mlirOpPassManagerGetNestedUnder,
+    mlirPrintPassPipeline, MlirOpPassManager, MlirStringRef,
 };
 use std::{
+    convert::Infallible,
     ffi::c_void,
     fmt::{self, Display, Formatter},
     marker::PhantomData,
@@ -14,21 +23,137 @@ use std::{
 #[derive(Clone, Copy, Debug)]
 pub struct OperationPassManager<'c, 'a> {
     raw: MlirOpPassManager,
+    instrumentation: Option<&'a Instrumentation<'c>>,
     _parent: PhantomData<&'a PassManager<'c>>,
 }
 
-impl OperationPassManager<'_, '_> {
+impl<'c, 'a> OperationPassManager<'c, 'a> {
     /// Returns an operation pass manager for nested operations corresponding to
     /// a given name.
     pub fn nested_under(&self, name: &str) -> Self {
         let name = StringRef::new(name);
 
-        unsafe { Self::from_raw(mlirOpPassManagerGetNestedUnder(self.raw, name.to_raw())) }
+        Self {
+            instrumentation: self.instrumentation,
+            ..unsafe { Self::from_raw(mlirOpPassManagerGetNestedUnder(self.raw, name.to_raw())) }
+        }
     }
 
     /// Adds a pass.
     pub fn add_pass(&self, pass: Pass) {
+        if let Some(instrumentation) = self.instrumentation {
+            instrumentation.add_statistics(pass.statistics());
+        }
+
+        if let Some(instrumentation) = self.instrumentation() {
+            instrumentation
+                .instrument(*self, || Ok::<_, Infallible>(self.add_raw_pass(pass)))
+                .unwrap_or_else(|error| match error {});
+        } else {
+            self.add_raw_pass(pass);
+        }
+    }
+
+    /// Adds passes in a textual pass pipeline, such as
+    /// `canonicalize{max-iterations=3},cse`.
+    ///
+    /// Passes must be registered. Rust passes registered with
+    /// [`register_external_pass`](super::register_external_pass) are also
+    /// available.
+    ///
+    /// A whole pipeline is validated before any passes are added, so no passes
+    /// are added if it is invalid.
+    pub fn add_pipeline(&self, pipeline: &str) -> Result<(), Error> {
+        // MLIR passes are validated by parsing them into a scratch pass
+        // manager as MLIR can add some passes before it fails.
+        let context = Context::new();
+        let scratch = PassManager::new_on(
+            &context,
+            split_nested_pipeline(&self.to_string()).map_or("any", |(name, _)| name),
+        );
+        let scratch = scratch.as_operation_pass_manager();
+
+        if self.instrumentation().is_none() && !contains_registered_pass(pipeline) {
+            scratch.add_raw_pipeline(pipeline)?;
+
+            return self.add_raw_pipeline(pipeline);
+        }
+
+        for element in parse_elements(scratch, pipeline)? {
+            self.add_element(element)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a pass with a pass argument and an options string, such as
+    /// `canonicalize` and `max-iterations=3 top-down=false`.
+    ///
+    /// A pass must be registered.
+    pub fn add_pass_with_options(&self, argument: &str, options: &str) -> Result<(), Error> {
+        self.add_pipeline(&format!("{argument}{{{options}}}"))
+    }
+
+    /// Adds a pass with typed options.
+    ///
+    /// A pass must be registered.
+    pub fn add_pass_with_typed_options<T: PassOptions>(&self, options: &T) -> Result<(), Error> {
+        self.add_pass_with_options(T::ARGUMENT, &options.to_string())
+    }
+
+    fn add_element(&self, element: Element) -> Result<(), Error> {
+        match element {
+            Element::Nested(name, elements) => {
+                let manager = self.nested_under(&name);
+
+                for element in elements {
+                    manager.add_element(element)?;
+                }
+
+                Ok(())
+            }
+            Element::Pass(pass) => {
+                self.add_pass(pass);
+
+                Ok(())
+            }
+            Element::Raw(element) => {
+                if let Some(instrumentation) = self.instrumentation() {
+                    instrumentation.instrument(*self, || self.add_raw_pipeline(&element))
+                } else {
+                    self.add_raw_pipeline(&element)
+                }
+            }
+        }
+    }
+
+    fn add_raw_pass(&self, pass: Pass) {
         unsafe { mlirOpPassManagerAddOwnedPass(self.raw, pass.to_raw()) }
+
+        if !pass.options().is_empty() {
+            add_options_marker(*self, pass.options());
+        }
+    }
+
+    fn add_raw_pipeline(&self, pipeline: &str) -> Result<(), Error> {
+        let mut error_message = None;
+
+        let result = LogicalResult::from_raw(unsafe {
+            mlirOpPassManagerAddPipeline(
+                self.raw,
+                StringRef::new(pipeline).to_raw(),
+                Some(handle_parse_error),
+                &mut error_message as *mut _ as *mut _,
+            )
+        });
+
+        if result.is_success() {
+            Ok(())
+        } else {
+            Err(Error::ParsePassPipeline(error_message.unwrap_or_else(
+                || "failed to parse error message in UTF-8".into(),
+            )))
+        }
     }
 
     /// Converts an operation pass manager into a raw object.
@@ -44,29 +169,65 @@ impl OperationPassManager<'_, '_> {
     pub unsafe fn from_raw(raw: MlirOpPassManager) -> Self {
         Self {
             raw,
+            instrumentation: None,
             _parent: Default::default(),
         }
     }
+
+    pub(crate) const fn with_instrumentation(
+        self,
+        instrumentation: &'a Instrumentation<'c>,
+    ) -> Self {
+        Self {
+            instrumentation: Some(instrumentation),
+            ..self
+        }
+    }
+
+    fn instrumentation(&self) -> Option<&'a Instrumentation<'c>> {
+        self.instrumentation
+            .filter(|instrumentation| instrumentation.is_enabled())
+    }
+}
+
+/// A validated element of a textual pass pipeline.
+enum Element {
+    Nested(String, Vec<Element>),
+    Pass(Pass),
+    Raw(String),
+}
+
+fn parse_elements(scratch: OperationPassManager, pipeline: &str) -> Result<Vec<Element>, Error> {
+    split_pipeline(pipeline)
+        .into_iter()
+        .map(|element| {
+            Ok(
+                if let Some((name, pipeline)) = split_nested_pipeline(element) {
+                    Element::Nested(
+                        name.into(),
+                        parse_elements(scratch.nested_under(name), pipeline)?,
+                    )
+                } else if let Some(pass) = create_registered_pass(element) {
+                    Element::Pass(pass?)
+                } else {
+                    scratch.add_raw_pipeline(element)?;
+                    Element::Raw(element.into())
+                },
+            )
+        })
+        .collect()
 }
 
 impl Display for OperationPassManager<'_, '_> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
-        let mut data = (formatter, Ok(()));
+        let mut data = (String::new(), Ok(()));
 
         unsafe extern "C" fn callback(string: MlirStringRef, data: *mut c_void) {
-            let data = &mut *(data as *mut (&mut Formatter, fmt::Result));
-            let result = (|| -> fmt::Result {
-                write!(
-                    data.0,
-                    "{}",
-                    StringRef::from_raw(string)
-                        .as_str()
-                        .map_err(|_| fmt::Error)?
-                )
-            })();
-
-            if data.1.is_ok() {
-                data.1 = result;
+            let data = &mut *(data as *mut (String, fmt::Result));
+
+            match StringRef::from_raw(string).as_str() {
+                Ok(string) => data.0.push_str(string),
+                Err(_) => data.1 = Err(fmt::Error),
             }
         }
 
@@ -74,6 +235,9 @@ impl Display for OperationPassManager<'_, '_> {
             mlirPrintPassPipeline(self.raw, Some(callback), &mut data as *mut _ as *mut c_void);
         }
 
-        data.1
+        data.1?;
+
+        // Instrumentation passes are hidden.
+        write!(formatter, "{}", strip_instrumentation(&data.0))
     }
 }
@@ -0,0 +1,272 @@
+use crate::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Typed options of a pass.
+///
+/// Options are rendered by their `Display` implementations as textual pass
+/// options, such as `max-iterations=3 top-down=false`.
+pub trait PassOptions: Display {
+    /// A pass argument in textual pass pipelines, such as `canonicalize`.
+    const ARGUMENT: &'static str;
+}
+
+/// A value of a pass option.
+pub trait PassOptionValue {
+    /// Formats a value in the textual pass option syntax.
+    fn format(&self, formatter: &mut Formatter) -> fmt::Result;
+
+    /// Parses a value in the textual pass option syntax.
+    fn parse(value: &str) -> Option<Self>
+    where
+        Self: Sized;
+}
+
+macro_rules! impl_display_option_value {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl PassOptionValue for $type {
+                fn format(&self, formatter: &mut Formatter) -> fmt::Result {
+                    write!(formatter, "{self}")
+                }
+
+                fn parse(value: &str) -> Option<Self> {
+                    value.trim().parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_display_option_value!(i32, i64, u32, u64, usize, f64);
+
+impl PassOptionValue for bool {
+    fn format(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{self}")
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        // Flags without values are enabled as in MLIR.
+        match value.trim() {
+            "" | "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl PassOptionValue for String {
+    fn format(&self, formatter: &mut Formatter) -> fmt::Result {
+        if self.is_empty()
+            || self
+                .chars()
+                .any(|character| character.is_whitespace() || ",{}\"'".contains(character))
+        {
+            write!(formatter, "{{{self}}}")
+        } else {
+            write!(formatter, "{self}")
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        Some(
+            value
+                .strip_prefix('{')
+                .and_then(|value| value.strip_suffix('}'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('"')
+                        .and_then(|value| value.strip_suffix('"'))
+                })
+                .unwrap_or(value)
+                .into(),
+        )
+    }
+}
+
+impl<T: PassOptionValue> PassOptionValue for Vec<T> {
+    fn format(&self, formatter: &mut Formatter) -> fmt::Result {
+        for (index, value) in self.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, ",")?;
+            }
+
+            value.format(formatter)?;
+        }
+
+        Ok(())
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let value = value
+            .strip_prefix('{')
+            .and_then(|value| value.strip_suffix('}'))
+            .unwrap_or(value);
+
+        if value.is_empty() {
+            return Some(vec![]);
+        }
+
+        split_top_level(value, |character| character == ',')
+            .into_iter()
+            .map(T::parse)
+            .collect()
+    }
+}
+
+/// A region simplification level of greedy pattern rewrite drivers.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegionSimplificationLevel {
+    /// Regions are not simplified.
+    Disabled,
+    /// Unreachable blocks and dead block arguments are erased.
+    Normal,
+    /// Identical blocks are also merged in addition to the normal
+    /// simplification.
+    Aggressive,
+}
+
+impl PassOptionValue for RegionSimplificationLevel {
+    fn format(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                Self::Disabled => "disabled",
+                Self::Normal => "normal",
+                Self::Aggressive => "aggressive",
+            }
+        )
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "disabled" => Some(Self::Disabled),
+            "normal" => Some(Self::Normal),
+            "aggressive" => Some(Self::Aggressive),
+            _ => None,
+        }
+    }
+}
+
+/// Parses textual pass options, such as `max-iterations=3 top-down=false`,
+/// into key-value pairs.
+///
+/// Options without values, such as `top-down`, have empty values.
+pub(crate) fn parse_pass_options(options: &str) -> Result<Vec<(&str, &str)>, Error> {
+    let options = options.trim();
+    let options = options
+        .strip_prefix('{')
+        .and_then(|options| options.strip_suffix('}'))
+        .unwrap_or(options);
+
+    split_top_level(options, char::is_whitespace)
+        .into_iter()
+        .filter(|option| !option.is_empty())
+        .map(|option| match option.split_once('=') {
+            Some((key, _)) if key.is_empty() => {
+                Err(Error::ParsePassOption(format!("missing key in `{option}`")))
+            }
+            Some((key, value)) => Ok((key, value)),
+            None => Ok((option, "")),
+        })
+        .collect()
+}
+
+/// Splits a string by separators outside of braces and quotes.
+fn split_top_level(string: &str, is_separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (index, character) in string.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            '{' if !quoted => depth += 1,
+            '}' if !quoted => depth = depth.saturating_sub(1),
+            _ if !quoted && depth == 0 && is_separator(character) => {
+                parts.push(&string[start..index]);
+                start = index + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&string[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::{conversion::FuncToLlvmOptions, transform::CanonicalizerOptions};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn display_empty_options() {
+        assert_eq!(CanonicalizerOptions::new().to_string(), "");
+    }
+
+    #[test]
+    fn display_options() {
+        assert_eq!(
+            CanonicalizerOptions::new()
+                .max_iterations(3)
+                .top_down(false)
+                .region_simplify(RegionSimplificationLevel::Aggressive)
+                .disable_patterns(vec!["foo".into(), "bar baz".into()])
+                .to_string(),
+            "top-down=false region-simplify=aggressive max-iterations=3 \
+            disable-patterns=foo,{bar baz}"
+        );
+        assert_eq!(
+            FuncToLlvmOptions::new()
+                .use_bare_ptr_memref_call_conv(true)
+                .to_string(),
+            "use-bare-ptr-memref-call-conv=true"
+        );
+    }
+
+    #[test]
+    fn parse_option_values() {
+        assert_eq!(bool::parse("true"), Some(true));
+        assert_eq!(bool::parse(""), Some(true));
+        assert_eq!(u32::parse("42"), Some(42));
+        assert_eq!(u32::parse("foo"), None);
+        assert_eq!(String::parse("{bar baz}"), Some("bar baz".into()));
+        assert_eq!(
+            Vec::<String>::parse("foo,{bar, baz}"),
+            Some(vec!["foo".into(), "bar, baz".into()])
+        );
+        assert_eq!(Vec::<i64>::parse(""), Some(vec![]));
+        assert_eq!(
+            RegionSimplificationLevel::parse("normal"),
+            Some(RegionSimplificationLevel::Normal)
+        );
+    }
+
+    #[test]
+    fn parse_options() {
+        assert_eq!(
+            parse_pass_options("max-iterations=3  top-down disable-patterns=foo,{bar baz}")
+                .unwrap(),
+            [
+                ("max-iterations", "3"),
+                ("top-down", ""),
+                ("disable-patterns", "foo,{bar baz}")
+            ]
+        );
+        assert_eq!(parse_pass_options("{a=1}").unwrap(), [("a", "1")]);
+        assert_eq!(parse_pass_options("").unwrap(), []);
+        assert!(parse_pass_options("=1").is_err());
+    }
+
+    #[test]
+    fn argument() {
+        assert_eq!(CanonicalizerOptions::ARGUMENT, "canonicalize");
+        assert_eq!(FuncToLlvmOptions::ARGUMENT, "convert-func-to-llvm");
+    }
+}
@@ -0,0 +1,874 @@
+//! Rewrite patterns and pattern drivers.
+
+use crate::{
+    context::Context,
+    ir::{
+        operation::{
+            MemoryEffectOpInterface, OperationLike, OperationPrintingFlags, WalkOrder, WalkResult,
+        },
+        AttributeLike, Block, BlockLike, BlockRef, Location, Module, OperationRef, Region,
+        RegionLike, RegionRef, RewriteListener, Rewriter, ValueLike,
+    },
+    Error,
+};
+use mlir_sys::{
+    mlirApplyPatternsAndFoldGreedilyWithOp, mlirBlockDetach, mlirFreezeRewritePattern,
+    mlirFrozenRewritePatternSetDestroy, mlirOpOperandIsNull, mlirPDLPatternModuleDestroy,
+    mlirPDLPatternModuleFromModule, mlirRewritePatternSetDestroy,
+    mlirRewritePatternSetFromPDLPatternModule, mlirValueGetFirstUse, MlirFrozenRewritePatternSet,
+    MlirGreedyRewriteDriverConfig, MlirOperation, MlirPDLPatternModule,
+};
+use std::{
+    cell::RefCell,
+    collections::{HashSet, VecDeque},
+    ffi::c_void,
+    marker::PhantomData,
+    ptr::null_mut,
+    rc::Rc,
+};
+
+/// A trait for rewrite patterns written in Rust.
+///
+/// Patterns must mutate IR only through rewriters given to them.
+///
+/// # Examples
+///
+/// The following example pattern erases all `arith.constant` operations.
+///
+/// ```
+/// use melior::{
+///     ir::{OperationRef, Rewriter},
+///     pass::pattern::RewritePattern,
+/// };
+///
+/// struct EraseConstant;
+///
+/// impl<'c> RewritePattern<'c> for EraseConstant {
+///     fn root(&self) -> Option<&str> {
+///         Some("arith.constant")
+///     }
+///
+///     fn match_and_rewrite(
+///         &self,
+///         operation: OperationRef<'c, '_>,
+///         rewriter: &Rewriter<'c>,
+///     ) -> bool {
+///         rewriter.erase_operation(operation);
+///         true
+///     }
+/// }
+/// ```
+pub trait RewritePattern<'c> {
+    /// Returns a name of root operations, or `None` to match any operations.
+    fn root(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns a benefit.
+    ///
+    /// Patterns with larger benefits are applied first.
+    fn benefit(&self) -> usize {
+        1
+    }
+
+    /// Matches and rewrites an operation.
+    ///
+    /// Returns `true` if the operation is rewritten.
+    fn match_and_rewrite(&self, operation: OperationRef<'c, '_>, rewriter: &Rewriter<'c>) -> bool;
+}
+
+/// A set of rewrite patterns written in Rust.
+#[derive(Default)]
+pub struct RewritePatternSet<'c> {
+    patterns: Vec<Box<dyn RewritePattern<'c> + 'c>>,
+}
+
+impl<'c> RewritePatternSet<'c> {
+    /// Creates a rewrite pattern set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pattern.
+    pub fn add(&mut self, pattern: impl RewritePattern<'c> + 'c) {
+        self.patterns.push(Box::new(pattern));
+        // The sort is stable, so patterns with the same benefit keep their order.
+        self.patterns
+            .sort_by_key(|pattern| std::cmp::Reverse(pattern.benefit()));
+    }
+
+    /// Applies patterns to operations nested in an operation greedily until
+    /// no pattern applies.
+    ///
+    /// Trivially dead operations are erased as well. An operation is trivially
+    /// dead if its results are unused and it has no memory effect according
+    /// to [`MemoryEffectOpInterface`].
+    ///
+    /// The root operation itself is not rewritten.
+    ///
+    /// It fails without rewriting IR if a configuration cannot be honored.
+    /// See [`GreedyRewriteConfig::fold`].
+    pub fn apply_greedily(
+        &self,
+        operation: &impl OperationLike<'c, '_>,
+        config: &GreedyRewriteConfig,
+    ) -> Result<(), Error> {
+        config.validate()?;
+
+        let worklist = Worklist::default();
+        let rewriter = Rewriter::from_operation(operation).with_listener(worklist.clone());
+        let root = operation.to_raw().ptr;
+        let folder = config.fold.then(|| {
+            let context = unsafe { operation.context().to_ref() };
+
+            PdlPatternModule::from_module(Module::new(Location::unknown(context))).freeze()
+        });
+
+        for _ in 0..config.max_iterations {
+            operation.walk(
+                if config.top_down {
+                    WalkOrder::PreOrder
+                } else {
+                    WalkOrder::PostOrder
+                },
+                |nested| {
+                    if nested.to_raw().ptr != root {
+                        worklist.push(nested.to_raw());
+                    }
+
+                    WalkResult::Advance
+                },
+            );
+
+            let mut changed = false;
+
+            while let Some(operation) = worklist.pop() {
+                let operation = unsafe { OperationRef::from_raw(operation) };
+
+                if is_trivially_dead(operation) {
+                    rewriter.erase_operation(operation);
+                    changed = true;
+                } else {
+                    changed |= self.apply_once(operation, &rewriter);
+                }
+            }
+
+            if config.region_simplification {
+                changed |= simplify_regions(operation, &rewriter);
+            }
+
+            if let Some(folder) = &folder {
+                let before = fingerprint(operation)?;
+
+                apply_patterns_greedily(operation, folder)?;
+
+                changed |= before != fingerprint(operation)?;
+            }
+
+            if !changed {
+                return Ok(());
+            }
+        }
+
+        Err(Error::ApplyPatterns)
+    }
+
+    fn apply_once(&self, operation: OperationRef<'c, '_>, rewriter: &Rewriter<'c>) -> bool {
+        let name = operation.name();
+        let name = name.as_string_ref().as_str().ok();
+
+        self.patterns
+            .iter()
+            .filter(|pattern| pattern.root().is_none() || pattern.root() == name)
+            .any(|pattern| {
+                rewriter.set_insertion_point_before(&operation);
+                pattern.match_and_rewrite(operation, rewriter)
+            })
+    }
+}
+
+/// A worklist of operations for pattern drivers.
+///
+/// It is notified by rewriters as a listener, so that erased operations are
+/// dropped and inserted operations are added.
+#[derive(Clone, Default)]
+pub(crate) struct Worklist {
+    state: Rc<RefCell<WorklistState>>,
+}
+
+#[derive(Default)]
+struct WorklistState {
+    operations: VecDeque<MlirOperation>,
+    queued: HashSet<*mut c_void>,
+}
+
+impl Worklist {
+    /// Pushes an operation unless it is already queued.
+    pub(crate) fn push(&self, operation: MlirOperation) {
+        let mut state = self.state.borrow_mut();
+
+        if state.queued.insert(operation.ptr) {
+            state.operations.push_back(operation);
+        }
+    }
+
+    /// Pops an operation that is not erased.
+    pub(crate) fn pop(&self) -> Option<MlirOperation> {
+        let mut state = self.state.borrow_mut();
+
+        while let Some(operation) = state.operations.pop_front() {
+            if state.queued.remove(&operation.ptr) {
+                return Some(operation);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'c> RewriteListener<'c> for Worklist {
+    fn notify_operation_inserted(&self, operation: OperationRef<'c, '_>) {
+        self.push(operation.to_raw());
+    }
+
+    fn notify_operation_erased(&self, operation: OperationRef<'c, '_>) {
+        self.state
+            .borrow_mut()
+            .queued
+            .remove(&operation.to_raw().ptr);
+    }
+}
+
+fn is_trivially_dead(operation: OperationRef) -> bool {
+    operation.result_count() > 0
+        && operation
+            .results()
+            .all(|result| unsafe { mlirOpOperandIsNull(mlirValueGetFirstUse(result.to_raw())) })
+        && MemoryEffectOpInterface::new(operation)
+            .is_some_and(|interface| interface.is_effect_free())
+}
+
+/// Erases blocks unreachable from entry blocks in regions nested in an
+/// operation.
+fn simplify_regions<'c>(operation: &impl OperationLike<'c, '_>, rewriter: &Rewriter<'c>) -> bool {
+    let mut regions = vec![];
+
+    // Inner regions come first so that they are not visited after their
+    // parent blocks are erased.
+    operation.walk(WalkOrder::PostOrder, |operation| {
+        regions.extend(operation.regions().map(|region| region.to_raw()));
+        WalkResult::Advance
+    });
+
+    let mut changed = false;
+
+    for region in regions {
+        changed |= erase_unreachable_blocks(unsafe { RegionRef::from_raw(region) }, rewriter);
+    }
+
+    changed
+}
+
+fn erase_unreachable_blocks<'c>(region: RegionRef<'c, '_>, rewriter: &Rewriter<'c>) -> bool {
+    let Some(entry) = region.first_block() else {
+        return false;
+    };
+    let mut reachable = HashSet::from([entry.to_raw().ptr]);
+    let mut blocks = vec![entry];
+
+    while let Some(block) = blocks.pop() {
+        if let Some(terminator) = block.terminator() {
+            for successor in terminator.successors() {
+                if reachable.insert(successor.to_raw().ptr) {
+                    blocks.push(successor);
+                }
+            }
+        }
+    }
+
+    let mut unreachable = vec![];
+    let mut block = entry.next_in_region();
+
+    while let Some(current) = block {
+        if !reachable.contains(&current.to_raw().ptr) {
+            unreachable.push(current.to_raw());
+        }
+
+        block = current.next_in_region();
+    }
+
+    if unreachable.is_empty() {
+        return false;
+    }
+
+    // Unreachable blocks are moved into a region, which drops references
+    // between them before destroying them.
+    let dead = Region::new();
+
+    for block in unreachable {
+        let mut operation = unsafe { BlockRef::from_raw(block) }.first_operation();
+
+        while let Some(current) = operation {
+            rewriter.notify_erased(current);
+            operation = current.next_in_block();
+        }
+
+        unsafe {
+            mlirBlockDetach(block);
+            dead.append_block(Block::from_raw(block));
+        }
+    }
+
+    true
+}
+
+/// Prints an operation in the generic form with locations to detect changes
+/// by MLIR's greedy driver, including in-place updates of attributes and
+/// types.
+fn fingerprint<'c>(operation: &impl OperationLike<'c, '_>) -> Result<String, Error> {
+    operation.to_string_with_flags(
+        OperationPrintingFlags::new()
+            .enable_debug_info(true, false)
+            .print_generic_operation_form(),
+    )
+}
+
+// The default of MLIR's greedy driver.
+const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+/// A configuration of greedy rewrite drivers.
+#[derive(Clone, Copy, Debug)]
+pub struct GreedyRewriteConfig {
+    max_iterations: usize,
+    top_down: bool,
+    fold: bool,
+    region_simplification: bool,
+}
+
+impl GreedyRewriteConfig {
+    /// Creates a configuration.
+    pub const fn new() -> Self {
+        Self {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            top_down: true,
+            fold: true,
+            region_simplification: true,
+        }
+    }
+
+    /// Sets a maximum number of iterations over IR.
+    pub const fn max_iterations(mut self, count: usize) -> Self {
+        self.max_iterations = count;
+        self
+    }
+
+    /// Sets if operations are visited from top to bottom.
+    pub const fn top_down(mut self, enabled: bool) -> Self {
+        self.top_down = enabled;
+        self
+    }
+
+    /// Sets if operations are folded.
+    ///
+    /// Folding is delegated to MLIR's greedy driver without patterns after
+    /// every iteration. The MLIR C API cannot configure the driver, so it
+    /// always visits operations from top to bottom, simplifies regions, and
+    /// iterates at most 10 times. Therefore, folding fails with other
+    /// configurations and must be disabled for them.
+    pub const fn fold(mut self, enabled: bool) -> Self {
+        self.fold = enabled;
+        self
+    }
+
+    /// Sets if regions are simplified by erasing unreachable blocks.
+    pub const fn region_simplification(mut self, enabled: bool) -> Self {
+        self.region_simplification = enabled;
+        self
+    }
+}
+
+impl GreedyRewriteConfig {
+    fn validate(&self) -> Result<(), Error> {
+        if !self.fold {
+            Ok(())
+        } else if !self.top_down {
+            Err(Error::GreedyRewriteConfig(
+                "folding requires top-down traversal",
+            ))
+        } else if !self.region_simplification {
+            Err(Error::GreedyRewriteConfig(
+                "folding requires region simplification",
+            ))
+        } else if self.max_iterations != DEFAULT_MAX_ITERATIONS {
+            Err(Error::GreedyRewriteConfig(
+                "folding requires default maximum number of iterations",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for GreedyRewriteConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A PDL pattern module.
+///
+/// Native constraint and rewrite functions cannot be registered because the
+/// MLIR C API does not expose them yet. Patterns can use only PDL operations.
+pub struct PdlPatternModule<'c> {
+    raw: MlirPDLPatternModule,
+    _context: PhantomData<&'c Context>,
+}
+
+impl<'c> PdlPatternModule<'c> {
+    /// Creates a PDL pattern module from a module of `pdl.pattern` operations.
+    pub fn from_module(module: Module<'c>) -> Self {
+        Self {
+            raw: unsafe { mlirPDLPatternModuleFromModule(module.into_raw()) },
+            _context: Default::default(),
+        }
+    }
+
+    /// Compiles patterns into a frozen pattern set.
+    pub fn freeze(self) -> FrozenPatternSet<'c> {
+        unsafe {
+            let set = mlirRewritePatternSetFromPDLPatternModule(self.raw);
+            let frozen = FrozenPatternSet::from_raw(mlirFreezeRewritePattern(set));
+
+            // Freezing moves patterns out of the set but does not destroy it.
+            mlirRewritePatternSetDestroy(set);
+
+            frozen
+        }
+    }
+
+    /// Converts a PDL pattern module into a raw object.
+    pub const fn to_raw(&self) -> MlirPDLPatternModule {
+        self.raw
+    }
+}
+
+impl Drop for PdlPatternModule<'_> {
+    fn drop(&mut self) {
+        unsafe { mlirPDLPatternModuleDestroy(self.raw) }
+    }
+}
+
+/// A frozen pattern set.
+///
+/// Frozen pattern sets are compiled and ready to be applied by MLIR's pattern
+/// drivers.
+pub struct FrozenPatternSet<'c> {
+    raw: MlirFrozenRewritePatternSet,
+    _context: PhantomData<&'c Context>,
+}
+
+impl FrozenPatternSet<'_> {
+    /// Creates a frozen pattern set from a raw object.
+    ///
+    /// # Safety
+    ///
+    /// A raw object must be valid.
+    pub unsafe fn from_raw(raw: MlirFrozenRewritePatternSet) -> Self {
+        Self {
+            raw,
+            _context: Default::default(),
+        }
+    }
+
+    /// Converts a frozen pattern set into a raw object.
+    pub const fn to_raw(&self) -> MlirFrozenRewritePatternSet {
+        self.raw
+    }
+}
+
+impl Drop for FrozenPatternSet<'_> {
+    fn drop(&mut self) {
+        unsafe { mlirFrozenRewritePatternSetDestroy(self.raw) }
+    }
+}
+
+/// Applies patterns to operations nested in an operation greedily with MLIR's
+/// greedy pattern driver.
+///
+/// The driver also folds operations and erases dead ones.
+pub fn apply_patterns_greedily<'c>(
+    operation: &impl OperationLike<'c, '_>,
+    patterns: &FrozenPatternSet<'c>,
+) -> Result<(), Error> {
+    let result = unsafe {
+        mlirApplyPatternsAndFoldGreedilyWithOp(
+            operation.to_raw(),
+            patterns.to_raw(),
+            // The C API ignores configurations and uses default ones.
+            MlirGreedyRewriteDriverConfig { ptr: null_mut() },
+        )
+    };
+
+    if result.value == 0 {
+        Err(Error::ApplyPatterns)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{r#type::TypeId, Module},
+        pass::{create_external, ExternalPass, PassManager},
+        test::create_test_context,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    const PDL_SOURCE: &str = indoc!(
+        "
+        pdl.pattern @fold_add_self : benefit(1) {
+            %type = pdl.type : i32
+            %value = pdl.operand
+            %operation = pdl.operation \"arith.addi\"(%value, %value : !pdl.value, !pdl.value) -> (%type : !pdl.type)
+            pdl.rewrite %operation {
+                pdl.replace %operation with (%value : !pdl.value)
+            }
+        }
+        "
+    );
+
+    // Constants in test sources are never used.
+    struct EraseConstant;
+
+    impl<'c> RewritePattern<'c> for EraseConstant {
+        fn root(&self) -> Option<&str> {
+            Some("arith.constant")
+        }
+
+        fn match_and_rewrite(
+            &self,
+            operation: OperationRef<'c, '_>,
+            rewriter: &Rewriter<'c>,
+        ) -> bool {
+            rewriter.erase_operation(operation);
+            true
+        }
+    }
+
+    // Rewrites `x + x` into `x`.
+    struct FoldAddSelf;
+
+    impl<'c> RewritePattern<'c> for FoldAddSelf {
+        fn root(&self) -> Option<&str> {
+            Some("arith.addi")
+        }
+
+        fn match_and_rewrite(
+            &self,
+            operation: OperationRef<'c, '_>,
+            rewriter: &Rewriter<'c>,
+        ) -> bool {
+            let lhs = operation.operand(0).unwrap();
+
+            if lhs != operation.operand(1).unwrap() {
+                return false;
+            }
+
+            rewriter.replace_operation_with_values(operation, &[lhs]);
+            true
+        }
+    }
+
+    // Never converges.
+    struct Toggle;
+
+    impl<'c> RewritePattern<'c> for Toggle {
+        fn match_and_rewrite(
+            &self,
+            operation: OperationRef<'c, '_>,
+            rewriter: &Rewriter<'c>,
+        ) -> bool {
+            rewriter.modify_operation_in_place(operation, |_| true)
+        }
+    }
+
+    const SOURCE: &str = indoc!(
+        "
+        func.func @foo(%arg0 : i32) -> i32 {
+            %0 = arith.constant 42 : i32
+            %1 = arith.addi %arg0, %arg0 : i32
+            %2 = arith.addi %1, %1 : i32
+            return %2 : i32
+        }
+        "
+    );
+
+    #[test]
+    fn apply_greedily() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let mut patterns = RewritePatternSet::new();
+
+        patterns.add(EraseConstant);
+        patterns.add(FoldAddSelf);
+
+        patterns
+            .apply_greedily(&module.as_operation(), &GreedyRewriteConfig::new())
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+        insta::assert_snapshot!(module.as_operation());
+    }
+
+    #[test]
+    fn apply_greedily_bottom_up() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let mut patterns = RewritePatternSet::new();
+
+        patterns.add(EraseConstant);
+        patterns.add(FoldAddSelf);
+
+        patterns
+            .apply_greedily(
+                &module.as_operation(),
+                &GreedyRewriteConfig::new().fold(false).top_down(false),
+            )
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+        insta::assert_snapshot!(module.as_operation());
+    }
+
+    #[test]
+    fn fail_to_converge() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let mut patterns = RewritePatternSet::new();
+
+        patterns.add(Toggle);
+
+        assert_eq!(
+            patterns.apply_greedily(
+                &module.as_operation(),
+                &GreedyRewriteConfig::new().fold(false).max_iterations(3),
+            ),
+            Err(Error::ApplyPatterns)
+        );
+    }
+
+    #[test]
+    fn fail_to_fold_with_unsupported_config() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let source = module.as_operation().to_string();
+        let mut patterns = RewritePatternSet::new();
+
+        patterns.add(EraseConstant);
+
+        for config in [
+            GreedyRewriteConfig::new().top_down(false),
+            GreedyRewriteConfig::new().region_simplification(false),
+            GreedyRewriteConfig::new().max_iterations(3),
+        ] {
+            assert!(matches!(
+                patterns.apply_greedily(&module.as_operation(), &config),
+                Err(Error::GreedyRewriteConfig(_))
+            ));
+        }
+
+        assert_eq!(module.as_operation().to_string(), source);
+    }
+
+    #[test]
+    fn erase_trivially_dead_operations() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo(%arg0 : i32) -> i32 {
+                    %0 = arith.addi %arg0, %arg0 : i32
+                    %1 = arith.muli %0, %0 : i32
+                    return %arg0 : i32
+                }
+                "
+            ),
+        )
+        .unwrap();
+
+        RewritePatternSet::new()
+            .apply_greedily(
+                &module.as_operation(),
+                &GreedyRewriteConfig::new().fold(false),
+            )
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+        insta::assert_snapshot!(module.as_operation());
+    }
+
+    #[test]
+    fn fold() {
+        let context = create_test_context();
+        let module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo() -> i32 {
+                    %0 = arith.constant 1 : i32
+                    %1 = arith.constant 2 : i32
+                    %2 = arith.addi %0, %1 : i32
+                    return %2 : i32
+                }
+                "
+            ),
+        )
+        .unwrap();
+
+        RewritePatternSet::new()
+            .apply_greedily(&module.as_operation(), &GreedyRewriteConfig::new())
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+        insta::assert_snapshot!(module.as_operation());
+    }
+
+    #[test]
+    fn simplify_regions() {
+        const SOURCE: &str = indoc!(
+            "
+            func.func @foo(%arg0 : i32) -> i32 {
+                return %arg0 : i32
+            ^bb1:
+                %0 = arith.addi %arg0, %arg0 : i32
+                cf.br ^bb2
+            ^bb2:
+                return %0 : i32
+            }
+            "
+        );
+
+        let context = create_test_context();
+        let block_count = |module: &Module| {
+            let mut count = 0;
+            let mut block = module
+                .body()
+                .first_operation()
+                .unwrap()
+                .region(0)
+                .unwrap()
+                .first_block();
+
+            while let Some(current) = block {
+                count += 1;
+                block = current.next_in_region();
+            }
+
+            count
+        };
+
+        let module = Module::parse(&context, SOURCE).unwrap();
+
+        RewritePatternSet::new()
+            .apply_greedily(
+                &module.as_operation(),
+                &GreedyRewriteConfig::new()
+                    .fold(false)
+                    .region_simplification(false),
+            )
+            .unwrap();
+
+        assert_eq!(block_count(&module), 3);
+
+        RewritePatternSet::new()
+            .apply_greedily(
+                &module.as_operation(),
+                &GreedyRewriteConfig::new().fold(false),
+            )
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+        assert_eq!(block_count(&module), 1);
+    }
+
+    #[test]
+    fn drop_erased_operations_from_worklist() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let worklist = Worklist::default();
+        let rewriter =
+            Rewriter::from_operation(&module.as_operation()).with_listener(worklist.clone());
+        let function = module.body().first_operation().unwrap();
+        let block = function.region(0).unwrap().first_block().unwrap();
+        let constant = block.first_operation().unwrap();
+        let addition = constant.next_in_block().unwrap();
+
+        worklist.push(constant.to_raw());
+        worklist.push(addition.to_raw());
+        worklist.push(constant.to_raw());
+
+        rewriter.erase_operation(constant);
+
+        assert_eq!(
+            worklist.pop().map(|operation| operation.ptr),
+            Some(addition.to_raw().ptr)
+        );
+        assert_eq!(worklist.pop().map(|operation| operation.ptr), None);
+    }
+
+    #[test]
+    fn apply_greedily_in_external_pass() {
+        #[repr(align(8))]
+        struct PassId;
+
+        static PASS_ID: PassId = PassId;
+
+        let context = create_test_context();
+        let mut module = Module::parse(&context, SOURCE).unwrap();
+        let manager = PassManager::new(&context);
+
+        manager.nested_under("func.func").add_pass(create_external(
+            |operation: OperationRef, pass: ExternalPass| {
+                let mut patterns = RewritePatternSet::new();
+
+                patterns.add(EraseConstant);
+                patterns.add(FoldAddSelf);
+
+                if patterns
+                    .apply_greedily(&operation, &GreedyRewriteConfig::new())
+                    .is_err()
+                {
+                    pass.signal_failure();
+                }
+            },
+            TypeId::create(&PASS_ID),
+            "fold add self",
+            "fold-add-self",
+            "Folds additions of the same values",
+            "func.func",
+            &[],
+        ));
+
+        manager.run(&mut module).unwrap();
+
+        assert!(module.as_operation().verify());
+        insta::assert_snapshot!(module.as_operation());
+    }
+
+    #[test]
+    fn apply_pdl_patterns_greedily() {
+        let context = create_test_context();
+        let module = Module::parse(&context, SOURCE).unwrap();
+        let patterns =
+            PdlPatternModule::from_module(Module::parse(&context, PDL_SOURCE).unwrap()).freeze();
+
+        apply_patterns_greedily(&module.as_operation(), &patterns).unwrap();
+
+        assert!(module.as_operation().verify());
+        insta::assert_snapshot!(module.as_operation());
+    }
+}
@@ -0,0 +1,398 @@
+use super::{PassManager, PassOptions};
+use crate::{Context, Error};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A pass pipeline.
+///
+/// A pipeline is a tree of passes anchored on operations. It is rendered by
+/// its `Display` implementation in the textual pass pipeline syntax, such as
+/// `builtin.module(func.func(canonicalize{max-iterations=3}),cse)`, and parsed
+/// back from it.
+///
+/// # Examples
+///
+/// ```
+/// use melior::pass::{transform::CanonicalizerOptions, Pipeline};
+///
+/// let pipeline = Pipeline::on("builtin.module")
+///     .nest("func.func", |pipeline| {
+///         pipeline.add(CanonicalizerOptions::new().max_iterations(3))
+///     })
+///     .add_pass("cse");
+///
+/// assert_eq!(
+///     pipeline.to_string(),
+///     "builtin.module(func.func(canonicalize{max-iterations=3}),cse)"
+/// );
+/// assert_eq!(pipeline.to_string().parse::<Pipeline>().unwrap(), pipeline);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Pipeline {
+    anchor: String,
+    elements: Vec<PipelineElement>,
+}
+
+/// An element of a pass pipeline.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PipelineElement {
+    /// A pass with a pass argument and textual options.
+    Pass { argument: String, options: String },
+    /// A pipeline nested under operations.
+    Nested(Pipeline),
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline anchored on operations of a given name, such
+    /// as `builtin.module`.
+    pub fn on(anchor: &str) -> Self {
+        Self {
+            anchor: anchor.into(),
+            elements: vec![],
+        }
+    }
+
+    /// Parses a pipeline in the textual pass pipeline syntax.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let Some((anchor, pipeline)) = split_nested_pipeline(source.trim()) else {
+            return Err(Error::ParsePassPipeline(format!(
+                "expected a pipeline of a form `op-name(...)`: {source}"
+            )));
+        };
+
+        if anchor.is_empty() {
+            return Err(Error::ParsePassPipeline(format!(
+                "missing an anchor operation name: {source}"
+            )));
+        }
+
+        Ok(Self {
+            anchor: anchor.into(),
+            elements: split_pipeline(pipeline)
+                .into_iter()
+                .map(PipelineElement::parse)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Returns a name of operations a pipeline is anchored on.
+    pub fn anchor(&self) -> &str {
+        &self.anchor
+    }
+
+    /// Returns elements.
+    pub fn elements(&self) -> &[PipelineElement] {
+        &self.elements
+    }
+
+    /// Returns mutable elements.
+    pub fn elements_mut(&mut self) -> &mut Vec<PipelineElement> {
+        &mut self.elements
+    }
+
+    /// Adds a pass with typed options.
+    pub fn add<T: PassOptions>(self, options: T) -> Self {
+        self.add_pass_with_options(T::ARGUMENT, &options.to_string())
+    }
+
+    /// Adds a pass without options, such as `cse`.
+    pub fn add_pass(self, argument: &str) -> Self {
+        self.add_pass_with_options(argument, "")
+    }
+
+    /// Adds a pass with a pass argument and an options string, such as
+    /// `canonicalize` and `max-iterations=3 top-down=false`.
+    pub fn add_pass_with_options(mut self, argument: &str, options: &str) -> Self {
+        self.elements.push(PipelineElement::Pass {
+            argument: argument.into(),
+            options: options.trim().into(),
+        });
+        self
+    }
+
+    /// Adds a pipeline nested under operations of a given name.
+    pub fn nest(mut self, anchor: &str, build: impl FnOnce(Self) -> Self) -> Self {
+        self.elements
+            .push(PipelineElement::Nested(build(Self::on(anchor))));
+        self
+    }
+
+    /// Creates a pass manager with a pipeline.
+    ///
+    /// All passes must be registered. Passes are added with
+    /// [`OperationPassManager::add_pipeline`](super::OperationPassManager::add_pipeline)
+    /// to a pass manager anchored on the anchor of a pipeline.
+    pub fn create_pass_manager<'c>(&self, context: &'c Context) -> Result<PassManager<'c>, Error> {
+        let manager = PassManager::new_on(context, &self.anchor);
+
+        if !self.elements.is_empty() {
+            manager.as_operation_pass_manager().add_pipeline(
+                &self
+                    .elements
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )?;
+        }
+
+        Ok(manager)
+    }
+
+    /// Validates a pipeline against registered passes and their options.
+    pub fn validate(&self, context: &Context) -> Result<(), Error> {
+        self.create_pass_manager(context).map(|_| ())
+    }
+}
+
+impl Display for Pipeline {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}(", self.anchor)?;
+
+        for (index, element) in self.elements.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, ",")?;
+            }
+
+            write!(formatter, "{element}")?;
+        }
+
+        write!(formatter, ")")
+    }
+}
+
+impl FromStr for Pipeline {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+impl PipelineElement {
+    fn parse(source: &str) -> Result<Self, Error> {
+        if let Some((anchor, pipeline)) = split_nested_pipeline(source) {
+            return Ok(Self::Nested(Pipeline::parse(&format!(
+                "{anchor}({pipeline})"
+            ))?));
+        }
+
+        let argument = pass_argument(source).trim();
+        let options = source[pass_argument(source).len()..].trim();
+
+        if argument.is_empty() {
+            return Err(Error::ParsePassPipeline(format!(
+                "missing a pass argument: {source}"
+            )));
+        }
+
+        let options = if options.is_empty() {
+            options
+        } else {
+            options
+                .strip_prefix('{')
+                .and_then(|options| options.strip_suffix('}'))
+                .ok_or_else(|| {
+                    Error::ParsePassPipeline(format!("invalid pass options: {source}"))
+                })?
+        };
+
+        Ok(Self::Pass {
+            argument: argument.into(),
+            options: options.trim().into(),
+        })
+    }
+}
+
+impl Display for PipelineElement {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Pass { argument, options } if options.is_empty() => {
+                write!(formatter, "{argument}")
+            }
+            Self::Pass { argument, options } => write!(formatter, "{argument}{{{options}}}"),
+            Self::Nested(pipeline) => write!(formatter, "{pipeline}"),
+        }
+    }
+}
+
+/// Splits a textual pass pipeline into top-level elements.
+pub(crate) fn split_pipeline(pipeline: &str) -> Vec<&str> {
+    let mut elements = vec![];
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (index, character) in pipeline.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            '(' | '{' | '[' if !quoted => depth += 1,
+            ')' | '}' | ']' if !quoted => depth = depth.saturating_sub(1),
+            ',' if !quoted && depth == 0 => {
+                elements.push(pipeline[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    elements.push(pipeline[start..].trim());
+    elements.retain(|element| !element.is_empty());
+
+    elements
+}
+
+/// Splits a nested pipeline element, such as `func.func(cse)`, into an anchor
+/// operation name and an inner pipeline.
+pub(crate) fn split_nested_pipeline(element: &str) -> Option<(&str, &str)> {
+    let index = element.find(['(', '{'])?;
+
+    if element[index..].starts_with('(') && element.ends_with(')') {
+        Some((
+            element[..index].trim(),
+            &element[index + 1..element.len() - 1],
+        ))
+    } else {
+        None
+    }
+}
+
+/// Returns a pass argument of a pipeline element, such as `cse` for
+/// `cse{options}`.
+pub(crate) fn pass_argument(element: &str) -> &str {
+    element
+        .find(['(', '{'])
+        .map_or(element, |index| &element[..index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pass::transform::CanonicalizerOptions, test::create_test_context,
+        utility::register_all_passes,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            split_pipeline("cse, canonicalize{a=1,2 b={x,y}},func.func(cse,sccp)"),
+            vec!["cse", "canonicalize{a=1,2 b={x,y}}", "func.func(cse,sccp)"]
+        );
+        assert_eq!(
+            split_nested_pipeline("func.func(cse,sccp)"),
+            Some(("func.func", "cse,sccp"))
+        );
+        assert_eq!(split_nested_pipeline("canonicalize{a=(1)}"), None);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Pipeline::on("builtin.module").to_string(),
+            "builtin.module()"
+        );
+        assert_eq!(
+            Pipeline::on("builtin.module")
+                .nest("func.func", |pipeline| pipeline
+                    .add(CanonicalizerOptions::new().max_iterations(3))
+                    .add_pass("cse"))
+                .add_pass_with_options("inline", "max-iterations=2")
+                .to_string(),
+            "builtin.module(func.func(canonicalize{max-iterations=3},cse),inline{max-iterations=2})"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Pipeline::parse(
+                "builtin.module( func.func(canonicalize{ max-iterations=3 top-down=false }), cse)"
+            )
+            .unwrap(),
+            Pipeline::on("builtin.module")
+                .nest("func.func", |pipeline| pipeline.add_pass_with_options(
+                    "canonicalize",
+                    "max-iterations=3 top-down=false"
+                ))
+                .add_pass("cse")
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for source in [
+            "builtin.module()",
+            "builtin.module(cse)",
+            "any(func.func(canonicalize{max-iterations=3},cse),gpu.module(gpu.func(cse)))",
+            "builtin.module(inline{default-pipeline=canonicalize op-pipelines={func.func(cse)}})",
+        ] {
+            assert_eq!(Pipeline::parse(source).unwrap().to_string(), source);
+        }
+    }
+
+    #[test]
+    fn edit() {
+        let mut pipeline = Pipeline::parse("builtin.module(cse,canonicalize)").unwrap();
+
+        pipeline.elements_mut().retain(|element| {
+            !matches!(element, PipelineElement::Pass { argument, .. } if argument == "cse")
+        });
+
+        assert_eq!(pipeline.to_string(), "builtin.module(canonicalize)");
+    }
+
+    #[test]
+    fn fail_to_parse() {
+        assert!(Pipeline::parse("cse").is_err());
+        assert!(Pipeline::parse("(cse)").is_err());
+        assert!(Pipeline::parse("builtin.module(cse{)").is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let context = create_test_context();
+
+        register_all_passes();
+
+        assert_eq!(
+            Pipeline::on("builtin.module")
+                .nest("func.func", |pipeline| pipeline.add_pass("cse"))
+                .validate(&context),
+            Ok(())
+        );
+        assert!(Pipeline::on("builtin.module")
+            .add_pass("no-such-pass")
+            .validate(&context)
+            .is_err());
+    }
+
+    #[test]
+    fn create_pass_manager() {
+        let context = create_test_context();
+
+        register_all_passes();
+
+        let pipeline =
+            Pipeline::on("builtin.module").nest("func.func", |pipeline| pipeline.add_pass("cse"));
+
+        assert_eq!(
+            pipeline
+                .create_pass_manager(&context)
+                .unwrap()
+                .as_operation_pass_manager()
+                .to_string(),
+            pipeline.to_string()
+        );
+        assert_eq!(
+            Pipeline::on("func.func")
+                .create_pass_manager(&context)
+                .unwrap()
+                .anchor(),
+            "func.func"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use super::{
+    pipeline::{pass_argument, split_nested_pipeline, split_pipeline},
+    Pass, PassDefinition,
+};
+use crate::Error;
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+type PassFactory = Arc<dyn Fn(&str) -> Result<Pass, Error> + Send + Sync>;
+
+/// Registers a factory of a Rust pass under a pass argument.
+///
+/// Registered passes can be named in textual pass pipelines, such as
+/// `builtin.module(func.func(my-pass{threshold=4}),cse)`, passed to
+/// [`parse_pass_pipeline`](crate::utility::parse_pass_pipeline) and
+/// [`OperationPassManager::add_pipeline`](super::OperationPassManager::add_pipeline).
+/// A factory receives a textual options string, such as `threshold=4`,
+/// which is empty if no options are given.
+///
+/// A factory should attach options to a created pass with
+/// [`Pass::with_options`], as
+/// [`PassDefinition::from_options`](super::PassDefinition::from_options)
+/// does, for printed pipelines to keep them.
+///
+/// # Examples
+///
+/// ```
+/// use melior::{
+///     ir::{operation::OperationLike, r#type::TypeId, OperationRef},
+///     pass::{create_external, register_external_pass, ExternalPass, PassManager},
+///     utility::parse_pass_pipeline,
+///     Context,
+/// };
+///
+/// #[repr(align(8))]
+/// struct PassId;
+///
+/// static DUMP_PASS: PassId = PassId;
+///
+/// register_external_pass("dump", |_options| {
+///     Ok(create_external(
+///         |operation: OperationRef, _pass: ExternalPass| operation.dump(),
+///         TypeId::create(&DUMP_PASS),
+///         "DumpPass",
+///         "dump",
+///         "Dumps operations.",
+///         "",
+///         &[],
+///     ))
+/// });
+///
+/// let context = Context::new();
+/// let manager = PassManager::new(&context);
+///
+/// parse_pass_pipeline(manager.as_operation_pass_manager(), "builtin.module(dump)").unwrap();
+///
+/// assert_eq!(manager.as_operation_pass_manager().to_string(), "builtin.module(dump)");
+/// ```
+pub fn register_external_pass(
+    argument: &str,
+    factory: impl Fn(&str) -> Result<Pass, Error> + Send + Sync + 'static,
+) {
+    registry()
+        .write()
+        .expect("pass registry is not poisoned")
+        .insert(argument.into(), Arc::new(factory));
+}
+
+/// Registers a pass defined with the [`pass`](macro@crate::pass) attribute
+/// macro under its pass argument.
+pub fn register_pass_definition<T: PassDefinition<'static> + Default + 'static>() {
+    register_external_pass(T::ARGUMENT, T::from_options);
+}
+
+/// Creates a registered Rust pass from a pipeline element, such as
+/// `my-pass{threshold=4}`.
+pub(crate) fn create_registered_pass(element: &str) -> Option<Result<Pass, Error>> {
+    let argument = pass_argument(element);
+    let factory = registry()
+        .read()
+        .expect("pass registry is not poisoned")
+        .get(argument)?
+        .clone();
+    let options = element[argument.len()..].trim();
+
+    Some(factory(
+        options
+            .strip_prefix('{')
+            .and_then(|options| options.strip_suffix('}'))
+            .unwrap_or(options),
+    ))
+}
+
+/// Returns `true` if a textual pass pipeline contains registered Rust passes.
+pub(crate) fn contains_registered_pass(pipeline: &str) -> bool {
+    let registry = registry().read().expect("pass registry is not poisoned");
+
+    if registry.is_empty() {
+        return false;
+    }
+
+    fn contains(registry: &HashMap<String, PassFactory>, pipeline: &str) -> bool {
+        split_pipeline(pipeline).into_iter().any(|element| {
+            if let Some((_, pipeline)) = split_nested_pipeline(element) {
+                contains(registry, pipeline)
+            } else {
+                registry.contains_key(pass_argument(element))
+            }
+        })
+    }
+
+    contains(&registry, pipeline)
+}
+
+fn registry() -> &'static RwLock<HashMap<String, PassFactory>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, PassFactory>>> = OnceLock::new();
+
+    REGISTRY.get_or_init(Default::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{Module, OperationRef},
+        pass::{ExternalPass, PassManager, PassStatistic, RunExternalPass},
+        test::create_test_context,
+        utility::parse_pass_pipeline,
+        ContextRef,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[melior::pass(name = "CountPass", argument = "test-count", anchor = "func.func")]
+    #[derive(Clone, Debug)]
+    struct CountPass {
+        #[option(default = 1)]
+        increment: u64,
+        #[statistic]
+        functions: PassStatistic,
+    }
+
+    impl<'c> RunExternalPass<'c> for CountPass {
+        fn initialize(&mut self, _context: ContextRef<'c>) {}
+
+        fn run(&mut self, _operation: OperationRef<'c, '_>, _pass: ExternalPass<'_>) {
+            self.functions.add(self.increment);
+        }
+    }
+
+    const PIPELINE: &str = "builtin.module(func.func(test-count{increment=4}),cse)";
+
+    #[test]
+    fn parse_registered_pass() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_pass_definition::<CountPass>();
+        parse_pass_pipeline(manager.as_operation_pass_manager(), PIPELINE).unwrap();
+
+        assert_eq!(manager.as_operation_pass_manager().to_string(), PIPELINE);
+
+        let mut module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo() {
+                    return
+                }
+                "
+            ),
+        )
+        .unwrap();
+
+        manager.run(&mut module).unwrap();
+
+        assert_eq!(manager.statistics()[0].value(), 4);
+    }
+
+    #[test]
+    fn collect_registered_pass_statistics() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_pass_definition::<CountPass>();
+        parse_pass_pipeline(manager.as_operation_pass_manager(), PIPELINE).unwrap();
+
+        let mut module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo() {
+                    return
+                }
+
+                func.func @bar() {
+                    return
+                }
+                "
+            ),
+        )
+        .unwrap();
+
+        manager.run(&mut module).unwrap();
+
+        let statistics = manager.statistics();
+
+        assert_eq!(statistics.len(), 1);
+        assert_eq!(statistics[0].pass(), "test-count");
+        assert_eq!(statistics[0].name(), "functions");
+        assert_eq!(statistics[0].value(), 8);
+    }
+
+    #[test]
+    fn round_trip_registered_pass() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_pass_definition::<CountPass>();
+        parse_pass_pipeline(manager.as_operation_pass_manager(), PIPELINE).unwrap();
+
+        let other = PassManager::new(&context);
+
+        parse_pass_pipeline(
+            other.as_operation_pass_manager(),
+            &manager.as_operation_pass_manager().to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(other.as_operation_pass_manager().to_string(), PIPELINE);
+    }
+
+    #[test]
+    fn add_registered_pass_without_options() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_pass_definition::<CountPass>();
+        manager
+            .nested_under("func.func")
+            .add_pipeline("test-count,cse")
+            .unwrap();
+
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "any(func.func(test-count,cse))"
+        );
+    }
+
+    #[test]
+    fn fail_to_add_invalid_pipeline_atomically() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_pass_definition::<CountPass>();
+
+        assert_eq!(
+            manager
+                .nested_under("func.func")
+                .add_pipeline("test-count,cse,test-count{foo=1}"),
+            Err(Error::ParsePassOption(
+                "unknown option `foo` of pass `test-count`".into()
+            ))
+        );
+        assert!(matches!(
+            manager
+                .nested_under("func.func")
+                .add_pipeline("test-count,foo"),
+            Err(Error::ParsePassPipeline(_))
+        ));
+        assert_eq!(
+            manager.as_operation_pass_manager().to_string(),
+            "any(func.func())"
+        );
+        assert!(manager.statistics().is_empty());
+    }
+
+    #[test]
+    fn fail_to_parse_registered_pass_options() {
+        let context = create_test_context();
+        let manager = PassManager::new(&context);
+
+        register_pass_definition::<CountPass>();
+
+        assert_eq!(
+            parse_pass_pipeline(
+                manager.as_operation_pass_manager(),
+                "builtin.module(func.func(test-count{foo=1}))"
+            ),
+            Err(Error::ParsePassOption(
+                "unknown option `foo` of pass `test-count`".into()
+            ))
+        );
+    }
+}
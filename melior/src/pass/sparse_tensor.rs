@@ -1,20 +1,6 @@
 //! Sparse tensor passes.
 
-melior_macro::passes!(
-    "SparseTensor",
-    [
-        mlirCreateSparseTensorLowerForeachToSCF,
-        mlirCreateSparseTensorLowerSparseOpsToForeach,
-        mlirCreateSparseTensorPreSparsificationRewrite,
-        mlirCreateSparseTensorSparseBufferRewrite,
-        mlirCreateSparseTensorSparseGPUCodegen,
-        mlirCreateSparseTensorSparseReinterpretMap,
-        mlirCreateSparseTensorSparseTensorCodegen,
-        mlirCreateSparseTensorSparseTensorConversionPass,
-        mlirCreateSparseTensorSparseVectorization,
-        mlirCreateSparseTensorSparsificationAndBufferization,
-        mlirCreateSparseTensorSparsificationPass,
-        mlirCreateSparseTensorStageSparseOperations,
-        mlirCreateSparseTensorStorageSpecifierToLLVM,
-    ]
-);
+melior_macro::passes_from_tablegen! {
+    prefix: "SparseTensor",
+    files: ["mlir/Dialect/SparseTensor/Transforms/Passes.td"],
+}
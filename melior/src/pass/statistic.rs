@@ -0,0 +1,76 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// A statistic counter of a pass.
+///
+/// Statistics are shared between clones, so Rust passes can keep clones of
+/// statistics registered on pass managers and update them while running.
+///
+/// # Examples
+///
+/// ```
+/// use melior::pass::PassStatistic;
+///
+/// let statistic = PassStatistic::new("my-pass", "num-rewrites", "Number of rewrites");
+///
+/// statistic.add(2);
+/// statistic.increment();
+///
+/// assert_eq!(statistic.value(), 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PassStatistic {
+    pass: Arc<str>,
+    name: Arc<str>,
+    description: Arc<str>,
+    value: Arc<AtomicU64>,
+}
+
+impl PassStatistic {
+    /// Creates a statistic.
+    pub fn new(pass: &str, name: &str, description: &str) -> Self {
+        Self {
+            pass: pass.into(),
+            name: name.into(),
+            description: description.into(),
+            value: Default::default(),
+        }
+    }
+
+    /// Returns a pass argument.
+    pub fn pass(&self) -> &str {
+        &self.pass
+    }
+
+    /// Returns a name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a description.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns a value.
+    pub fn value(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Adds a value.
+    pub fn add(&self, value: u64) {
+        self.value.fetch_add(value, Ordering::Relaxed);
+    }
+
+    /// Increments a value.
+    pub fn increment(&self) {
+        self.add(1);
+    }
+
+    /// Resets a value.
+    pub fn reset(&self) {
+        self.value.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,308 @@
+use super::PassInstrumentation;
+use crate::ir::{block::BlockLike, operation::OperationLike, OperationRef};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// A timing of a pass or a pipeline.
+///
+/// Pipelines nested under operations are named like `'func.func' Pipeline`
+/// as in MLIR's timing reports. A root timing is named `Total`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassTiming {
+    name: String,
+    wall_time: Duration,
+    user_time: Duration,
+    children: Vec<PassTiming>,
+}
+
+impl PassTiming {
+    /// Returns a name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a wall time.
+    pub const fn wall_time(&self) -> Duration {
+        self.wall_time
+    }
+
+    /// Returns a user time, which is a sum of times spent in all threads.
+    pub const fn user_time(&self) -> Duration {
+        self.user_time
+    }
+
+    /// Returns timings of nested passes and pipelines.
+    pub fn children(&self) -> &[PassTiming] {
+        &self.children
+    }
+
+    /// Finds a timing of a child by name.
+    pub fn child(&self, name: &str) -> Option<&PassTiming> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+/// A timer of passes.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Timer {
+    state: Arc<Mutex<TimerState>>,
+}
+
+#[derive(Debug, Default)]
+struct TimerState {
+    starts: HashMap<(String, usize), Instant>,
+    records: Vec<TimingRecord>,
+    start: Option<Instant>,
+    total: Duration,
+}
+
+#[derive(Debug)]
+struct TimingRecord {
+    path: Vec<String>,
+    pass: String,
+    start: Instant,
+    end: Instant,
+}
+
+impl Timer {
+    /// Starts timing a run of a pass manager.
+    ///
+    /// Records of previous runs are discarded.
+    pub fn start(&self) {
+        let mut state = self.lock();
+
+        state.starts.clear();
+        state.records.clear();
+        state.start = Some(Instant::now());
+        state.total = Duration::ZERO;
+    }
+
+    /// Stops timing a run of a pass manager.
+    pub fn stop(&self) {
+        let mut state = self.lock();
+
+        if let Some(start) = state.start.take() {
+            state.total += start.elapsed();
+        }
+    }
+
+    /// Builds a timing report.
+    pub fn report(&self) -> PassTiming {
+        let state = self.lock();
+        let mut root = Node::default();
+
+        for record in &state.records {
+            let mut node = &mut root;
+
+            for name in &record.path {
+                node = node.child(&format!("'{name}' Pipeline"));
+                node.intervals.push((record.start, record.end));
+            }
+
+            node.child(&record.pass)
+                .intervals
+                .push((record.start, record.end));
+        }
+
+        let mut timing = root.into_timing("Total".into());
+
+        timing.wall_time = state.total;
+        timing.user_time = timing.children.iter().map(|child| child.user_time).sum();
+
+        timing
+    }
+
+    fn finish(&self, pass: &str, operation: OperationRef) {
+        let end = Instant::now();
+        let mut state = self.lock();
+
+        if let Some(start) = state
+            .starts
+            .remove(&(pass.to_owned(), operation.to_raw().ptr as usize))
+        {
+            state.records.push(TimingRecord {
+                path: operation_path(operation),
+                pass: pass.into(),
+                start,
+                end,
+            });
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TimerState> {
+        self.state.lock().expect("timer state is not poisoned")
+    }
+}
+
+impl<'c> PassInstrumentation<'c> for Timer {
+    fn run_before_pass(
+        &mut self,
+        pass: &str,
+        operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        self.lock().starts.insert(
+            (pass.into(), operation.to_raw().ptr as usize),
+            Instant::now(),
+        );
+
+        Ok(())
+    }
+
+    fn run_after_pass(
+        &mut self,
+        pass: &str,
+        operation: OperationRef<'c, '_>,
+    ) -> Result<(), String> {
+        self.finish(pass, operation);
+
+        Ok(())
+    }
+
+    fn run_after_pass_failed(&mut self, pass: &str, operation: OperationRef<'c, '_>) {
+        self.finish(pass, operation);
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    children: Vec<(String, Node)>,
+    intervals: Vec<(Instant, Instant)>,
+}
+
+impl Node {
+    fn child(&mut self, name: &str) -> &mut Node {
+        let index = if let Some(index) = self.children.iter().position(|(key, _)| key == name) {
+            index
+        } else {
+            self.children.push((name.into(), Node::default()));
+            self.children.len() - 1
+        };
+
+        &mut self.children[index].1
+    }
+
+    fn into_timing(mut self, name: String) -> PassTiming {
+        self.intervals.sort();
+
+        let mut wall_time = Duration::ZERO;
+        let mut last = None::<Instant>;
+
+        // Merge overlapping intervals of parallel executions.
+        for &(start, end) in &self.intervals {
+            let start = last.map_or(start, |last| start.max(last));
+
+            if end > start {
+                wall_time += end - start;
+                last = Some(end);
+            }
+        }
+
+        PassTiming {
+            name,
+            wall_time,
+            user_time: self
+                .intervals
+                .iter()
+                .map(|(start, end)| *end - *start)
+                .sum(),
+            children: self
+                .children
+                .into_iter()
+                .map(|(name, node)| node.into_timing(name))
+                .collect(),
+        }
+    }
+}
+
+fn operation_path(operation: OperationRef) -> Vec<String> {
+    let mut path = vec![];
+    let mut current = Some(operation);
+
+    while let Some(operation) = current {
+        let parent = operation.block().and_then(|block| block.parent_operation());
+
+        if parent.is_some() {
+            path.push(
+                operation
+                    .name()
+                    .as_string_ref()
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_owned(),
+            );
+        }
+
+        current = parent;
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ir::Module,
+        pass::{self, PassManager},
+        test::create_test_context,
+    };
+    use indoc::indoc;
+
+    #[test]
+    fn report_timing() {
+        let context = create_test_context();
+        let mut module = Module::parse(
+            &context,
+            indoc!(
+                "
+                func.func @foo(%arg0 : i32) -> i32 {
+                    %res = arith.addi %arg0, %arg0 : i32
+                    return %res : i32
+                }
+                "
+            ),
+        )
+        .unwrap();
+        let manager = PassManager::new(&context);
+
+        manager.enable_timing();
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager
+            .nested_under("func.func")
+            .add_pass(pass::transform::create_cse());
+        manager.run(&mut module).unwrap();
+
+        let timing = manager.timing_report().unwrap();
+
+        assert_eq!(timing.name(), "Total");
+        assert_eq!(
+            timing
+                .children()
+                .iter()
+                .map(|child| child.name())
+                .collect::<Vec<_>>(),
+            ["symbol-dce", "'func.func' Pipeline"]
+        );
+        assert_eq!(
+            timing
+                .child("'func.func' Pipeline")
+                .unwrap()
+                .children()
+                .iter()
+                .map(|child| child.name())
+                .collect::<Vec<_>>(),
+            ["cse"]
+        );
+        assert!(timing.wall_time() >= timing.child("symbol-dce").unwrap().wall_time());
+    }
+
+    #[test]
+    fn report_no_timing() {
+        let context = create_test_context();
+
+        assert_eq!(PassManager::new(&context).timing_report(), None);
+    }
+}
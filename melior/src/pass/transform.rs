@@ -1,28 +1,6 @@
 //! Transform passes.
 
-melior_macro::passes!(
-    "Transforms",
-    [
-        // spell-checker: disable-next-line
-        mlirCreateTransformsCSE,
-        mlirCreateTransformsCanonicalizer,
-        mlirCreateTransformsCompositeFixedPointPass,
-        mlirCreateTransformsControlFlowSink,
-        mlirCreateTransformsGenerateRuntimeVerification,
-        mlirCreateTransformsInliner,
-        mlirCreateTransformsLocationSnapshot,
-        mlirCreateTransformsLoopInvariantCodeMotion,
-        mlirCreateTransformsLoopInvariantSubsetHoisting,
-        mlirCreateTransformsMem2Reg,
-        mlirCreateTransformsPrintIRPass,
-        mlirCreateTransformsPrintOpStats,
-        mlirCreateTransformsRemoveDeadValues,
-        mlirCreateTransformsSCCP,
-        mlirCreateTransformsSROA,
-        mlirCreateTransformsStripDebugInfo,
-        mlirCreateTransformsSymbolDCE,
-        mlirCreateTransformsSymbolPrivatize,
-        mlirCreateTransformsTopologicalSort,
-        mlirCreateTransformsViewOpGraph,
-    ]
-);
+melior_macro::passes_from_tablegen! {
+    prefix: "Transforms",
+    files: ["mlir/Transforms/Passes.td"],
+}
@@ -0,0 +1,295 @@
+//! Preset pass pipelines.
+//!
+//! Presets lower modules of common dialect mixes into the `llvm` dialect
+//! ready for [`ExecutionEngine`](crate::ExecutionEngine). Functions called
+//! through [`ExecutionEngine::invoke_packed`](crate::ExecutionEngine::invoke_packed)
+//! need `llvm.emit_c_interface` attributes.
+//!
+//! Presets are built from textual pass arguments, so all passes must be
+//! registered with [`register_all_passes`](crate::utility::register_all_passes)
+//! before they are used.
+//!
+//! # Examples
+//!
+//! ```
+//! use melior::{
+//!     dialect::DialectRegistry,
+//!     ir::Module,
+//!     pipeline,
+//!     utility::{register_all_dialects, register_all_passes},
+//!     Context,
+//! };
+//!
+//! let registry = DialectRegistry::new();
+//! register_all_dialects(&registry);
+//!
+//! let context = Context::new();
+//! context.append_dialect_registry(&registry);
+//! context.load_all_available_dialects();
+//! register_all_passes();
+//!
+//! let mut module = Module::parse(
+//!     &context,
+//!     "func.func @foo(%x: i64) -> i64 { return %x : i64 }",
+//! )
+//! .unwrap();
+//!
+//! pipeline::scalar_to_llvm()
+//!     .create_pass_manager(&context)
+//!     .unwrap()
+//!     .run(&mut module)
+//!     .unwrap();
+//! ```
+
+use crate::pass::Pipeline;
+
+const MODULE_ANCHOR: &str = "builtin.module";
+const FUNCTION_ANCHOR: &str = "func.func";
+
+/// Creates a pipeline that lowers scalar code in the `arith`, `cf`, `scf`,
+/// `func`, `index` and `math` dialects into the `llvm` dialect.
+pub fn scalar_to_llvm() -> Pipeline {
+    lower_to_llvm(Pipeline::on(MODULE_ANCHOR), false, false)
+}
+
+/// Creates a pipeline that lowers memref code with scalar code into the
+/// `llvm` dialect.
+///
+/// It also lowers the `affine` dialect used in memref code.
+pub fn memref_to_llvm() -> Pipeline {
+    lower_to_llvm(
+        Pipeline::on(MODULE_ANCHOR).add_pass("lower-affine"),
+        true,
+        false,
+    )
+}
+
+/// Creates a pipeline that lowers vector code with memref and scalar code
+/// into the `llvm` dialect.
+pub fn vector_to_llvm() -> Pipeline {
+    lower_to_llvm(
+        Pipeline::on(MODULE_ANCHOR)
+            .nest(FUNCTION_ANCHOR, |pipeline| {
+                pipeline.add_pass("convert-vector-to-scf")
+            })
+            .add_pass("lower-affine"),
+        true,
+        true,
+    )
+}
+
+/// Creates a pipeline that bufferizes tensor and linalg code and lowers it
+/// with vector, memref and scalar code into the `llvm` dialect.
+///
+/// Functions take and return memrefs with identity layouts in place of
+/// tensors. Buffers allocated in functions are deallocated automatically.
+pub fn tensor_to_llvm() -> Pipeline {
+    lower_to_llvm(
+        Pipeline::on(MODULE_ANCHOR)
+            .add_pass("empty-tensor-to-alloc-tensor")
+            .add_pass_with_options(
+                "one-shot-bufferize",
+                "bufferize-function-boundaries \
+                function-boundary-type-conversion=identity-layout-map",
+            )
+            .add_pass("buffer-deallocation-pipeline")
+            .add_pass("convert-bufferization-to-memref")
+            .nest(FUNCTION_ANCHOR, |pipeline| {
+                pipeline
+                    .add_pass("convert-linalg-to-loops")
+                    .add_pass("convert-vector-to-scf")
+            })
+            .add_pass("lower-affine"),
+        true,
+        true,
+    )
+}
+
+fn lower_to_llvm(pipeline: Pipeline, memref: bool, vector: bool) -> Pipeline {
+    let mut pipeline = pipeline
+        .add_pass("convert-scf-to-cf")
+        .add_pass("canonicalize")
+        .add_pass("cse");
+
+    if vector {
+        pipeline = pipeline.add_pass("convert-vector-to-llvm");
+    }
+
+    pipeline = pipeline.nest(FUNCTION_ANCHOR, |pipeline| {
+        pipeline.add_pass("convert-math-to-llvm")
+    });
+
+    if memref {
+        pipeline = pipeline
+            .add_pass("expand-strided-metadata")
+            // The expansion may create affine operations.
+            .add_pass("lower-affine")
+            .add_pass("finalize-memref-to-llvm");
+    }
+
+    pipeline
+        .add_pass("convert-func-to-llvm")
+        .add_pass("convert-arith-to-llvm")
+        .add_pass("convert-cf-to-llvm")
+        .add_pass("convert-index-to-llvm")
+        .add_pass("reconcile-unrealized-casts")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        execution_engine::NativeType,
+        ir::{operation::OperationLike, Module},
+        test::create_test_context,
+        utility::register_all_passes,
+        Context, ExecutionEngine,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn lower<'c>(context: &'c Context, pipeline: Pipeline, source: &str) -> Module<'c> {
+        register_all_passes();
+
+        let mut module = Module::parse(context, source).unwrap();
+
+        pipeline
+            .create_pass_manager(context)
+            .unwrap()
+            .run(&mut module)
+            .unwrap();
+
+        assert!(module.as_operation().verify());
+
+        module
+    }
+
+    fn invoke<T: NativeType, R: NativeType>(module: &Module, name: &str, argument: T) -> R {
+        ExecutionEngine::new(module, 2, &[], false)
+            .unwrap()
+            .invoke::<(T,), R>(name, (argument,))
+            .unwrap()
+    }
+
+    #[test]
+    fn render_presets() {
+        for pipeline in [
+            scalar_to_llvm(),
+            memref_to_llvm(),
+            vector_to_llvm(),
+            tensor_to_llvm(),
+        ] {
+            assert_eq!(Pipeline::parse(&pipeline.to_string()).unwrap(), pipeline);
+        }
+    }
+
+    #[test]
+    fn lower_scalar_code() {
+        let context = create_test_context();
+        let module = lower(
+            &context,
+            scalar_to_llvm(),
+            r#"
+            func.func @factorial(%n : i64) -> i64 attributes { llvm.emit_c_interface } {
+                %c0 = arith.constant 0 : index
+                %c1 = arith.constant 1 : index
+                %one = arith.constant 1 : i64
+                %upper = arith.index_cast %n : i64 to index
+                %result = scf.for %i = %c0 to %upper step %c1 iter_args(%acc = %one) -> (i64) {
+                    %j = arith.addi %i, %c1 : index
+                    %k = arith.index_cast %j : index to i64
+                    %next = arith.muli %acc, %k : i64
+                    scf.yield %next : i64
+                }
+                %large = arith.cmpi sgt, %n, %one : i64
+                cf.cond_br %large, ^result, ^one
+            ^result:
+                return %result : i64
+            ^one:
+                return %one : i64
+            }
+            "#,
+        );
+
+        assert_eq!(invoke::<i64, i64>(&module, "factorial", 5), 120);
+    }
+
+    #[test]
+    fn lower_memref_code() {
+        let context = create_test_context();
+        let module = lower(
+            &context,
+            memref_to_llvm(),
+            r#"
+            func.func @sum(%n : i64) -> i64 attributes { llvm.emit_c_interface } {
+                %c0 = arith.constant 0 : index
+                %c1 = arith.constant 1 : index
+                %zero = arith.constant 0 : i64
+                %memref = memref.alloca() : memref<4xi64>
+                affine.for %i = 0 to 4 {
+                    %value = arith.index_cast %i : index to i64
+                    %scaled = arith.muli %value, %n : i64
+                    memref.store %scaled, %memref[%i] : memref<4xi64>
+                }
+                %view = memref.subview %memref[1] [3] [1]
+                    : memref<4xi64> to memref<3xi64, strided<[1], offset: 1>>
+                %c3 = arith.constant 3 : index
+                %result = scf.for %i = %c0 to %c3 step %c1 iter_args(%acc = %zero) -> (i64) {
+                    %value = memref.load %view[%i] : memref<3xi64, strided<[1], offset: 1>>
+                    %next = arith.addi %acc, %value : i64
+                    scf.yield %next : i64
+                }
+                return %result : i64
+            }
+            "#,
+        );
+
+        assert_eq!(invoke::<i64, i64>(&module, "sum", 2), 12);
+    }
+
+    #[test]
+    fn lower_vector_code() {
+        let context = create_test_context();
+        let module = lower(
+            &context,
+            vector_to_llvm(),
+            r#"
+            func.func @sum(%x : i32) -> i32 attributes { llvm.emit_c_interface } {
+                %vector = vector.broadcast %x : i32 to vector<4xi32>
+                %offsets = arith.constant dense<[0, 1, 2, 3]> : vector<4xi32>
+                %sum = arith.addi %vector, %offsets : vector<4xi32>
+                %result = vector.reduction <add>, %sum : vector<4xi32> into i32
+                return %result : i32
+            }
+            "#,
+        );
+
+        assert_eq!(invoke::<i32, i32>(&module, "sum", 1), 10);
+    }
+
+    #[test]
+    fn lower_tensor_code() {
+        let context = create_test_context();
+        let module = lower(
+            &context,
+            tensor_to_llvm(),
+            r#"
+            func.func @sum(%x : f32) -> f32 attributes { llvm.emit_c_interface } {
+                %zero = arith.constant 0.0 : f32
+                %empty = tensor.empty() : tensor<4xf32>
+                %filled = linalg.fill ins(%x : f32) outs(%empty : tensor<4xf32>) -> tensor<4xf32>
+                %init = tensor.empty() : tensor<f32>
+                %accumulator = linalg.fill ins(%zero : f32) outs(%init : tensor<f32>) -> tensor<f32>
+                %reduced = linalg.reduce ins(%filled : tensor<4xf32>) outs(%accumulator : tensor<f32>) dimensions = [0]
+                    (%value : f32, %acc : f32) {
+                        %sum = arith.addf %value, %acc : f32
+                        linalg.yield %sum : f32
+                    }
+                %result = tensor.extract %reduced[] : tensor<f32>
+                return %result : f32
+            }
+            "#,
+        );
+
+        assert_eq!(invoke::<f32, f32>(&module, "sum", 1.5), 6.0);
+    }
+}
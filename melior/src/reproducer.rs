@@ -0,0 +1,221 @@
+//! Crash reproducers.
+//!
+//! A crash reproducer contains a module and a pass pipeline in the format of
+//! MLIR's reproducers, which `mlir-opt --run-reproducer` also accepts.
+//!
+//! Reproducers are written only when pass pipelines fail. Crashes of
+//! processes are not captured.
+
+use crate::{
+    dialect::DialectRegistry,
+    ir::{operation::OperationLike, Module},
+    pass::PassManager,
+    utility::{parse_pass_pipeline, register_all_dialects, register_all_passes},
+    Context, Error,
+};
+use std::{fs, path::Path};
+
+const METADATA_START: &str = "{-#";
+const PIPELINE_KEY: &str = "pipeline:";
+
+/// Reruns a pass pipeline on a module in a crash reproducer and returns the
+/// module printed after the pipeline.
+///
+/// A reproducer runs in a new context with all built-in dialects and passes
+/// registered, as `mlir-opt` does.
+pub fn run(path: impl AsRef<Path>) -> Result<String, Error> {
+    let source = fs::read_to_string(path).map_err(|error| Error::Reproducer(error.to_string()))?;
+    let (source, pipeline) = parse(&source)?;
+    let context = Context::new();
+    let registry = DialectRegistry::new();
+
+    register_all_dialects(&registry);
+    register_all_passes();
+    context.append_dialect_registry(&registry);
+
+    let mut module = Module::parse(&context, source)
+        .ok_or_else(|| Error::Reproducer("failed to parse module".into()))?;
+    let manager = PassManager::new(&context);
+
+    parse_pass_pipeline(manager.as_operation_pass_manager(), &pipeline)?;
+    manager.run(&mut module)?;
+
+    Ok(module.as_operation().to_string())
+}
+
+/// Writes a crash reproducer.
+pub(crate) fn write(path: &Path, source: &str, pipeline: &str) -> Result<(), Error> {
+    fs::write(
+        path,
+        format!(
+            "{source}\n\n{METADATA_START}\n  external_resources: {{\n    mlir_reproducer: {{\n      \
+            {PIPELINE_KEY} \"{}\"\n    }}\n  }}\n#-}}\n",
+            pipeline.replace('\\', "\\\\").replace('"', "\\\"")
+        ),
+    )
+    .map_err(|error| Error::Reproducer(error.to_string()))
+}
+
+fn parse(source: &str) -> Result<(&str, String), Error> {
+    let index = source
+        .rfind(METADATA_START)
+        .ok_or_else(|| Error::Reproducer("reproducer metadata not found".into()))?;
+    let (source, metadata) = source.split_at(index);
+    let metadata = metadata
+        .find(PIPELINE_KEY)
+        .map(|index| metadata[index + PIPELINE_KEY.len()..].trim_start())
+        .and_then(|metadata| metadata.strip_prefix('"'))
+        .ok_or_else(|| Error::Reproducer("pass pipeline not found".into()))?;
+    let mut pipeline = String::new();
+    let mut characters = metadata.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '"' => return Ok((source, pipeline)),
+            '\\' => pipeline.extend(characters.next()),
+            _ => pipeline.push(character),
+        }
+    }
+
+    Err(Error::Reproducer("unterminated pass pipeline".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::{r#type::TypeId, OperationRef},
+        pass::{self, create_external, ExternalPass},
+        test::{create_temp_path, create_test_context},
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[repr(align(8))]
+    struct PassId;
+
+    static FAILING_PASS: PassId = PassId;
+
+    fn create_failing_pass() -> pass::Pass {
+        create_external(
+            |_: OperationRef, pass: ExternalPass| pass.signal_failure(),
+            TypeId::create(&FAILING_PASS),
+            "fail",
+            "fail",
+            "Fails always.",
+            "",
+            &[],
+        )
+    }
+
+    const MODULE: &str = indoc!(
+        "
+        func.func @foo(%arg0 : i32) -> i32 {
+            return %arg0 : i32
+        }
+        "
+    );
+
+    #[test]
+    fn parse_reproducer() {
+        let path = create_temp_path("parse_reproducer.mlir");
+
+        write(&path, "module {}", "builtin.module(canonicalize{a=\"b\"})").unwrap();
+
+        let source = fs::read_to_string(&path).unwrap();
+
+        assert_eq!(
+            parse(&source).unwrap().1,
+            "builtin.module(canonicalize{a=\"b\"})"
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn generate_reproducer() {
+        let context = create_test_context();
+        let path = create_temp_path("generate_reproducer.mlir");
+        let mut module = Module::parse(&context, MODULE).unwrap();
+        let manager = PassManager::new(&context);
+
+        manager.enable_crash_reproducer(&path, false);
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager
+            .nested_under("func.func")
+            .add_pass(create_failing_pass());
+
+        assert_eq!(manager.run(&mut module), Err(Error::RunPass));
+
+        let source = fs::read_to_string(&path).unwrap();
+        let (ir, pipeline) = parse(&source).unwrap();
+
+        assert!(ir.contains("func.func @foo"));
+        assert_eq!(pipeline, "any(symbol-dce,func.func(fail))");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn generate_local_reproducer() {
+        let context = create_test_context();
+        let path = create_temp_path("generate_local_reproducer.mlir");
+        let mut module = Module::parse(&context, MODULE).unwrap();
+        let manager = PassManager::new(&context);
+
+        manager.enable_crash_reproducer(&path, true);
+        manager.add_pass(pass::transform::create_symbol_dce());
+        manager
+            .nested_under("func.func")
+            .add_pass(create_failing_pass());
+
+        assert_eq!(manager.run(&mut module), Err(Error::RunPass));
+
+        let source = fs::read_to_string(&path).unwrap();
+        let (ir, pipeline) = parse(&source).unwrap();
+
+        assert!(ir.contains("func.func @foo"));
+        assert!(!ir.contains("module {"));
+        assert_eq!(pipeline, "builtin.module(func.func(fail))");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn run_reproducer() {
+        let path = create_temp_path("run_reproducer.mlir");
+
+        write(&path, MODULE, "builtin.module(symbol-privatize)").unwrap();
+
+        assert!(run(&path).unwrap().contains("func.func private @foo"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn fail_to_write_reproducer() {
+        let context = create_test_context();
+        let path = create_temp_path("fail_to_write_reproducer").join("reproducer.mlir");
+        let mut module = Module::parse(&context, MODULE).unwrap();
+        let manager = PassManager::new(&context);
+        let diagnostics = Rc::new(RefCell::new(vec![]));
+
+        context.attach_diagnostic_handler({
+            let diagnostics = diagnostics.clone();
+
+            move |diagnostic| {
+                diagnostics.borrow_mut().push(diagnostic.to_string());
+                true
+            }
+        });
+        manager.enable_crash_reproducer(&path, false);
+        manager.add_pass(create_failing_pass());
+
+        assert_eq!(manager.run(&mut module), Err(Error::RunPass));
+        assert!(diagnostics
+            .borrow()
+            .iter()
+            .any(|diagnostic| diagnostic.contains("failed to write crash reproducer")));
+    }
+}
@@ -47,6 +47,15 @@ impl<'a> StringRef<'a> {
         }
     }
 
+    /// Converts a string reference into bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        if self.raw.length == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.raw.data as *const u8, self.raw.length) }
+        }
+    }
+
     /// Converts a string reference into a raw object.
     pub const fn to_raw(self) -> MlirStringRef {
         self.raw
@@ -87,6 +96,12 @@ mod tests {
         assert_eq!(StringRef::new("foo").as_str().unwrap(), "foo");
     }
 
+    #[test]
+    fn as_bytes() {
+        assert_eq!(StringRef::new("foo").as_bytes(), b"foo");
+        assert_eq!(StringRef::new("").as_bytes(), b"");
+    }
+
     #[test]
     fn not_equal() {
         assert_ne!(StringRef::new("foo"), StringRef::new("bar"));
@@ -1,8 +1,18 @@
 use crate::{
     dialect::DialectRegistry,
+    ir::{
+        operation::{OperationLike, WalkOrder, WalkResult},
+        Module, OperationRef,
+    },
     utility::{register_all_dialects, register_all_llvm_translations},
     Context,
 };
+use std::{
+    env::temp_dir,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 pub fn load_all_dialects(context: &Context) {
     let registry = DialectRegistry::new();
@@ -24,3 +34,30 @@ pub fn create_test_context() -> Context {
 
     context
 }
+
+pub fn find_operation<'c, 'a>(module: &'a Module<'c>, name: &str) -> OperationRef<'c, 'a> {
+    let mut raw = None;
+
+    module
+        .as_operation()
+        .walk(WalkOrder::PreOrder, |operation| {
+            if operation.name().as_string_ref().as_str() == Ok(name) {
+                raw = Some(operation.to_raw());
+                WalkResult::Interrupt
+            } else {
+                WalkResult::Advance
+            }
+        });
+
+    unsafe { OperationRef::from_raw(raw.expect("operation")) }
+}
+
+pub fn create_temp_path(name: &str) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    temp_dir().join(format!(
+        "melior_{}_{}_{name}",
+        process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
@@ -1,8 +1,13 @@
 //! Utility functions.
 
 use crate::{
-    context::Context, dialect::DialectRegistry, ir::Module, logical_result::LogicalResult, pass,
-    string_ref::StringRef, Error,
+    context::Context,
+    dialect::DialectRegistry,
+    ir::Module,
+    logical_result::LogicalResult,
+    pass::{self, split_nested_pipeline},
+    string_ref::StringRef,
+    Error,
 };
 use mlir_sys::{
     mlirLoadIRDLDialects, mlirParsePassPipeline, mlirRegisterAllDialects,
@@ -67,7 +72,50 @@ pub fn register_all_passes() {
 }
 
 /// Parses a pass pipeline.
+///
+/// Passes in a pipeline replace ones in a pass manager. They are added with
+/// [`OperationPassManager::add_pipeline`](pass::OperationPassManager::add_pipeline)
+/// after the pass manager is anchored on the anchor of the pipeline, so that
+/// they are instrumented. A whole pipeline is validated before the pass
+/// manager is modified.
+///
+/// Rust passes registered with
+/// [`register_external_pass`](crate::pass::register_external_pass) are also
+/// available.
 pub fn parse_pass_pipeline(manager: pass::OperationPassManager, source: &str) -> Result<(), Error> {
+    let Some((name, pipeline)) = split_nested_pipeline(source.trim()) else {
+        // MLIR reports an invalid pipeline or prints a valid one in a form with
+        // an anchor.
+        let context = Context::new();
+        let scratch = pass::PassManager::new(&context);
+
+        parse_raw_pass_pipeline(scratch.as_operation_pass_manager(), source)?;
+
+        return parse_pass_pipeline(manager, &scratch.as_operation_pass_manager().to_string());
+    };
+    let anchor = format!("{name}()");
+
+    {
+        let context = Context::new();
+        let scratch = pass::PassManager::new(&context);
+
+        parse_raw_pass_pipeline(scratch.as_operation_pass_manager(), &anchor)?;
+        add_pipeline(scratch.as_operation_pass_manager(), pipeline)?;
+    }
+
+    parse_raw_pass_pipeline(manager, &anchor)?;
+    add_pipeline(manager, pipeline)
+}
+
+fn add_pipeline(manager: pass::OperationPassManager, pipeline: &str) -> Result<(), Error> {
+    if pipeline.trim().is_empty() {
+        Ok(())
+    } else {
+        manager.add_pipeline(pipeline)
+    }
+}
+
+fn parse_raw_pass_pipeline(manager: pass::OperationPassManager, source: &str) -> Result<(), Error> {
     let mut error_message = None;
 
     let result = LogicalResult::from_raw(unsafe {
@@ -94,7 +142,7 @@ pub fn load_irdl_dialects(module: &Module) -> bool {
     unsafe { mlirLoadIRDLDialects(module.to_raw()).value == 1 }
 }
 
-unsafe extern "C" fn handle_parse_error(raw_string: MlirStringRef, data: *mut c_void) {
+pub(crate) unsafe extern "C" fn handle_parse_error(raw_string: MlirStringRef, data: *mut c_void) {
     let string = StringRef::from_raw(raw_string);
     let data = &mut *(data as *mut Option<String>);
 